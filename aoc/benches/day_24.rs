@@ -0,0 +1,14 @@
+use std::env;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_24::Day24;
+use solver::Part;
+
+fn bench(c: &mut Criterion) {
+    // day-24 is not a workspace member, so it is benchmarked from aoc with the mkl feature
+    env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../day-24")).unwrap();
+    bench::bench_day(c, &Day24, &Part::ALL);
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
@@ -0,0 +1,14 @@
+use std::env;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_25::Day25;
+use solver::Part;
+
+fn bench(c: &mut Criterion) {
+    // day-25 is not a workspace member, so it is benchmarked from aoc with the rustworkx feature
+    env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../day-25")).unwrap();
+    bench::bench_day(c, &Day25, &[Part::One]);
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
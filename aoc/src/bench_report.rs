@@ -0,0 +1,219 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Benchmarks of each day, see the `bench` crate.
+pub const BENCHMARKS: [&str; 4] = ["parse-1", "part-1", "parse-2", "part-2"];
+
+/// Mean benchmark times in nanoseconds, keyed by `day-NN` and benchmark name.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Timings(BTreeMap<String, BTreeMap<String, f64>>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub day: u8,
+    pub benchmark: &'static str,
+    pub baseline: f64,
+    pub current: f64,
+}
+
+impl Regression {
+    /// Slowdown in percent.
+    pub fn change(&self) -> f64 {
+        change(self.baseline, self.current)
+    }
+}
+
+impl Timings {
+    /// Reads the latest results `cargo bench` left in the criterion output directories, missing
+    /// directories and benchmarks are skipped.
+    pub fn from_criterion(dirs: &[PathBuf]) -> Result<Self, String> {
+        let mut timings = Timings::default();
+        for dir in dirs {
+            for day in 1..=25 {
+                for benchmark in BENCHMARKS {
+                    let path = dir
+                        .join(day_key(day))
+                        .join(benchmark)
+                        .join("new")
+                        .join("estimates.json");
+                    if let Some(mean) = read_mean(&path)? {
+                        timings.insert(day, benchmark, mean);
+                    }
+                }
+            }
+        }
+        Ok(timings)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+        toml::from_str(&text).map_err(|error| format!("{}: {}", path.display(), error.message()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = toml::to_string(self).map_err(|error| error.to_string())?;
+        fs::write(
+            path,
+            format!(
+                "# Mean benchmark times in nanoseconds, written by `aoc bench-report --save-baseline`.\n\n{}",
+                text
+            ),
+        )
+        .map_err(|error| format!("Failed to write {}: {}", path.display(), error))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, day: u8, benchmark: &str) -> Option<f64> {
+        self.0.get(&day_key(day))?.get(benchmark).copied()
+    }
+
+    fn insert(&mut self, day: u8, benchmark: &str, nanoseconds: f64) {
+        self.0
+            .entry(day_key(day))
+            .or_default()
+            .insert(benchmark.to_string(), nanoseconds);
+    }
+}
+
+fn day_key(day: u8) -> String {
+    format!("day-{:02}", day)
+}
+
+fn read_mean(path: &Path) -> Result<Option<f64>, String> {
+    #[derive(Deserialize)]
+    struct Estimates {
+        mean: Estimate,
+    }
+
+    #[derive(Deserialize)]
+    struct Estimate {
+        point_estimate: f64,
+    }
+
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+    let estimates: Estimates = serde_json::from_str(&text)
+        .map_err(|error| format!("Failed to parse {}: {}", path.display(), error))?;
+    Ok(Some(estimates.mean.point_estimate))
+}
+
+fn change(baseline: f64, current: f64) -> f64 {
+    (current / baseline - 1.0) * 100.0
+}
+
+/// Table of all 25 days with the current times and their change against `baseline`. Benchmarks
+/// that got slower by more than `threshold` percent are marked with `!` and returned.
+pub fn summarize(
+    current: &Timings,
+    baseline: &Timings,
+    threshold: f64,
+) -> (String, Vec<Regression>) {
+    let mut regressions = vec![];
+    let mut header = format!("{:<6}", "day");
+    for benchmark in BENCHMARKS {
+        write!(header, " | {:<20}", benchmark).unwrap();
+    }
+    let mut table = format!("{}\n", header.trim_end());
+    for day in 1..=25 {
+        let mut row = format!("{:<6}", day_key(day));
+        for benchmark in BENCHMARKS {
+            let cell = match (current.get(day, benchmark), baseline.get(day, benchmark)) {
+                (None, _) => "-".to_string(),
+                (Some(current), None) => format_time(current),
+                (Some(current), Some(baseline)) => {
+                    let change = change(baseline, current);
+                    let regressed = change > threshold;
+                    if regressed {
+                        regressions.push(Regression {
+                            day,
+                            benchmark,
+                            baseline,
+                            current,
+                        });
+                    }
+                    format!(
+                        "{} {:+.1}%{}",
+                        format_time(current),
+                        change,
+                        if regressed { " !" } else { "" }
+                    )
+                }
+            };
+            write!(row, " | {:<20}", cell).unwrap();
+        }
+        table.push_str(row.trim_end());
+        table.push('\n');
+    }
+    (table, regressions)
+}
+
+fn format_time(nanoseconds: f64) -> String {
+    if nanoseconds < 1e3 {
+        format!("{:.0} ns", nanoseconds)
+    } else if nanoseconds < 1e6 {
+        format!("{:.1} µs", nanoseconds / 1e3)
+    } else if nanoseconds < 1e9 {
+        format!("{:.1} ms", nanoseconds / 1e6)
+    } else {
+        format!("{:.2} s", nanoseconds / 1e9)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarize_flags_regressions() {
+        let mut baseline = Timings::default();
+        baseline.insert(1, "parse-1", 100.0);
+        baseline.insert(1, "part-1", 2_000_000.0);
+        let mut current = Timings::default();
+        current.insert(1, "parse-1", 105.0);
+        current.insert(1, "part-1", 3_000_000.0);
+        current.insert(2, "part-2", 1_500.0);
+
+        let (table, regressions) = summarize(&current, &baseline, 10.0);
+        assert_eq!(
+            regressions,
+            vec![Regression {
+                day: 1,
+                benchmark: "part-1",
+                baseline: 2_000_000.0,
+                current: 3_000_000.0,
+            }]
+        );
+        assert_eq!(regressions[0].change(), 50.0);
+        let rows = table
+            .lines()
+            .map(|line| line.split('|').map(str::trim).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(rows.len(), 26);
+        assert_eq!(
+            rows[1],
+            ["day-01", "105 ns +5.0%", "3.0 ms +50.0% !", "-", "-"]
+        );
+        assert_eq!(rows[2], ["day-02", "-", "-", "-", "1.5 µs"]);
+    }
+
+    #[test]
+    fn timings_round_trip_through_toml() {
+        let mut timings = Timings::default();
+        timings.insert(3, "part-2", 1234.5);
+        let text = toml::to_string(&timings).unwrap();
+        assert_eq!(toml::from_str::<Timings>(&text).unwrap(), timings);
+    }
+}
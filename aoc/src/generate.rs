@@ -0,0 +1,121 @@
+//! Generators of puzzle inputs of arbitrary size, used to benchmark and fuzz the solutions with
+//! inputs far larger than the real ones. Every generated input is solvable, and the answers are
+//! included when the construction determines them.
+
+use std::fmt::Write;
+
+use rand::SeedableRng;
+use rand_pcg::Pcg64;
+use solver::Part;
+
+mod day_10;
+mod day_17;
+mod day_20;
+mod day_23;
+mod day_25;
+
+/// Random number generator of the generators, seeded so that an input can be generated again.
+pub type Random = Pcg64;
+
+/// Generates an input of the given size, or explains why the size is not supported.
+pub type Generate = fn(&mut Random, usize) -> Result<Generated, String>;
+
+/// A generated puzzle input with the answers known from its construction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generated {
+    pub input: String,
+    pub answers: Vec<(Part, String)>,
+}
+
+impl Generated {
+    /// The answers as a `[day-NN]` table of the answers file read by `aoc verify`.
+    pub fn answers_toml(&self, day: u8) -> String {
+        let mut toml = format!("[day-{:02}]\n", day);
+        for (part, answer) in &self.answers {
+            writeln!(toml, "part-{} = \"{}\"", part, answer).unwrap();
+        }
+        toml
+    }
+}
+
+/// The days with a generator, with what their size means.
+static GENERATORS: [(u8, &str, Generate); 5] = [
+    (10, "half the side of the map", day_10::generate),
+    (17, "width and height of the map", day_17::generate),
+    (20, "number of binary counters", day_20::generate),
+    (23, "junctions per side", day_23::generate),
+    (25, "number of components", day_25::generate),
+];
+
+pub fn generate(day: u8, size: usize, seed: u64) -> Result<Generated, String> {
+    let (_, _, generate) = GENERATORS
+        .iter()
+        .find(|(generator_day, _, _)| *generator_day == day)
+        .ok_or_else(|| format!("No generator for day {}, {}", day, supported()))?;
+    generate(&mut Random::seed_from_u64(seed), size)
+}
+
+/// Lists the days with a generator and what `--size` means for them.
+pub fn supported() -> String {
+    let days = GENERATORS
+        .iter()
+        .map(|(day, size, _)| format!("{} (size: {})", day, size))
+        .collect::<Vec<_>>();
+    format!("generators exist for days {}", days.join(", "))
+}
+
+/// Checks that `size` is at least `min`.
+fn at_least(size: usize, min: usize) -> Result<(), String> {
+    if size < min {
+        return Err(format!("The size has to be at least {}", min));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solvers;
+
+    /// Solves the generated input and compares the answers with the ones known from the
+    /// construction.
+    pub fn assert_solves(day: u8, generated: &Generated) {
+        let solver = solvers::solver(day).unwrap();
+        for (part, answer) in &generated.answers {
+            assert_eq!(
+                solver.solve(*part, &generated.input).as_ref(),
+                Ok(answer),
+                "part {} of\n{}",
+                part,
+                generated.input
+            );
+        }
+    }
+
+    #[test]
+    fn same_seed_same_input() {
+        assert_eq!(generate(10, 20, 7), generate(10, 20, 7));
+        assert_ne!(generate(10, 20, 7), generate(10, 20, 8));
+    }
+
+    #[test]
+    fn unsupported_days_and_sizes_are_errors() {
+        assert!(generate(1, 10, 0).unwrap_err().starts_with("No generator"));
+        assert_eq!(
+            generate(17, 3, 0),
+            Err("The size has to be at least 12".to_string())
+        );
+    }
+
+    #[test]
+    fn answers_toml_works() {
+        let generated = Generated {
+            input: String::new(),
+            answers: vec![(Part::One, "4".to_string()), (Part::Two, "1".to_string())],
+        };
+        assert_eq!(
+            generated.answers_toml(10),
+            "[day-10]\npart-1 = \"4\"\npart-2 = \"1\"\n"
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use grid::{Direction, Grid, Position};
+use rand::{seq::IndexedRandom, RngExt};
+use solver::Part;
+
+use super::{at_least, Generated, Random};
+
+/// Tiles around the loop, ground is more likely than any single pipe like in the real inputs.
+const FILLER: [char; 9] = ['|', '-', 'L', 'J', '7', 'F', '.', '.', '.'];
+
+/// Grows a random region of `size` x `size` cells without holes and lays the loop along its
+/// border on a map with every cell doubled, so that the tiles of the loop are the corners and
+/// edge midpoints of the region's cells.
+///
+/// The loop has as many tiles as the region has corners and edges on its border, and by Pick's
+/// theorem it encloses `4 * cells - tiles / 2 + 1` tiles.
+pub fn generate(rng: &mut Random, size: usize) -> Result<Generated, String> {
+    at_least(size, 1)?;
+    let region = grow_region(rng, size);
+    let side = 2 * size + 1;
+    let mut map = Grid::filled(side, side, ' ');
+    let mut loop_tiles = vec![];
+    for (cell, _) in region.iter().filter(|(_, inside)| **inside) {
+        let center = Position::new(2 * cell.x + 1, 2 * cell.y + 1);
+        for direction in Direction::ALL {
+            if region.get(cell.move_in_direction(direction)) == Some(&true) {
+                continue;
+            }
+            let midpoint = center.move_in_direction(direction);
+            let corners = [direction.turn_left(), direction.turn_right()]
+                .map(|side| midpoint.move_in_direction(side));
+            let edge = if direction.is_vertical() { '-' } else { '|' };
+            map[midpoint] = edge;
+            loop_tiles.push(midpoint);
+            for corner in corners {
+                if map[corner] == ' ' {
+                    map[corner] = '+';
+                    loop_tiles.push(corner);
+                }
+            }
+        }
+    }
+    for tile in &loop_tiles {
+        if map[*tile] == '+' {
+            map[*tile] = corner_pipe(&map, *tile);
+        }
+    }
+    let cells = region.values().filter(|inside| **inside).count();
+    let enclosed = 4 * cells - loop_tiles.len() / 2 + 1;
+
+    let start = *loop_tiles.choose(rng).unwrap();
+    let mut map = map.map(|tile| match tile {
+        ' ' => *FILLER.choose(rng).unwrap(),
+        _ => *tile,
+    });
+    map[start] = 'S';
+    // only the two pipes of the loop may connect to the start
+    for direction in Direction::ALL {
+        let neighbor = start.move_in_direction(direction);
+        if map.get(neighbor).is_some_and(|tile| {
+            !loop_tiles.contains(&neighbor) && connects(*tile, direction.opposite())
+        }) {
+            map[neighbor] = '.';
+        }
+    }
+    Ok(Generated {
+        input: format!("{}\n", map),
+        answers: vec![
+            (Part::One, (loop_tiles.len() / 2).to_string()),
+            (Part::Two, enclosed.to_string()),
+        ],
+    })
+}
+
+/// Adds random cells to a region starting with one cell, skipping the cells that would enclose
+/// a hole or touch the region only diagonally, until half of the cells are taken.
+fn grow_region(rng: &mut Random, size: usize) -> Grid<bool> {
+    let mut region = Grid::filled(size, size, false);
+    let first = Position::new(
+        rng.random_range(0..size as i32),
+        rng.random_range(0..size as i32),
+    );
+    region[first] = true;
+    let mut cells = 1;
+    let mut candidates = first.neighbors().to_vec();
+    while cells < size * size / 2 && !candidates.is_empty() {
+        let candidate = candidates.swap_remove(rng.random_range(0..candidates.len()));
+        if region.get(candidate) != Some(&false) || !keeps_region_simple(&region, candidate) {
+            continue;
+        }
+        region[candidate] = true;
+        cells += 1;
+        candidates.extend(candidate.neighbors());
+    }
+    region
+}
+
+/// Whether adding `cell` leaves the region connected without holes and without cells that only
+/// touch at a corner, which would make the loop cross itself.
+fn keeps_region_simple(region: &Grid<bool>, cell: Position) -> bool {
+    let inside =
+        |dx: i32, dy: i32| region.get(Position::new(cell.x + dx, cell.y + dy)) == Some(&true);
+    // the 8 surrounding cells, clockwise
+    let ring = [
+        (0, -1),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+        (0, 1),
+        (-1, 1),
+        (-1, 0),
+        (-1, -1),
+    ]
+    .map(|(dx, dy)| inside(dx, dy));
+    let corner_only = [(1, 1), (1, -1), (-1, 1), (-1, -1)]
+        .iter()
+        .any(|(dx, dy)| inside(*dx, *dy) && !inside(*dx, 0) && !inside(0, *dy));
+    // the surrounding cells of the region have to form a single run around the cell
+    let runs = (0..8).filter(|i| ring[*i] && !ring[(i + 7) % 8]).count();
+    !corner_only && runs == 1
+}
+
+fn connects(tile: char, direction: Direction) -> bool {
+    let directions: &[Direction] = match tile {
+        '|' => &[Direction::North, Direction::South],
+        '-' => &[Direction::East, Direction::West],
+        'L' => &[Direction::North, Direction::East],
+        'J' => &[Direction::North, Direction::West],
+        '7' => &[Direction::South, Direction::West],
+        'F' => &[Direction::South, Direction::East],
+        _ => &[],
+    };
+    directions.contains(&direction)
+}
+
+/// The pipe at a corner of the region's border, connecting the two edges that meet there.
+fn corner_pipe(map: &Grid<char>, position: Position) -> char {
+    let edge = |direction: Direction| {
+        let expected = if direction.is_vertical() { '|' } else { '-' };
+        map.get(position.move_in_direction(direction)) == Some(&expected)
+    };
+    match (
+        edge(Direction::North),
+        edge(Direction::East),
+        edge(Direction::South),
+    ) {
+        (true, true, _) => 'L',
+        (true, false, true) => '|',
+        (true, false, false) => 'J',
+        (false, true, true) => 'F',
+        (false, true, false) => '-',
+        (false, false, _) => '7',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::tests::assert_solves;
+    use rand::SeedableRng;
+
+    #[test]
+    fn generated_loops_are_solved() {
+        for seed in 0..20 {
+            let generated = generate(&mut Random::seed_from_u64(seed), 12).unwrap();
+            assert_solves(10, &generated);
+        }
+    }
+
+    #[test]
+    fn single_cell_is_a_square() {
+        let generated = generate(&mut Random::seed_from_u64(0), 1).unwrap();
+        assert_eq!(generated.answers[0], (Part::One, "4".to_string()));
+        assert_eq!(generated.answers[1], (Part::Two, "1".to_string()));
+        assert_solves(10, &generated);
+    }
+}
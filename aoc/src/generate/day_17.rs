@@ -0,0 +1,41 @@
+use rand::RngExt;
+
+use super::{at_least, Generated, Random};
+
+/// Fills a `size` x `size` map with random heat losses. The least heat loss isn't known without
+/// searching, so no answers are included.
+///
+/// Every map of at least 12 x 12 blocks can be crossed by both crucibles: an ultra crucible
+/// can cover any distance of 11 or more blocks with straight runs of 4 to 10 blocks, so it can
+/// go east and south alternately with as many runs in each direction.
+pub fn generate(rng: &mut Random, size: usize) -> Result<Generated, String> {
+    at_least(size, 12)?;
+    let mut input = String::with_capacity((size + 1) * size);
+    for _ in 0..size {
+        input.extend((0..size).map(|_| char::from(b'0' + rng.random_range(1..=9u8))));
+        input.push('\n');
+    }
+    Ok(Generated {
+        input,
+        answers: vec![],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solvers;
+    use rand::SeedableRng;
+    use solver::Part;
+
+    #[test]
+    fn generated_maps_are_solved() {
+        let generated = generate(&mut Random::seed_from_u64(0), 12).unwrap();
+        let solver = solvers::solver(17).unwrap();
+        for part in Part::ALL {
+            let heat_loss = solver.solve(part, &generated.input).unwrap();
+            // every block costs at least 1 and 22 blocks have to be entered
+            assert!(heat_loss.parse::<u32>().unwrap() >= 22);
+        }
+    }
+}
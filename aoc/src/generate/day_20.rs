@@ -0,0 +1,153 @@
+use rand::{seq::SliceRandom, RngExt};
+use solver::Part;
+
+use super::{at_least, Generated, Random};
+
+/// Flip-flops per counter, like in the real inputs.
+const BITS: u32 = 12;
+
+/// Builds `size` binary counters like the ones of the real inputs: a chain of flip-flops
+/// counting button presses, and a conjunction that resets the chain once the flip-flops of the
+/// set bits of a prime are on. The conjunctions of all counters feed `rx` through an inverter
+/// each and one more conjunction, so `rx` gets its first low pulse after as many presses as
+/// the product of the primes.
+///
+/// Part 1 depends on the order of the pulses and is left out.
+pub fn generate(rng: &mut Random, size: usize) -> Result<Generated, String> {
+    at_least(size, 1)?;
+    if size > 10 {
+        return Err("The size can be at most 10, more counters overflow the answer".to_string());
+    }
+    let mut names = ('a'..='z')
+        .flat_map(|a| ('a'..='z').map(move |b| format!("{}{}", a, b)))
+        .filter(|name| name != "rx")
+        .collect::<Vec<_>>();
+    names.shuffle(rng);
+    let mut names = names.into_iter();
+    let mut name = || names.next().unwrap();
+
+    let mut primes = vec![];
+    while primes.len() < size {
+        let candidate = rng.random_range(1 << (BITS - 1)..1 << BITS);
+        if is_prime(candidate) && !primes.contains(&candidate) {
+            primes.push(candidate);
+        }
+    }
+    let last = name();
+    let mut starts = vec![];
+    let mut lines = vec![format!("&{} -> rx", last)];
+    for prime in &primes {
+        let bits = (0..BITS).map(|_| name()).collect::<Vec<_>>();
+        let hub = name();
+        let inverter = name();
+        let mut resets = vec![bits[0].clone()];
+        for (i, bit) in bits.iter().enumerate() {
+            let mut outputs = bits.get(i + 1).into_iter().cloned().collect::<Vec<_>>();
+            if prime & (1 << i) != 0 {
+                outputs.insert(rng.random_range(0..=outputs.len()), hub.clone());
+            } else {
+                resets.push(bit.clone());
+            }
+            lines.push(format!("%{} -> {}", bit, outputs.join(", ")));
+        }
+        resets.shuffle(rng);
+        resets.insert(rng.random_range(0..=resets.len()), inverter.clone());
+        lines.push(format!("&{} -> {}", hub, resets.join(", ")));
+        lines.push(format!("&{} -> {}", inverter, last));
+        starts.push(bits[0].clone());
+    }
+    lines.push(format!("broadcaster -> {}", starts.join(", ")));
+    lines.shuffle(rng);
+
+    let presses = primes.iter().map(|prime| *prime as u128).product::<u128>();
+    Ok(Generated {
+        input: lines.join("\n") + "\n",
+        answers: vec![(Part::Two, presses.to_string())],
+    })
+}
+
+fn is_prime(n: u32) -> bool {
+    n >= 2
+        && (2..)
+            .take_while(|d| d * d <= n)
+            .all(|d| !n.is_multiple_of(d))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, VecDeque};
+
+    use super::*;
+    use crate::solvers;
+    use rand::SeedableRng;
+
+    /// Presses the button until `rx` gets a low pulse, with the pulses processed in the order
+    /// they are sent.
+    fn presses_until_rx(input: &str) -> u64 {
+        let modules = input
+            .lines()
+            .map(|line| {
+                let (name, outputs) = line.split_once(" -> ").unwrap();
+                let outputs = outputs.split(", ").collect::<Vec<_>>();
+                match name.split_at(1) {
+                    ("%", name) | ("&", name) => (name, (&line[..1], outputs)),
+                    _ => (name, ("", outputs)),
+                }
+            })
+            .collect::<HashMap<_, _>>();
+        let mut on = HashMap::new();
+        let mut memory = HashMap::new();
+        for (name, (_, outputs)) in &modules {
+            for output in outputs {
+                memory.insert((*output, *name), false);
+            }
+        }
+        for presses in 1.. {
+            let mut pulses = VecDeque::from([("button", "broadcaster", false)]);
+            while let Some((source, target, high)) = pulses.pop_front() {
+                if target == "rx" && !high {
+                    return presses;
+                }
+                let Some((kind, outputs)) = modules.get(target) else {
+                    continue;
+                };
+                let sent = match *kind {
+                    "%" if high => continue,
+                    "%" => {
+                        let state = on.entry(target).or_insert(false);
+                        *state = !*state;
+                        *state
+                    }
+                    "&" => {
+                        memory.insert((target, source), high);
+                        !memory
+                            .iter()
+                            .filter(|((to, _), _)| *to == target)
+                            .all(|(_, high)| *high)
+                    }
+                    _ => high,
+                };
+                pulses.extend(outputs.iter().map(|output| (target, *output, sent)));
+            }
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn rx_gets_a_low_pulse_after_the_prime() {
+        for seed in 0..3 {
+            let generated = generate(&mut Random::seed_from_u64(seed), 1).unwrap();
+            assert_eq!(
+                generated.answers,
+                [(Part::Two, presses_until_rx(&generated.input).to_string())]
+            );
+        }
+    }
+
+    #[test]
+    fn generated_networks_are_parsed() {
+        let generated = generate(&mut Random::seed_from_u64(0), 4).unwrap();
+        let solver = solvers::solver(20).unwrap();
+        assert!(solver.solve(Part::One, &generated.input).is_ok());
+    }
+}
@@ -0,0 +1,129 @@
+use grid::{Grid, Position};
+use rand::RngExt;
+use solver::Part;
+
+use super::{at_least, Generated, Random};
+
+/// Distance between neighbouring junctions.
+const SPACING: i32 = 24;
+/// How far a trail may wander off the straight line between two junctions.
+const DETOUR: i32 = 4;
+/// Distance of the outermost junctions from the edge of the map.
+const MARGIN: i32 = DETOUR + 2;
+
+/// Lays out `size` x `size` junctions connected to their neighbours by winding trails, with
+/// slopes next to the junctions that only let the trails be walked east and south. The start
+/// leads straight to the first junction and the last one straight to the end.
+///
+/// The slopes make the trails a directed acyclic graph, so the longest hike of part 1 follows
+/// from the lengths of the trails. Part 2 needs a search and is left out.
+pub fn generate(rng: &mut Random, size: usize) -> Result<Generated, String> {
+    at_least(size, 1)?;
+    let n = size as i32;
+    let side = 2 * MARGIN + SPACING * (n - 1) + 1;
+    let mut map = Grid::filled(side as usize, side as usize, '#');
+    let junction = |i: i32, j: i32| Position::new(MARGIN + SPACING * i, MARGIN + SPACING * j);
+    for y in 0..=MARGIN {
+        map[Position::new(MARGIN, y)] = '.';
+        map[Position::new(side - 1 - MARGIN, side - 1 - y)] = '.';
+    }
+    // longest hike from the start to each junction, row by row
+    let mut longest = vec![vec![0; size]; size];
+    for j in 0..n {
+        for i in 0..n {
+            let here = junction(i, j);
+            map[here] = '.';
+            let mut from = vec![];
+            if i == 0 && j == 0 {
+                from.push(MARGIN as usize);
+            }
+            if i > 0 {
+                let length = trail(rng, &mut map, junction(i - 1, j), '>', |along, across| {
+                    Position::new(along, across)
+                });
+                from.push(longest[j as usize][i as usize - 1] + length);
+            }
+            if j > 0 {
+                let length = trail(rng, &mut map, junction(i, j - 1), 'v', |along, across| {
+                    Position::new(across, along)
+                });
+                from.push(longest[j as usize - 1][i as usize] + length);
+            }
+            longest[j as usize][i as usize] = from.into_iter().max().unwrap();
+        }
+    }
+    let hike = longest[size - 1][size - 1] + MARGIN as usize;
+    Ok(Generated {
+        input: format!("{}\n", map),
+        answers: vec![(Part::One, hike.to_string())],
+    })
+}
+
+/// Digs a trail of random detours from the junction at `start` to the next one east or south,
+/// returning the number of steps between the two. `position` turns a distance along the trail
+/// and an offset across it into a position relative to `start`.
+///
+/// Detours stay `DETOUR + 2` tiles away from both junctions, so they never come close to the
+/// trails leaving the junctions in the other direction.
+fn trail(
+    rng: &mut Random,
+    map: &mut Grid<char>,
+    start: Position,
+    slope: char,
+    position: impl Fn(i32, i32) -> Position,
+) -> usize {
+    let mut tiles = vec![(1, 0)];
+    let mut along = 1;
+    // a tile of forest has to separate two detours
+    let mut next_detour = DETOUR + 2;
+    while along < SPACING - 1 {
+        let width = rng.random_range(2..=6);
+        let detour_end = along + width;
+        if along >= next_detour && detour_end <= SPACING - DETOUR - 2 && rng.random_bool(0.5) {
+            let offset = rng.random_range(1..=DETOUR) * if rng.random_bool(0.5) { 1 } else { -1 };
+            let step = offset.signum();
+            tiles.extend((1..=offset.abs()).map(|across| (along, across * step)));
+            tiles.extend((along + 1..detour_end).map(|along| (along, offset)));
+            tiles.extend(
+                (0..=offset.abs())
+                    .rev()
+                    .map(|across| (detour_end, across * step)),
+            );
+            along = detour_end;
+            next_detour = detour_end + 2;
+        }
+        along += 1;
+        tiles.push((along, 0));
+    }
+    for (along, across) in &tiles {
+        let offset = position(*along, *across);
+        map[Position::new(start.x + offset.x, start.y + offset.y)] = '.';
+    }
+    let first = position(1, 0);
+    let last = position(SPACING - 1, 0);
+    map[Position::new(start.x + first.x, start.y + first.y)] = slope;
+    map[Position::new(start.x + last.x, start.y + last.y)] = slope;
+    tiles.len() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::tests::assert_solves;
+    use rand::SeedableRng;
+
+    #[test]
+    fn generated_mazes_are_solved() {
+        for seed in 0..3 {
+            let generated = generate(&mut Random::seed_from_u64(seed), 3).unwrap();
+            assert_solves(23, &generated);
+        }
+    }
+
+    #[test]
+    fn single_junction_is_a_straight_line() {
+        let generated = generate(&mut Random::seed_from_u64(0), 1).unwrap();
+        assert_eq!(generated.answers, [(Part::One, "12".to_string())]);
+        assert_solves(23, &generated);
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::BTreeSet;
+
+use rand::{seq::SliceRandom, RngExt};
+use solver::Part;
+
+use super::{at_least, Generated, Random};
+
+/// Splits `size` components into two groups wired so that cutting any set of wires inside a
+/// group takes at least 4 cuts, and joins the groups with exactly 3 wires. Those 3 wires are
+/// then the only way to split the components with 3 cuts, and the answer is the product of the
+/// sizes of the groups.
+///
+/// Each group is a ring where every component is also wired to the ones two places away, which
+/// takes 4 cuts to split, with random extra wires on top.
+pub fn generate(rng: &mut Random, size: usize) -> Result<Generated, String> {
+    at_least(size, 10)?;
+    let first_group = rng.random_range(5..=size - 5);
+    let mut components = (0..size).collect::<Vec<_>>();
+    components.shuffle(rng);
+    let groups = [&components[..first_group], &components[first_group..]];
+    let mut wires = BTreeSet::new();
+    let mut wire = |a: usize, b: usize| wires.insert((a.min(b), a.max(b)));
+    for group in groups {
+        for (i, component) in group.iter().enumerate() {
+            wire(*component, group[(i + 1) % group.len()]);
+            wire(*component, group[(i + 2) % group.len()]);
+        }
+        for _ in 0..group.len() / 2 {
+            let a = group[rng.random_range(0..group.len())];
+            let b = group[rng.random_range(0..group.len())];
+            if a != b {
+                wire(a, b);
+            }
+        }
+    }
+    let mut between = BTreeSet::new();
+    while between.len() < 3 {
+        between.insert((
+            groups[0][rng.random_range(0..groups[0].len())],
+            groups[1][rng.random_range(0..groups[1].len())],
+        ));
+    }
+    between.into_iter().for_each(|(a, b)| {
+        wire(a, b);
+    });
+
+    let names = names(rng, size);
+    let mut lines = vec![vec![]; size];
+    for (a, b) in wires {
+        let (from, to) = if rng.random_bool(0.5) { (a, b) } else { (b, a) };
+        lines[from].push(names[to].as_str());
+    }
+    let mut input = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, connected)| !connected.is_empty())
+        .map(|(component, connected)| format!("{}: {}\n", names[component], connected.join(" ")))
+        .collect::<Vec<_>>();
+    input.shuffle(rng);
+    Ok(Generated {
+        input: input.concat(),
+        answers: vec![(Part::One, (first_group * (size - first_group)).to_string())],
+    })
+}
+
+/// Distinct random names of lowercase letters, three letters long like in the real inputs when
+/// there are few enough components.
+fn names(rng: &mut Random, count: usize) -> Vec<String> {
+    let length = (3..)
+        .find(|length| 26usize.pow(*length) >= 2 * count)
+        .unwrap();
+    let mut names = BTreeSet::new();
+    let mut ordered = vec![];
+    while ordered.len() < count {
+        let name = (0..length)
+            .map(|_| char::from(rng.random_range(b'a'..=b'z')))
+            .collect::<String>();
+        if names.insert(name.clone()) {
+            ordered.push(name);
+        }
+    }
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use rand::SeedableRng;
+
+    /// Sizes of the groups left after removing `removed` wires, found with a flood fill.
+    fn groups(input: &str, removed: &[(&str, &str)]) -> Vec<usize> {
+        let mut neighbors: HashMap<&str, Vec<&str>> = HashMap::new();
+        for line in input.lines() {
+            let (name, connected) = line.split_once(": ").unwrap();
+            for other in connected.split(' ') {
+                if removed.contains(&(name, other)) || removed.contains(&(other, name)) {
+                    continue;
+                }
+                neighbors.entry(name).or_default().push(other);
+                neighbors.entry(other).or_default().push(name);
+            }
+        }
+        let mut unseen = neighbors.keys().copied().collect::<BTreeSet<_>>();
+        let mut sizes = vec![];
+        while let Some(first) = unseen.pop_first() {
+            let mut to_see = vec![first];
+            let mut size = 1;
+            while let Some(name) = to_see.pop() {
+                for other in &neighbors[name] {
+                    if unseen.remove(other) {
+                        size += 1;
+                        to_see.push(other);
+                    }
+                }
+            }
+            sizes.push(size);
+        }
+        sizes.sort();
+        sizes
+    }
+
+    #[test]
+    fn three_wires_join_the_groups() {
+        let generated = generate(&mut Random::seed_from_u64(0), 16).unwrap();
+        let wires = generated
+            .input
+            .lines()
+            .flat_map(|line| {
+                let (name, connected) = line.split_once(": ").unwrap();
+                connected.split(' ').map(move |other| (name, other))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(groups(&generated.input, &[]), [16]);
+        // every way to split the components with 3 cuts
+        let mut splits = vec![];
+        for a in 0..wires.len() {
+            for b in a + 1..wires.len() {
+                for c in b + 1..wires.len() {
+                    let sizes = groups(&generated.input, &[wires[a], wires[b], wires[c]]);
+                    if sizes.len() > 1 {
+                        splits.push(sizes);
+                    }
+                }
+            }
+        }
+        assert_eq!(splits.len(), 1);
+        let product = splits[0].iter().product::<usize>();
+        assert_eq!(generated.answers, [(Part::One, product.to_string())]);
+    }
+}
@@ -0,0 +1,505 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use bench_report::Timings;
+use clap::{Parser, Subcommand};
+use report::{Format, Report};
+use solver::{
+    diagnostics, params, recorder, ExportError, ParseError, Part, Run, Solver, StreamError,
+};
+use verify::Answers;
+
+mod bench_report;
+mod generate;
+mod player;
+mod report;
+mod solvers;
+mod verify;
+
+#[derive(Parser)]
+#[command(about = "Runs the Advent of Code 2023 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the solution of a single day
+    Run {
+        #[arg(long)]
+        day: u8,
+        /// Part to run, both parts are run when omitted
+        #[arg(long, value_parser = parse_part)]
+        part: Option<Part>,
+        /// Puzzle input, `-` reads stdin, defaults to the input.txt of the day's crate
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Solve while reading the input instead of loading all of it first, only days 1, 9 and
+        /// 15 support it. The time spent parsing is included in the solve time.
+        #[arg(long)]
+        stream: bool,
+        /// How to print the answers
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Where to write diagnostics like the maps some days draw: `stderr`, `none` or a file
+        #[arg(long, default_value = "stderr", value_parser = parse_diagnostics)]
+        diagnostics: Diagnostics,
+        /// Override a parameter of the puzzle like `cycles=5000`, can be repeated
+        #[arg(long = "param", value_parser = params::parse_override)]
+        params: Vec<(String, String)>,
+    },
+    /// Run the solutions on their input.txt and compare the answers with the expected ones
+    Verify {
+        /// Day to verify, all days are verified when omitted
+        #[arg(long)]
+        day: Option<u8>,
+        /// Expected answers, a `[day-NN]` table per day with `part-1` and `part-2` keys
+        #[arg(long, default_value = "answers.toml")]
+        answers: PathBuf,
+        /// Seconds a part may run before it is stopped and reported as timed out
+        #[arg(long, default_value_t = 60)]
+        timeout: u64,
+    },
+    /// Write the puzzle input of a day as a Graphviz graph, for the days that are solved with
+    /// a graph
+    Graph {
+        #[arg(long)]
+        day: u8,
+        /// Puzzle input, defaults to the input.txt of the day's crate
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// File to write the graph to, defaults to stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Draw the result of a day as an SVG or PNG picture, for days 10, 11, 16, 17 and 18
+    Render {
+        #[arg(long)]
+        day: u8,
+        #[arg(long, default_value = "1", value_parser = parse_part)]
+        part: Part,
+        /// Puzzle input, defaults to the input.txt of the day's crate
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// File to write the picture to, ending in .svg or .png
+        #[arg(long)]
+        output: PathBuf,
+        /// Override a parameter of the puzzle like `cycles=5000`, can be repeated
+        #[arg(long = "param", value_parser = params::parse_override)]
+        params: Vec<(String, String)>,
+    },
+    /// Watch the simulation of a day step by step in the terminal, for the days that record
+    /// their steps: 14, 16, 17, 18, 21 and 22
+    Replay {
+        #[arg(long)]
+        day: u8,
+        #[arg(long, default_value = "1", value_parser = parse_part)]
+        part: Part,
+        /// Puzzle input, defaults to the input.txt of the day's crate
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Frames to keep, longer simulations are sampled evenly
+        #[arg(long, default_value_t = 200)]
+        max_frames: usize,
+        /// Print the frames one after the other instead of playing them
+        #[arg(long)]
+        dump: bool,
+        /// Override a parameter of the puzzle like `cycles=5000`, can be repeated
+        #[arg(long = "param", value_parser = params::parse_override)]
+        params: Vec<(String, String)>,
+    },
+    /// Generate a puzzle input of any size for the days that have a generator, printing the
+    /// answers known from its construction to stderr
+    Gen {
+        #[arg(long)]
+        day: u8,
+        /// Size of the input: half the side of the map for day 10, the side of the map for day
+        /// 17, counters for day 20, junctions per side for day 23 and components for day 25
+        #[arg(long)]
+        size: usize,
+        /// Seed of the random number generator, the same seed generates the same input
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// File to write the input to, defaults to stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// File to write the known answers to, in the format read by `verify --answers`
+        #[arg(long)]
+        answers: Option<PathBuf>,
+    },
+    /// Summarize the results of `cargo bench` and compare them with the stored baseline
+    BenchReport {
+        /// Criterion output directories, days 24 and 25 are benchmarked by aoc with the `mkl`
+        /// and `rustworkx` features
+        #[arg(long = "criterion-dir", default_values = ["target/criterion"])]
+        criterion_dirs: Vec<PathBuf>,
+        #[arg(long, default_value = "bench-baseline.toml")]
+        baseline: PathBuf,
+        /// Slowdown in percent above which a benchmark counts as a regression
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+        /// Store the current results as the new baseline
+        #[arg(long)]
+        save_baseline: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Diagnostics {
+    Stderr,
+    None,
+    File(PathBuf),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command) -> Result<(), String> {
+    match command {
+        Command::Run {
+            day,
+            part,
+            input,
+            stream,
+            format,
+            diagnostics,
+            params,
+        } => {
+            let solver = solvers::solver(day).ok_or_else(|| solvers::no_solver(day))?;
+            set_params(solver, params)?;
+            let path = input.unwrap_or_else(|| default_input(day));
+            let parts = match part {
+                Some(part) => vec![part],
+                None => Part::ALL.to_vec(),
+            };
+            if stream {
+                return run_stream(day, &parts, &path, format);
+            }
+            let input = if path == Path::new(STDIN) {
+                io::read_to_string(io::stdin())
+            } else {
+                fs::read_to_string(&path)
+            }
+            .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+            diagnostics::set_sink(match diagnostics {
+                Diagnostics::Stderr => Some(Box::new(io::stderr())),
+                Diagnostics::None => None,
+                Diagnostics::File(diagnostics_path) => Some(Box::new(
+                    File::create(&diagnostics_path).map_err(|error| {
+                        format!("Failed to create {}: {}", diagnostics_path.display(), error)
+                    })?,
+                )),
+            });
+            for part in &parts {
+                solver.check_input_params(*part, &input)?;
+            }
+            for part in &parts {
+                let run = solver
+                    .run(*part, &input)
+                    .map_err(|error| format_parse_error(&path, &input, &error))?;
+                let report = Report::new(day, *part, run);
+                println!("{}", report.format(format, parts.len() > 1));
+            }
+            Ok(())
+        }
+        Command::Verify {
+            day,
+            answers,
+            timeout,
+        } => {
+            let answers = Answers::load(&answers)?;
+            let days = match day {
+                Some(day @ 1..=25) => vec![day],
+                Some(day) => return Err(format!("There is no puzzle on day {}", day)),
+                None => (1..=25).collect(),
+            };
+            verify::verify_days(&answers, &days, Duration::from_secs(timeout))
+        }
+        Command::Graph { day, input, output } => {
+            let export = solvers::graph(day).ok_or_else(|| match solvers::solver(day) {
+                Some(_) => format!("Day {} has no graph to export", day),
+                None => solvers::no_solver(day),
+            })?;
+            let path = input.unwrap_or_else(|| default_input(day));
+            let input = fs::read_to_string(&path)
+                .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+            let result = match &output {
+                Some(output) => {
+                    let mut file = File::create(output).map_err(|error| {
+                        format!("Failed to create {}: {}", output.display(), error)
+                    })?;
+                    export(&input, &mut file)
+                }
+                None => export(&input, &mut io::stdout()),
+            };
+            result.map_err(|error| match error {
+                ExportError::Parse(error) => format_parse_error(&path, &input, &error),
+                ExportError::Io(error) => format!("Failed to write the graph: {}", error),
+            })
+        }
+        Command::Render {
+            day,
+            part,
+            input,
+            output,
+            params,
+        } => {
+            let render = solvers::picture(day)
+                .ok_or_else(|| format!("Day {} has no picture to render", day))?;
+            set_params(
+                solvers::solver(day).expect("a day with a picture has a solver"),
+                params,
+            )?;
+            let extension = output.extension().and_then(|extension| extension.to_str());
+            if !matches!(extension, Some("svg" | "png")) {
+                return Err(format!(
+                    "Unknown picture format of {}, expected .svg or .png",
+                    output.display()
+                ));
+            }
+            let path = input.unwrap_or_else(|| default_input(day));
+            let input = fs::read_to_string(&path)
+                .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+            let picture =
+                render(part, &input).map_err(|error| format_parse_error(&path, &input, &error))?;
+            let result = match extension {
+                Some("svg") => fs::write(&output, picture.to_svg()),
+                _ => File::create(&output)
+                    .and_then(|file| picture.write_png(io::BufWriter::new(file))),
+            };
+            result.map_err(|error| format!("Failed to write {}: {}", output.display(), error))
+        }
+        Command::Replay {
+            day,
+            part,
+            input,
+            max_frames,
+            dump,
+            params,
+        } => {
+            let solver = solvers::solver(day).ok_or_else(|| solvers::no_solver(day))?;
+            set_params(solver, params)?;
+            let path = input.unwrap_or_else(|| default_input(day));
+            let input = fs::read_to_string(&path)
+                .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+            solver.check_input_params(part, &input)?;
+            diagnostics::set_sink(None);
+            recorder::start(max_frames);
+            let run = solver.run(part, &input);
+            let frames = recorder::finish();
+            let run = run.map_err(|error| format_parse_error(&path, &input, &error))?;
+            if frames.is_empty() {
+                return Err(format!("Day {} part {} records no steps", day, part));
+            }
+            if dump {
+                for frame in &frames {
+                    println!("step {}: {}\n{}\n", frame.step, frame.label, frame.text);
+                }
+                return Ok(());
+            }
+            let title = format!("day {:02} part {}: {}", day, part, run.answer);
+            player::play(title, frames)
+                .map_err(|error| format!("Failed to run the player: {}", error))
+        }
+        Command::Gen {
+            day,
+            size,
+            seed,
+            output,
+            answers,
+        } => {
+            let generated = generate::generate(day, size, seed)?;
+            match &output {
+                Some(output) => fs::write(output, &generated.input)
+                    .map_err(|error| format!("Failed to write {}: {}", output.display(), error))?,
+                None => print!("{}", generated.input),
+            }
+            for (part, answer) in &generated.answers {
+                eprintln!("day {:02} part {}: {}", day, part, answer);
+            }
+            if let Some(answers) = answers {
+                fs::write(&answers, generated.answers_toml(day))
+                    .map_err(|error| format!("Failed to write {}: {}", answers.display(), error))?;
+            }
+            Ok(())
+        }
+        Command::BenchReport {
+            criterion_dirs,
+            baseline,
+            threshold,
+            save_baseline,
+        } => {
+            let current = Timings::from_criterion(&criterion_dirs)?;
+            if current.is_empty() {
+                return Err("No benchmark results found, run `cargo bench` first".to_string());
+            }
+            if save_baseline {
+                current.save(&baseline)?;
+                println!("Saved the baseline to {}", baseline.display());
+                return Ok(());
+            }
+            let baseline = if baseline.exists() {
+                Timings::load(&baseline)?
+            } else {
+                Timings::default()
+            };
+            let (table, regressions) = bench_report::summarize(&current, &baseline, threshold);
+            print!("{}", table);
+            if regressions.is_empty() {
+                return Ok(());
+            }
+            println!();
+            for regression in &regressions {
+                println!(
+                    "day-{:02}/{} regressed by {:.1}%",
+                    regression.day,
+                    regression.benchmark,
+                    regression.change()
+                );
+            }
+            Err(format!("{} benchmark(s) regressed", regressions.len()))
+        }
+    }
+}
+
+/// Solves `parts` while reading the input from `path`, which is opened again for every part.
+fn run_stream(day: u8, parts: &[Part], path: &Path, format: Format) -> Result<(), String> {
+    let solver = solvers::stream_solver(day)
+        .ok_or_else(|| format!("Day {} can't be solved from a stream", day))?;
+    if path == Path::new(STDIN) && parts.len() > 1 {
+        return Err("Only one part can be solved from stdin, pick one with --part".to_string());
+    }
+    for part in parts {
+        let mut reader: Box<dyn BufRead> = if path == Path::new(STDIN) {
+            Box::new(io::stdin().lock())
+        } else {
+            Box::new(BufReader::new(File::open(path).map_err(|error| {
+                format!("Failed to read {}: {}", path.display(), error)
+            })?))
+        };
+        let start = Instant::now();
+        let answer = solver
+            .solve_stream(*part, &mut reader)
+            .map_err(|error| match error {
+                // the line is gone by now, so unlike format_parse_error it can't be quoted
+                StreamError::Parse(error) => format!(
+                    "{}:{}:{}: {}",
+                    path.display(),
+                    error.line,
+                    error.column,
+                    error
+                ),
+                StreamError::Io(error) => format!("Failed to read {}: {}", path.display(), error),
+            })?;
+        let run = Run {
+            answer,
+            parse_time: Duration::ZERO,
+            solve_time: start.elapsed(),
+        };
+        let report = Report::new(day, *part, run);
+        println!("{}", report.format(format, parts.len() > 1));
+    }
+    Ok(())
+}
+
+/// Checks the parameter overrides against the day and, if the day accepts them, makes them the
+/// ones its solver reads.
+fn set_params(solver: &dyn Solver, overrides: Vec<(String, String)>) -> Result<(), String> {
+    solver.check_params(&overrides)?;
+    params::set_overrides(overrides);
+    Ok(())
+}
+
+/// Formats a parse error like a compiler diagnostic, quoting the offending line of the input.
+fn format_parse_error(path: &Path, input: &str, error: &ParseError) -> String {
+    let line = input.lines().nth(error.line - 1).unwrap_or_default();
+    let number = error.line.to_string();
+    let gutter = " ".repeat(number.len());
+    format!(
+        "{}:{}:{}: {}\n{} |\n{} | {}\n{} | {}^",
+        path.display(),
+        error.line,
+        error.column,
+        error,
+        gutter,
+        number,
+        line,
+        gutter,
+        " ".repeat(error.column - 1)
+    )
+}
+
+/// Passed as the input path to read the input from stdin.
+const STDIN: &str = "-";
+
+fn default_input(day: u8) -> PathBuf {
+    PathBuf::from(format!("day-{:02}", day)).join("input.txt")
+}
+
+fn parse_part(value: &str) -> Result<Part, String> {
+    let part: u8 = value
+        .parse()
+        .map_err(|_| format!("Invalid part: {}", value))?;
+    Part::try_from(part)
+}
+
+fn parse_diagnostics(value: &str) -> Result<Diagnostics, String> {
+    match value {
+        "stderr" => Ok(Diagnostics::Stderr),
+        "none" => Ok(Diagnostics::None),
+        "" => Err("Empty diagnostics path".to_string()),
+        path => Ok(Diagnostics::File(PathBuf::from(path))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_error_quotes_the_line() {
+        let error = ParseError::new(2, 3, "a number");
+        let formatted = format_parse_error(Path::new("in.txt"), "1 2\n3 x\n", &error);
+        assert_eq!(
+            formatted,
+            "in.txt:2:3: expected a number on line 2, column 3\n  |\n2 | 3 x\n  |   ^"
+        );
+    }
+
+    #[test]
+    fn parse_diagnostics_works() {
+        assert_eq!(parse_diagnostics("stderr"), Ok(Diagnostics::Stderr));
+        assert_eq!(parse_diagnostics("none"), Ok(Diagnostics::None));
+        assert_eq!(
+            parse_diagnostics("maps.txt"),
+            Ok(Diagnostics::File(PathBuf::from("maps.txt")))
+        );
+    }
+
+    #[test]
+    fn params_are_checked() {
+        let cycles = vec![("cycles".to_string(), "5000".to_string())];
+        assert_eq!(
+            set_params(solvers::solver(7).unwrap(), cycles.clone()),
+            Err("Day 7 has no parameters".to_string())
+        );
+        assert_eq!(
+            set_params(solvers::solver(20).unwrap(), cycles),
+            Err("Unknown parameter cycles, expected one of presses".to_string())
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Terminal player for the frames recorded by the simulations, see [`solver::recorder`].
+
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    widgets::{Block, Paragraph},
+    DefaultTerminal,
+};
+use solver::recorder::Frame;
+
+/// Frames per second when the player starts.
+const INITIAL_SPEED: u32 = 10;
+const MAX_SPEED: u32 = 1024;
+/// Lines scrolled by page up and page down.
+const PAGE: u16 = 20;
+
+const HELP: &str =
+    "space play/pause  ←/→ step  home/end  +/- speed  hjkl/↑↓ scroll  pgup/pgdn page  q quit";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Continue,
+    Quit,
+}
+
+/// Playback state: which frame is shown, whether it is playing and how the frame is scrolled.
+#[derive(Debug)]
+pub struct Player {
+    title: String,
+    frames: Vec<Frame>,
+    current: usize,
+    playing: bool,
+    /// Frames per second.
+    speed: u32,
+    /// Lines and columns scrolled away at the top and at the left.
+    scroll: (u16, u16),
+}
+
+impl Player {
+    /// Panics if there are no frames.
+    pub fn new(title: String, frames: Vec<Frame>) -> Self {
+        assert!(!frames.is_empty(), "nothing to play");
+        Self {
+            title,
+            frames,
+            current: 0,
+            playing: true,
+            speed: INITIAL_SPEED,
+            scroll: (0, 0),
+        }
+    }
+
+    pub fn handle(&mut self, key: KeyCode) -> Action {
+        let last = self.frames.len() - 1;
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Char(' ') => {
+                if !self.playing && self.current == last {
+                    self.current = 0;
+                }
+                self.playing = !self.playing;
+            }
+            KeyCode::Right | KeyCode::Char('n') => self.step_to(self.current.saturating_add(1)),
+            KeyCode::Left | KeyCode::Char('p') => self.step_to(self.current.saturating_sub(1)),
+            KeyCode::Home | KeyCode::Char('g') => self.step_to(0),
+            KeyCode::End | KeyCode::Char('G') => self.step_to(last),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.speed = (self.speed * 2).min(MAX_SPEED),
+            KeyCode::Char('-') => self.speed = (self.speed / 2).max(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll.0 = self.scroll.0.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll.0 = self.scroll.0.saturating_add(1),
+            KeyCode::PageUp => self.scroll.0 = self.scroll.0.saturating_sub(PAGE),
+            KeyCode::PageDown => self.scroll.0 = self.scroll.0.saturating_add(PAGE),
+            KeyCode::Char('h') => self.scroll.1 = self.scroll.1.saturating_sub(1),
+            KeyCode::Char('l') => self.scroll.1 = self.scroll.1.saturating_add(1),
+            _ => {}
+        }
+        Action::Continue
+    }
+
+    /// Shows the next frame while playing, pausing at the last one.
+    pub fn advance(&mut self) {
+        if !self.playing {
+            return;
+        }
+        if self.current + 1 < self.frames.len() {
+            self.current += 1;
+        }
+        if self.current + 1 == self.frames.len() {
+            self.playing = false;
+        }
+    }
+
+    fn step_to(&mut self, frame: usize) {
+        self.playing = false;
+        self.current = frame.min(self.frames.len() - 1);
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(1) / self.speed
+    }
+
+    pub fn render(&self, terminal_frame: &mut ratatui::Frame) {
+        let frame = &self.frames[self.current];
+        let [view, status] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)])
+            .areas(terminal_frame.area());
+        let block = Block::bordered().title(format!(" {}: {} ", self.title, frame.label));
+        terminal_frame.render_widget(
+            Paragraph::new(frame.text.as_str())
+                .scroll(self.scroll)
+                .block(block),
+            view,
+        );
+        let state = if self.playing { "playing" } else { "paused" };
+        terminal_frame.render_widget(
+            Paragraph::new(format!(
+                "frame {}/{}  step {}  {} at {} fps  {}",
+                self.current + 1,
+                self.frames.len(),
+                frame.step,
+                state,
+                self.speed,
+                HELP
+            )),
+            status,
+        );
+    }
+}
+
+/// Plays `frames` in the terminal until the user quits.
+pub fn play(title: String, frames: Vec<Frame>) -> io::Result<()> {
+    let mut terminal = ratatui::try_init()?;
+    let result = run(&mut terminal, Player::new(title, frames));
+    ratatui::try_restore()?;
+    result
+}
+
+fn run(terminal: &mut DefaultTerminal, mut player: Player) -> io::Result<()> {
+    let mut shown = Instant::now();
+    loop {
+        terminal.draw(|frame| player.render(frame))?;
+        let timeout = if player.playing {
+            player.interval().saturating_sub(shown.elapsed())
+        } else {
+            Duration::from_secs(60)
+        };
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && player.handle(key.code) == Action::Quit {
+                    return Ok(());
+                }
+            }
+        } else {
+            player.advance();
+            shown = Instant::now();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn player(count: usize) -> Player {
+        let frames = (0..count)
+            .map(|step| Frame {
+                step: step * 2,
+                label: format!("step {}", step * 2),
+                text: format!("#{}#\n...", step),
+            })
+            .collect();
+        Player::new("day 14 part 1: 136".to_string(), frames)
+    }
+
+    #[test]
+    fn playing_stops_at_the_end() {
+        let mut player = player(3);
+        player.advance();
+        player.advance();
+        assert_eq!((player.current, player.playing), (2, false));
+        player.advance();
+        assert_eq!(player.current, 2);
+        player.handle(KeyCode::Char(' '));
+        assert_eq!((player.current, player.playing), (0, true));
+    }
+
+    #[test]
+    fn keys_step_and_scroll() {
+        let mut player = player(3);
+        player.handle(KeyCode::Right);
+        assert_eq!((player.current, player.playing), (1, false));
+        player.handle(KeyCode::End);
+        player.handle(KeyCode::Right);
+        assert_eq!(player.current, 2);
+        player.handle(KeyCode::Home);
+        player.handle(KeyCode::Left);
+        assert_eq!(player.current, 0);
+        player.handle(KeyCode::Char('j'));
+        player.handle(KeyCode::Char('l'));
+        player.handle(KeyCode::Up);
+        player.handle(KeyCode::Up);
+        assert_eq!(player.scroll, (0, 1));
+        player.handle(KeyCode::Char('+'));
+        assert_eq!(player.interval(), Duration::from_millis(50));
+        assert_eq!(player.handle(KeyCode::Char('q')), Action::Quit);
+    }
+
+    #[test]
+    fn frame_is_rendered() {
+        let mut player = player(3);
+        player.handle(KeyCode::Right);
+        let mut terminal = Terminal::new(TestBackend::new(40, 5)).unwrap();
+        terminal.draw(|frame| player.render(frame)).unwrap();
+        let lines = terminal
+            .backend()
+            .buffer()
+            .content()
+            .chunks(40)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>();
+        assert_eq!(lines[0], "┌ day 14 part 1: 136: step 2 ──────────┐");
+        assert_eq!(lines[1], "│#1#                                   │");
+        assert_eq!(lines[2], "│...                                   │");
+        assert!(lines[4].starts_with("frame 2/3  step 2  paused at 10 fps"));
+    }
+}
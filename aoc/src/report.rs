@@ -0,0 +1,75 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use solver::{Part, Run};
+
+/// How the answers are printed to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Just the answers, prefixed with the part when both parts are run
+    Text,
+    /// One JSON object per line and part, with the answer and the timings
+    Json,
+}
+
+/// Result of running a single part, as printed with `--format json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub parse_ms: f64,
+    pub solve_ms: f64,
+}
+
+impl Report {
+    pub fn new(day: u8, part: Part, run: Run) -> Self {
+        Self {
+            day,
+            part: match part {
+                Part::One => 1,
+                Part::Two => 2,
+            },
+            answer: run.answer,
+            parse_ms: run.parse_time.as_secs_f64() * 1000.0,
+            solve_ms: run.solve_time.as_secs_f64() * 1000.0,
+        }
+    }
+
+    /// `with_part` prefixes the text format with the part, to tell the answers apart.
+    pub fn format(&self, format: Format, with_part: bool) -> String {
+        match format {
+            Format::Text if with_part => format!("Part {}: {}", self.part, self.answer),
+            Format::Text => self.answer.clone(),
+            Format::Json => serde_json::to_string(self).expect("reports serialize to JSON"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn report() -> Report {
+        let run = Run {
+            answer: "42".to_string(),
+            parse_time: Duration::from_micros(1500),
+            solve_time: Duration::from_millis(20),
+        };
+        Report::new(7, Part::Two, run)
+    }
+
+    #[test]
+    fn text_format_works() {
+        assert_eq!(report().format(Format::Text, false), "42");
+        assert_eq!(report().format(Format::Text, true), "Part 2: 42");
+    }
+
+    #[test]
+    fn json_format_works() {
+        assert_eq!(
+            report().format(Format::Json, true),
+            r#"{"day":7,"part":2,"answer":"42","parse_ms":1.5,"solve_ms":20.0}"#
+        );
+    }
+}
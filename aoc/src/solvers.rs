@@ -0,0 +1,179 @@
+use std::io::Write;
+
+use render::Picture;
+use solver::{ExportError, ParseError, Part, Solver, StreamSolver};
+
+/// Writes the puzzle input of a day as a Graphviz graph.
+pub type ExportDot = fn(&str, &mut dyn Write) -> Result<(), ExportError>;
+
+/// Draws the result of a part of a day.
+pub type Render = fn(Part, &str) -> Result<Picture, ParseError>;
+
+// day-24 and day-25 are only built with the `mkl` and `rustworkx` features, see
+// aoc/Cargo.toml.
+static SOLVERS: &[&dyn Solver] = &[
+    &day_01::Day01,
+    &day_02::Day02,
+    &day_03::Day03,
+    &day_04::Day04,
+    &day_05::Day05,
+    &day_06::Day06,
+    &day_07::Day07,
+    &day_08::Day08,
+    &day_09::Day09,
+    &day_10::Day10,
+    &day_11::Day11,
+    &day_12::Day12,
+    &day_13::Day13,
+    &day_14::Day14,
+    &day_15::Day15,
+    &day_16::Day16,
+    &day_17::Day17,
+    &day_18::Day18,
+    &day_19::Day19,
+    &day_20::Day20,
+    &day_21::Day21,
+    &day_22::Day22,
+    &day_23::Day23,
+    #[cfg(feature = "mkl")]
+    &day_24::Day24,
+    #[cfg(feature = "rustworkx")]
+    &day_25::Day25,
+];
+
+/// The features of `aoc` that build the days with heavy dependencies.
+const FEATURES: [(u8, &str); 2] = [(24, "mkl"), (25, "rustworkx")];
+
+static STREAM_SOLVERS: [&dyn StreamSolver; 3] = [&day_01::Day01, &day_09::Day09, &day_15::Day15];
+
+static GRAPHS: &[(u8, ExportDot)] = &[
+    (19, day_19::export_dot),
+    (20, day_20::export_dot),
+    (23, day_23::export_dot),
+    #[cfg(feature = "rustworkx")]
+    (25, day_25::export_dot),
+];
+
+static PICTURES: [(u8, Render); 5] = [
+    (10, day_10::render),
+    (11, day_11::render),
+    (16, day_16::render),
+    (17, day_17::render),
+    (18, day_18::render),
+];
+
+pub fn solver(day: u8) -> Option<&'static dyn Solver> {
+    SOLVERS.iter().find(|solver| solver.day() == day).copied()
+}
+
+/// Why there is no solver for `day`, naming the feature that builds it if there is one.
+pub fn no_solver(day: u8) -> String {
+    match FEATURES.iter().find(|(feature_day, _)| *feature_day == day) {
+        Some((_, feature)) => format!(
+            "No solver for day {}, build aoc with --features {}",
+            day, feature
+        ),
+        None => format!("No solver for day {}", day),
+    }
+}
+
+pub fn stream_solver(day: u8) -> Option<&'static dyn StreamSolver> {
+    STREAM_SOLVERS
+        .iter()
+        .find(|solver| solver.day() == day)
+        .copied()
+}
+
+pub fn graph(day: u8) -> Option<ExportDot> {
+    GRAPHS
+        .iter()
+        .find(|(graph_day, _)| *graph_day == day)
+        .map(|(_, export)| *export)
+}
+
+pub fn picture(day: u8) -> Option<Render> {
+    PICTURES
+        .iter()
+        .find(|(picture_day, _)| *picture_day == day)
+        .map(|(_, render)| *render)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::*;
+
+    #[test]
+    fn solvers_are_in_day_order() {
+        let days = SOLVERS
+            .iter()
+            .map(|solver| solver.day())
+            .collect::<Vec<_>>();
+        let mut expected = (1..=23).collect::<Vec<_>>();
+        expected.extend(
+            FEATURES
+                .iter()
+                .filter(|(_, feature)| built_with(feature))
+                .map(|(day, _)| *day),
+        );
+        assert_eq!(days, expected);
+    }
+
+    #[test]
+    fn solver_lookup_works() {
+        assert_eq!(solver(17).map(|solver| solver.day()), Some(17));
+        assert_eq!(solver(24).is_some(), built_with("mkl"));
+        assert_eq!(solver(25).is_some(), built_with("rustworkx"));
+        assert!(solver(26).is_none());
+        assert!(solver(0).is_none());
+        assert_eq!(
+            no_solver(24),
+            "No solver for day 24, build aoc with --features mkl"
+        );
+        assert_eq!(no_solver(26), "No solver for day 26");
+    }
+
+    fn built_with(feature: &str) -> bool {
+        let built = [
+            ("mkl", cfg!(feature = "mkl")),
+            ("rustworkx", cfg!(feature = "rustworkx")),
+        ];
+        built.contains(&(feature, true))
+    }
+
+    #[test]
+    fn fuzz_corpus_does_not_panic() {
+        let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("../fuzz/corpus");
+        for solver in SOLVERS {
+            // days 24 and 25 keep their corpus in their own fuzz directory
+            let day_corpus = match corpus.join(format!("day_{:02}", solver.day())) {
+                day_corpus if day_corpus.exists() => day_corpus,
+                _ => Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join(format!("../day-{:02}/fuzz/corpus/parse", solver.day())),
+            };
+            for entry in fs::read_dir(&day_corpus).unwrap() {
+                let bytes = fs::read(entry.unwrap().path()).unwrap();
+                // the fuzz targets see the longest valid UTF-8 prefix of their input
+                let input = match std::str::from_utf8(&bytes) {
+                    Ok(input) => input,
+                    Err(error) => std::str::from_utf8(&bytes[..error.valid_up_to()]).unwrap(),
+                };
+                for part in Part::ALL {
+                    let _ = solver.parse(part, input);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn graph_lookup_works() {
+        assert_eq!(stream_solver(9).map(|solver| solver.day()), Some(9));
+        assert!(stream_solver(10).is_none());
+        assert!(graph(20).is_some());
+        assert_eq!(graph(25).is_some(), built_with("rustworkx"));
+        assert!(graph(17).is_none());
+        assert!(picture(17).is_some());
+        assert!(picture(19).is_none());
+    }
+}
@@ -0,0 +1,319 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    fs,
+    io::Read,
+    path::Path,
+    process::{Command, Stdio},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+use solver::Part;
+
+use crate::{report::Report, solvers};
+
+/// Expected answers for the real puzzle inputs, read from a TOML file with a `day-NN` table per
+/// day holding `part-1` and `part-2` keys.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Answers(BTreeMap<(u8, Part), Expected>);
+
+/// What a part of the answers file holds, the answer or `{ skip = "reason" }` for a part that is
+/// left unanswered on purpose.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Expected {
+    Answer(String),
+    Skip { skip: String },
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+        Self::parse(&text).map_err(|error| format!("{}: {}", path.display(), error))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let tables: BTreeMap<String, BTreeMap<String, Expected>> =
+            toml::from_str(text).map_err(|error| error.message().to_string())?;
+        let mut answers = BTreeMap::new();
+        for (day_key, parts) in tables {
+            let day = day_key
+                .strip_prefix("day-")
+                .and_then(|day| day.parse::<u8>().ok())
+                .ok_or_else(|| format!("Invalid day table [{}], expected [day-NN]", day_key))?;
+            for (part_key, answer) in parts {
+                let part = part_key
+                    .strip_prefix("part-")
+                    .and_then(|part| part.parse::<u8>().ok())
+                    .and_then(|part| Part::try_from(part).ok())
+                    .ok_or_else(|| {
+                        format!(
+                            "Invalid key {} in [{}], expected part-1 or part-2",
+                            part_key, day_key
+                        )
+                    })?;
+                answers.insert((day, part), answer);
+            }
+        }
+        Ok(Self(answers))
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&Expected> {
+        self.0.get(&(day, part))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Pass(Report),
+    Fail {
+        expected: String,
+        report: Report,
+    },
+    Timeout(Duration),
+    /// The solution could not be run, e.g. because the input doesn't parse.
+    Error(String),
+    /// There is no expected answer to compare with.
+    Missing,
+    /// The part is left unanswered on purpose, for the reason given.
+    Skipped(String),
+    /// The day has no solver in this build of the runner, for the reason given.
+    NotRun(String),
+}
+
+impl Outcome {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Outcome::Pass(_) | Outcome::Skipped(_))
+    }
+
+    /// How the outcome is counted in the [`summary`].
+    fn status(&self) -> &'static str {
+        match self {
+            Outcome::Pass(_) => "passed",
+            Outcome::Fail { .. } => "failed",
+            Outcome::Timeout(_) => "timed out",
+            Outcome::Error(_) => "failed to run",
+            Outcome::Missing => "have no expected answer",
+            Outcome::Skipped(_) => "skipped",
+            Outcome::NotRun(_) => "have no solver",
+        }
+    }
+}
+
+/// Counts the outcomes by status, like `44 passed, 2 have no solver`, in the order of the first
+/// outcome with each status.
+pub fn summary<'a>(outcomes: impl IntoIterator<Item = &'a Outcome>) -> String {
+    let mut counts = Vec::<(&str, usize)>::new();
+    for outcome in outcomes {
+        let status = outcome.status();
+        match counts.iter_mut().find(|(counted, _)| *counted == status) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((status, 1)),
+        }
+    }
+    counts
+        .iter()
+        .map(|(status, count)| format!("{} {}", count, status))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Pass(report) => write!(f, "pass ({})", format_time(report)),
+            Outcome::Fail { expected, report } => write!(
+                f,
+                "FAIL expected {}, got {} ({})",
+                expected,
+                report.answer,
+                format_time(report)
+            ),
+            Outcome::Timeout(timeout) => write!(f, "TIMEOUT after {}s", timeout.as_secs_f64()),
+            Outcome::Error(error) => write!(f, "ERROR {}", error.replace('\n', "\n    ")),
+            Outcome::Missing => write!(f, "MISSING no expected answer"),
+            Outcome::Skipped(reason) => write!(f, "skipped, {}", reason),
+            Outcome::NotRun(reason) => write!(f, "NOT RUN {}", reason),
+        }
+    }
+}
+
+fn format_time(report: &Report) -> String {
+    format!("{:.1} ms", report.parse_ms + report.solve_ms)
+}
+
+/// Verifies both parts of each of `days`, printing an outcome per part and a summary. Fails
+/// unless every part passes or is skipped.
+pub fn verify_days(answers: &Answers, days: &[u8], timeout: Duration) -> Result<(), String> {
+    let mut outcomes = vec![];
+    for day in days {
+        // the last day has a single part
+        let parts = Part::ALL
+            .into_iter()
+            .filter(|part| *day < 25 || *part == Part::One);
+        for part in parts {
+            let outcome = match solvers::solver(*day) {
+                Some(_) => verify(*day, part, answers.get(*day, part), timeout),
+                None => Outcome::NotRun(solvers::no_solver(*day)),
+            };
+            println!("day {:02} part {}: {}", day, part, outcome);
+            outcomes.push(outcome);
+        }
+    }
+    println!("{}", summary(&outcomes));
+    match outcomes.iter().filter(|outcome| !outcome.is_ok()).count() {
+        0 => Ok(()),
+        failures => Err(format!("{} part(s) did not pass", failures)),
+    }
+}
+
+/// Runs a single part in a child `aoc run` process, so that it can be killed once `timeout` is
+/// up, and compares its answer with `expected`.
+pub fn verify(day: u8, part: Part, expected: Option<&Expected>, timeout: Duration) -> Outcome {
+    let expected = match expected {
+        Some(Expected::Answer(answer)) => answer.as_str(),
+        Some(Expected::Skip { skip }) => return Outcome::Skipped(skip.clone()),
+        None => return Outcome::Missing,
+    };
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(error) => return Outcome::Error(format!("Failed to find the aoc binary: {}", error)),
+    };
+    let child = Command::new(exe)
+        .args(["run", "--format", "json", "--diagnostics", "none"])
+        .args(["--day", &day.to_string(), "--part", &part.to_string()])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(error) => return Outcome::Error(format!("Failed to start the solution: {}", error)),
+    };
+    // the pipes are read while the child runs, it would block on a full pipe otherwise
+    let stdout = read_to_end(child.stdout.take());
+    let stderr = read_to_end(child.stderr.take());
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if start.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Outcome::Timeout(timeout);
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(10)),
+            Err(error) => return Outcome::Error(error.to_string()),
+        }
+    };
+    let (stdout, stderr) = (joined(stdout), joined(stderr));
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&stderr);
+        let error = stderr.trim().trim_start_matches("error: ");
+        return Outcome::Error(error.to_string());
+    }
+    match serde_json::from_slice::<Report>(&stdout) {
+        Ok(report) if report.answer == expected => Outcome::Pass(report),
+        Ok(report) => Outcome::Fail {
+            expected: expected.to_string(),
+            report,
+        },
+        Err(error) => Outcome::Error(format!("Unexpected output of the solution: {}", error)),
+    }
+}
+
+/// Reads all of `pipe` on a thread of its own.
+fn read_to_end(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut bytes = vec![];
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        bytes
+    })
+}
+
+fn joined(reader: JoinHandle<Vec<u8>>) -> Vec<u8> {
+    reader.join().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_works() {
+        let answers = Answers::parse(
+            r#"
+[day-01]
+part-1 = "142"
+part-2 = "281"
+
+[day-25]
+part-1 = "54"
+"#,
+        )
+        .unwrap();
+        let answer = |answer: &str| Some(Expected::Answer(answer.to_string()));
+        assert_eq!(answers.get(1, Part::One), answer("142").as_ref());
+        assert_eq!(answers.get(1, Part::Two), answer("281").as_ref());
+        assert_eq!(answers.get(25, Part::Two), None);
+        assert_eq!(answers.get(2, Part::One), None);
+    }
+
+    #[test]
+    fn invalid_keys_are_rejected() {
+        assert!(Answers::parse("[day-x]\npart-1 = \"1\"").is_err());
+        assert!(Answers::parse("[day-01]\npart-3 = \"1\"").is_err());
+        assert!(Answers::parse("[day-01]\npart-1 = 1").is_err());
+    }
+
+    #[test]
+    fn summary_counts_every_status() {
+        let report = Report {
+            day: 1,
+            part: 1,
+            answer: "142".to_string(),
+            parse_ms: 0.0,
+            solve_ms: 0.0,
+        };
+        let outcomes = [
+            Outcome::Pass(report.clone()),
+            Outcome::Missing,
+            Outcome::Pass(report),
+            Outcome::NotRun("no solver".to_string()),
+            Outcome::NotRun("no solver".to_string()),
+        ];
+        assert_eq!(
+            summary(&outcomes),
+            "2 passed, 1 have no expected answer, 2 have no solver"
+        );
+        assert!(!Outcome::Missing.is_ok());
+        assert!(!Outcome::NotRun("no solver".to_string()).is_ok());
+    }
+
+    #[test]
+    fn skipped_parts_pass_and_unanswered_parts_fail() {
+        // neither a skipped nor an unanswered part runs the solution
+        let answers = Answers::parse(
+            r#"
+[day-20]
+part-1 = { skip = "no answer yet" }
+part-2 = { skip = "read off the graph" }
+
+[day-22]
+part-1 = { skip = "slow" }
+"#,
+        )
+        .unwrap();
+        let timeout = Duration::from_secs(1);
+        assert_eq!(verify_days(&answers, &[20], timeout), Ok(()));
+        assert_eq!(
+            verify_days(&answers, &[20, 22], timeout),
+            Err("1 part(s) did not pass".to_string())
+        );
+    }
+}
@@ -0,0 +1,36 @@
+//! Criterion glue shared by the `benches/` of the day crates.
+
+use std::{fs, hint::black_box, time::Duration};
+
+use criterion::{Criterion, SamplingMode};
+use solver::{Part, Solver};
+
+/// Benchmarks parsing the real `input.txt` of the day and solving it for each of `parts`, as
+/// `day-NN/parse-N` and `day-NN/part-N`. Some days parse the parts differently, so each part is
+/// parsed on its own, and the part benchmarks only measure solving.
+///
+/// Benchmarks run with the day's crate as working directory, so `input.txt` is the real input.
+pub fn bench_day(c: &mut Criterion, solver: &dyn Solver, parts: &[Part]) {
+    let input = fs::read_to_string("input.txt").expect("the day's input.txt");
+    let mut group = c.benchmark_group(format!("day-{:02}", solver.day()));
+    // some parts take seconds, flat sampling keeps those to the minimum of 10 runs
+    group.sampling_mode(SamplingMode::Flat);
+    group.sample_size(10);
+    for part in parts {
+        group.bench_function(format!("parse-{}", part), |b| {
+            b.iter_custom(|iterations| {
+                (0..iterations)
+                    .map(|_| solver.parse(*part, black_box(&input)).unwrap())
+                    .sum::<Duration>()
+            })
+        });
+        group.bench_function(format!("part-{}", part), |b| {
+            b.iter_custom(|iterations| {
+                (0..iterations)
+                    .map(|_| solver.run(*part, black_box(&input)).unwrap().solve_time)
+                    .sum::<Duration>()
+            })
+        });
+    }
+    group.finish();
+}
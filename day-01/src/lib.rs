@@ -1,51 +1,207 @@
-pub fn process_part_1(input: &str) -> String {
-    let sum: u32 = input
-        .lines()
-        .map(|line| {
-            let numbers: Vec<u32> = line.chars().filter_map(|char| char.to_digit(10)).collect();
-            numbers.first().unwrap_or(&0) * 10 + numbers.last().unwrap_or(&0)
-        })
-        .sum();
-    sum.to_string()
+use std::io::BufRead;
+
+use solver::stream::for_each_record;
+use solver::{params, ParseError, Part, Run, Solver, StreamError, StreamSolver};
+
+mod vocabulary;
+
+pub use vocabulary::{concatenate, Language, Token, Vocabulary, MAX_VALUE};
+
+pub struct Day01;
+
+/// Language of the number words of part 2, more words on top of it, and whether a line without
+/// a digit is an error instead of counting as 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Day01Params {
+    pub language: Language,
+    /// Words and their values, written `zero:0,first:1`. They take the place of a word of the
+    /// language with the same spelling.
+    pub words: Vec<(String, u32)>,
+    pub strict: bool,
+}
+
+impl Default for Day01Params {
+    fn default() -> Self {
+        Self {
+            language: Language::English,
+            words: vec![],
+            strict: false,
+        }
+    }
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let sum: u32 = input
-        .lines()
-        .map(|line| {
-            let numbers = parse_line(line);
-            numbers.first().unwrap_or(&0) * 10 + numbers.last().unwrap_or(&0)
+impl params::Params for Day01Params {
+    const NAMES: &'static [&'static str] = &["language", "words", "strict"];
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "language" => self.language = params::parse(name, value)?,
+            "words" => self.words = parse_words(value)?,
+            _ => self.strict = params::parse(name, value)?,
+        }
+        Ok(())
+    }
+}
+
+/// Parses `word:value` pairs separated by commas, nothing for no words.
+fn parse_words(value: &str) -> Result<Vec<(String, u32)>, String> {
+    let invalid = |reason: &str| format!("Invalid value {} of words: {}", value, reason);
+    value
+        .split(',')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (word, number) = pair
+                .split_once(':')
+                .ok_or_else(|| invalid("expected word:value pairs"))?;
+            if word.is_empty() {
+                return Err(invalid("expected a word before every value"));
+            }
+            match number.parse() {
+                Ok(number) if number <= MAX_VALUE => Ok((word.to_string(), number)),
+                _ => Err(invalid("expected values from 0 to 999999999")),
+            }
         })
-        .sum();
-    sum.to_string()
-}
-
-const DIGITS: [&str; 9] = [
-    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-];
-
-fn parse_line(line: &str) -> Vec<u32> {
-    let mut numbers = vec![];
-    for i in 0..line.len() {
-        let slice = line.get(i..);
-        if let Some(slice) = slice {
-            let digit = slice
-                .get(0..1)
-                .map(|digit| digit.parse::<u32>().ok())
-                .flatten();
-            if let Some(digit) = digit {
-                numbers.push(digit);
-            } else {
-                DIGITS.iter().enumerate().for_each(|(index, digit)| {
-                    if slice.starts_with(digit) {
-                        numbers.push((index + 1) as u32);
-                        return;
-                    }
-                });
+        .collect()
+}
+
+/// The first and the last word found on a line, `line` counts from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineValue {
+    pub line: usize,
+    pub first: Token,
+    pub last: Token,
+}
+
+impl LineValue {
+    /// The digits of the first word followed by the digits of the last one.
+    pub fn value(&self) -> u64 {
+        concatenate(self.first.value, self.last.value)
+    }
+}
+
+/// What was found in the calibration document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Extraction {
+    pub values: Vec<LineValue>,
+    /// Lines without any word, which count as 0.
+    pub missing: Vec<usize>,
+}
+
+impl Extraction {
+    pub fn sum(&self) -> u128 {
+        self.values
+            .iter()
+            .map(|value| u128::from(value.value()))
+            .sum()
+    }
+
+    /// Fails at the first line without a word.
+    pub fn check(&self) -> Result<(), ParseError> {
+        match self.missing.first() {
+            Some(line) => Err(missing(*line)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Finds the first and the last word of every line of `input`, empty lines at the end are
+/// ignored.
+pub fn extract(input: &str, vocabulary: &Vocabulary) -> Extraction {
+    let mut extraction = Extraction::default();
+    for (i, line) in input.trim_end_matches(['\n', '\r']).lines().enumerate() {
+        match vocabulary.first_and_last(line) {
+            Some((first, last)) => extraction.values.push(LineValue {
+                line: i + 1,
+                first,
+                last,
+            }),
+            None => extraction.missing.push(i + 1),
+        }
+    }
+    extraction
+}
+
+impl Solver for Day01 {
+    fn day(&self) -> u8 {
+        1
+    }
+
+    fn check_params(&self, overrides: &[(String, String)]) -> Result<(), String> {
+        params::with_overrides::<Day01Params>(overrides).map(|_| ())
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let params = params::current::<Day01Params>();
+        let vocabulary = vocabulary(part, &params);
+        // the lines are scanned for words when solving, strict mode only checks that each has one
+        let parse = || {
+            if params.strict {
+                check_lines(input, &vocabulary)?;
             }
+            Ok(input)
+        };
+        Run::timed(parse, |input| extract(input, &vocabulary).sum().to_string())
+    }
+}
+
+impl StreamSolver for Day01 {
+    fn solve_stream(&self, part: Part, reader: &mut dyn BufRead) -> Result<String, StreamError> {
+        let params = params::current::<Day01Params>();
+        let vocabulary = vocabulary(part, &params);
+        let mut sum = 0_u128;
+        for_each_record(reader, b'\n', |line| {
+            match vocabulary.first_and_last(line) {
+                Some((first, last)) => sum += u128::from(concatenate(first.value, last.value)),
+                None if params.strict => return Err(missing(1)),
+                None => {}
+            }
+            Ok(())
+        })?;
+        Ok(sum.to_string())
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day01.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day01.solve(Part::Two, input)
+}
+
+fn vocabulary(part: Part, params: &Day01Params) -> Vocabulary {
+    match part {
+        Part::One => Vocabulary::digits(),
+        Part::Two => {
+            let language = params
+                .language
+                .words()
+                .iter()
+                .filter(|(word, _)| params.words.iter().all(|(added, _)| added != word));
+            let added = params
+                .words
+                .iter()
+                .map(|(word, value)| (word.as_str(), *value));
+            Vocabulary::with_words(&language.copied().chain(added).collect::<Vec<_>>())
         }
     }
-    return numbers;
+}
+
+/// Fails at the first line of `input` without a word, like [`Extraction::check`] without
+/// extracting the words.
+fn check_lines(input: &str, vocabulary: &Vocabulary) -> Result<(), ParseError> {
+    let lines = input.trim_end_matches(['\n', '\r']).lines();
+    match lines
+        .enumerate()
+        .find(|(_, line)| !vocabulary.has_word(line))
+    {
+        Some((i, _)) => Err(missing(i + 1)),
+        None => Ok(()),
+    }
+}
+
+fn missing(line: usize) -> ParseError {
+    ParseError::new(line, 1, "a line with a digit")
 }
 
 #[cfg(test)]
@@ -59,7 +215,7 @@ treb7uchet";
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT_1);
+        let result = process_part_1(INPUT_1).unwrap();
         assert_eq!(result, "142");
     }
 
@@ -73,7 +229,71 @@ zoneight234
 
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT_2);
+        let result = process_part_2(INPUT_2).unwrap();
         assert_eq!(result, "281");
     }
+
+    #[test]
+    fn part_2_in_german() {
+        let vocabulary = Vocabulary::with_words(Language::German.words());
+        let extraction = extract("zweiundvierzig\nachtzehn\n", &vocabulary);
+        assert_eq!(extraction.sum(), 24 + 88);
+    }
+
+    #[test]
+    fn words_can_be_added() {
+        let overrides = [("words", "zero:0,first:1,seventeen:17,six:60")]
+            .map(|(name, value)| (name.to_string(), value.to_string()));
+        let params = params::with_overrides::<Day01Params>(&overrides).unwrap();
+        let vocabulary = vocabulary(Part::Two, &params);
+        let extraction = extract("zerotwo\nfirstsix\nseventeen\n", &vocabulary);
+        assert_eq!(extraction.sum(), 2 + 160 + 1717);
+        for words in ["zero", "zero:", ":0", "zero:-1", "zero:1000000000"] {
+            let overrides = [("words".to_string(), words.to_string())];
+            assert!(params::with_overrides::<Day01Params>(&overrides).is_err());
+        }
+    }
+
+    #[test]
+    fn lines_without_digits_are_reported() {
+        let extraction = extract("ab1c\n\nxyz\né2ü9\n\n", &Vocabulary::digits());
+        let found = extraction
+            .values
+            .iter()
+            .map(|value| {
+                (
+                    value.line,
+                    value.first.start,
+                    value.last.start,
+                    value.value(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(found, [(1, 2, 2, 11), (4, 2, 5, 29)]);
+        assert_eq!(extraction.missing, [2, 3]);
+        assert_eq!(extraction.sum(), 40);
+        assert_eq!(
+            extraction.check(),
+            Err(ParseError::new(2, 1, "a line with a digit"))
+        );
+        assert_eq!(extract(INPUT_1, &Vocabulary::digits()).check(), Ok(()));
+    }
+
+    #[test]
+    fn strict_mode_rejects_lines_without_digits() {
+        let vocabulary = Vocabulary::digits();
+        assert_eq!(
+            check_lines("ab1c\n\nxyz\n", &vocabulary),
+            Err(ParseError::new(2, 1, "a line with a digit"))
+        );
+        assert_eq!(check_lines(INPUT_1, &vocabulary), Ok(()));
+    }
+
+    #[test]
+    fn solve_stream_works() {
+        let result = Day01.solve_stream(Part::One, &mut INPUT_1.as_bytes());
+        assert_eq!(result.unwrap(), "142");
+        let result = Day01.solve_stream(Part::Two, &mut INPUT_2.as_bytes());
+        assert_eq!(result.unwrap(), "281");
+    }
 }
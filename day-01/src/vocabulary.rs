@@ -0,0 +1,235 @@
+//! The words that count as digits in the calibration document, found with an Aho-Corasick
+//! automaton so that the lines are scanned once however many words there are.
+
+use std::{
+    cmp::{self, Reverse},
+    str::FromStr,
+};
+
+use aho_corasick::AhoCorasick;
+
+/// Number words of part 2.
+pub const ENGLISH: [(&str, u32); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+pub const GERMAN: [(&str, u32); 10] = [
+    ("null", 0),
+    ("eins", 1),
+    ("zwei", 2),
+    ("drei", 3),
+    ("vier", 4),
+    ("fünf", 5),
+    ("sechs", 6),
+    ("sieben", 7),
+    ("acht", 8),
+    ("neun", 9),
+];
+
+pub const FRENCH: [(&str, u32); 10] = [
+    ("zéro", 0),
+    ("un", 1),
+    ("deux", 2),
+    ("trois", 3),
+    ("quatre", 4),
+    ("cinq", 5),
+    ("six", 6),
+    ("sept", 7),
+    ("huit", 8),
+    ("neuf", 9),
+];
+
+/// The largest value of a word, so that two of them written one after the other fit a `u64`.
+pub const MAX_VALUE: u32 = 999_999_999;
+
+/// Languages of the number words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    German,
+    French,
+}
+
+impl Language {
+    pub fn words(self) -> &'static [(&'static str, u32)] {
+        match self {
+            Self::English => &ENGLISH,
+            Self::German => &GERMAN,
+            Self::French => &FRENCH,
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "english" => Ok(Self::English),
+            "german" => Ok(Self::German),
+            "french" => Ok(Self::French),
+            _ => Err("expected english, german or french".to_string()),
+        }
+    }
+}
+
+/// A word found in a line, with the byte range it takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub start: usize,
+    pub end: usize,
+    pub value: u32,
+}
+
+/// Words and the values they stand for. Words may overlap in a line, like the "eight" and "two"
+/// of "eightwo", and all of them are found.
+#[derive(Debug, Clone)]
+pub struct Vocabulary {
+    automaton: AhoCorasick,
+    values: Vec<u32>,
+}
+
+impl Vocabulary {
+    /// Panics if there are no words, one of them is empty or its value is above [`MAX_VALUE`].
+    pub fn new<'a>(words: impl IntoIterator<Item = (&'a str, u32)>) -> Self {
+        let (words, values): (Vec<_>, Vec<_>) = words.into_iter().unzip();
+        assert!(
+            !words.is_empty() && words.iter().all(|word| !word.is_empty()),
+            "a vocabulary needs words"
+        );
+        assert!(
+            values.iter().all(|value| *value <= MAX_VALUE),
+            "values have at most nine digits"
+        );
+        let automaton = AhoCorasick::new(words).expect("the words fit an automaton");
+        Self { automaton, values }
+    }
+
+    /// The digits `0` to `9`, like part 1.
+    pub fn digits() -> Self {
+        Self::new(DIGITS)
+    }
+
+    /// The digits and the `words` spelling numbers, like part 2 with [`ENGLISH`].
+    pub fn with_words(words: &[(&str, u32)]) -> Self {
+        Self::new(DIGITS.into_iter().chain(words.iter().copied()))
+    }
+
+    /// All the words in `line` ordered by where they start, longer words first.
+    pub fn tokens(&self, line: &str) -> Vec<Token> {
+        let mut tokens = self.find(line).collect::<Vec<_>>();
+        tokens.sort_unstable_by_key(|token| (token.start, Reverse(token.end)));
+        tokens
+    }
+
+    /// Whether there is a word in `line`.
+    pub fn has_word(&self, line: &str) -> bool {
+        self.automaton.is_match(line)
+    }
+
+    /// The first and the last word of `line`, the longest one where several start at the same
+    /// place.
+    pub fn first_and_last(&self, line: &str) -> Option<(Token, Token)> {
+        let mut tokens = self.find(line);
+        let token = tokens.next()?;
+        Some(tokens.fold((token, token), |(first, last), token| {
+            (
+                cmp::min_by_key(first, token, |token| (token.start, Reverse(token.end))),
+                cmp::max_by_key(last, token, |token| (token.start, token.end)),
+            )
+        }))
+    }
+
+    /// The digits of the first word followed by the digits of the last one, 0 without words.
+    pub fn calibration_value(&self, line: &str) -> u64 {
+        self.first_and_last(line)
+            .map_or(0, |(first, last)| concatenate(first.value, last.value))
+    }
+
+    /// The words in `line` in the order the automaton finds them, which is by where they end.
+    fn find<'a>(&'a self, line: &'a str) -> impl Iterator<Item = Token> + 'a {
+        self.automaton
+            .find_overlapping_iter(line)
+            .map(|found| Token {
+                start: found.start(),
+                end: found.end(),
+                value: self.values[found.pattern()],
+            })
+    }
+}
+
+/// The number written with the digits of `first` followed by the digits of `last`, so 17 and 10
+/// make 1710 and single digits work like the tens and the ones.
+pub fn concatenate(first: u32, last: u32) -> u64 {
+    let digits = last.checked_ilog10().map_or(1, |log| log + 1);
+    u64::from(first) * 10_u64.pow(digits) + u64::from(last)
+}
+
+const DIGITS: [(&str, u32); 10] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn overlapping_words_are_found() {
+        let vocabulary = Vocabulary::with_words(&ENGLISH);
+        let values = vocabulary
+            .tokens("eightwone3")
+            .iter()
+            .map(|token| (token.start, token.value))
+            .collect::<Vec<_>>();
+        assert_eq!(values, [(0, 8), (4, 2), (6, 1), (9, 3)]);
+    }
+
+    #[test]
+    fn longest_word_wins_at_the_same_start() {
+        let words = [("seven", 7), ("seventeen", 17), ("teen", 10)];
+        let vocabulary = Vocabulary::with_words(&words);
+        let (first, last) = vocabulary.first_and_last("seventeen").unwrap();
+        assert_eq!((first.value, last.value), (17, 10));
+        assert_eq!(vocabulary.calibration_value("seventeen"), 1710);
+    }
+
+    #[rstest]
+    #[case(1, 2, 12)]
+    #[case(0, 0, 0)]
+    #[case(3, 0, 30)]
+    #[case(17, 10, 1710)]
+    #[case(100, 7, 1007)]
+    #[case(MAX_VALUE, MAX_VALUE, 999_999_999_999_999_999)]
+    fn values_are_concatenated(#[case] first: u32, #[case] last: u32, #[case] expected: u64) {
+        assert_eq!(concatenate(first, last), expected);
+    }
+
+    #[rstest]
+    #[case(&GERMAN, "fünfundzwanzig", 55)]
+    #[case(&GERMAN, "ünf3null", 30)]
+    #[case(&FRENCH, "zérotrois", 3)]
+    #[case(&FRENCH, "ñ🦀huit", 88)]
+    #[case(&ENGLISH, "élan", 0)]
+    fn utf8_lines_work(#[case] words: &[(&str, u32)], #[case] line: &str, #[case] expected: u64) {
+        let vocabulary = Vocabulary::with_words(words);
+        assert_eq!(vocabulary.calibration_value(line), expected);
+    }
+}
@@ -1,108 +1,252 @@
+use std::{collections::BTreeMap, str::FromStr};
+
 use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{digit1, line_ending, space1},
+    character::complete::{self, alpha1, line_ending, space1},
+    error::context,
     multi::separated_list1,
     sequence::{separated_pair, tuple},
-    IResult,
 };
+use solver::parse::{parse_all, tag, IResult};
+use solver::{params, ParseError, Part, Run, Solver};
 
-pub fn process_part_1(input: &str) -> String {
-    let (_, games) = parse_input(input).expect("Failed to parse input");
-    let max_red = 12;
-    let max_green = 13;
-    let max_blue = 14;
-    let sum_ids: u32 = games
-        .iter()
-        .filter(|game| {
-            game.rounds.iter().all(|round| {
-                round.red <= max_red && round.green <= max_green && round.blue <= max_blue
-            })
-        })
+pub struct Day02;
+
+/// Cubes of each colour in the bag for part 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Day02Params {
+    pub red: u32,
+    pub green: u32,
+    pub blue: u32,
+}
+
+impl Default for Day02Params {
+    fn default() -> Self {
+        Self {
+            red: 12,
+            green: 13,
+            blue: 14,
+        }
+    }
+}
+
+impl params::Params for Day02Params {
+    const NAMES: &'static [&'static str] = &["red", "green", "blue"];
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let amount = params::parse(name, value)?;
+        match name {
+            "red" => self.red = amount,
+            "green" => self.green = amount,
+            _ => self.blue = amount,
+        }
+        Ok(())
+    }
+}
+
+impl Day02Params {
+    pub fn cubes(&self) -> Cubes {
+        Cubes::from_iter([
+            ("red", self.red),
+            ("green", self.green),
+            ("blue", self.blue),
+        ])
+    }
+}
+
+impl Solver for Day02 {
+    fn day(&self) -> u8 {
+        2
+    }
+
+    fn check_params(&self, overrides: &[(String, String)]) -> Result<(), String> {
+        params::with_overrides::<Day02Params>(overrides).map(|_| ())
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || parse_all(input, parse_input);
+        match part {
+            Part::One => {
+                let bag = params::current();
+                Run::timed(parse, |games| part_1(games, bag))
+            }
+            Part::Two => Run::timed(parse, part_2),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day02.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day02.solve(Part::Two, input)
+}
+
+fn part_1(games: Vec<Game>, bag: Day02Params) -> String {
+    let sum_ids: u32 = possible_games(&games, &bag.cubes())
         .map(|game| game.id)
         .sum();
     sum_ids.to_string()
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let (_, games) = parse_input(input).expect("Failed to parse input");
-    let sum_power: u32 = games
+fn part_2(games: Vec<Game>) -> String {
+    let sum_power: u64 = games
         .iter()
-        .map(|game| {
-            let mut max_red = 0;
-            let mut max_green = 0;
-            let mut max_blue = 0;
-            game.rounds.iter().for_each(|round| {
-                max_red = max_red.max(round.red);
-                max_green = max_green.max(round.green);
-                max_blue = max_blue.max(round.blue);
-            });
-            max_red * max_green * max_blue
-        })
+        .map(|game| game.minimum_bag().power(&["red", "green", "blue"]))
         .sum();
     sum_power.to_string()
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Game>> {
-    separated_list1(line_ending, parse_game)(input)
+/// Counts of cubes by color, like the ones shown in a round or held in a bag.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cubes(BTreeMap<String, u32>);
+
+impl Cubes {
+    /// Cubes of `color`, 0 for colors there are none of.
+    pub fn get(&self, color: &str) -> u32 {
+        self.0.get(color).copied().unwrap_or_default()
+    }
+
+    /// The colors in alphabetical order with their counts.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.0.iter().map(|(color, count)| (color.as_str(), *count))
+    }
+
+    /// Whether `bag` has at least as many cubes of every color.
+    pub fn fit_in(&self, bag: &Cubes) -> bool {
+        self.iter().all(|(color, count)| count <= bag.get(color))
+    }
+
+    /// The product of the counts of `colors`.
+    pub fn power(&self, colors: &[&str]) -> u64 {
+        colors.iter().fold(1, |power, color| {
+            power.saturating_mul(self.get(color).into())
+        })
+    }
 }
 
-fn parse_game(input: &str) -> IResult<&str, Game> {
-    let (input, ((_, game_id), rounds)) =
-        separated_pair(tuple((tag("Game "), digit1)), tag(": "), parse_rounds)(input)?;
-    Ok((
-        input,
-        Game {
-            id: game_id
-                .parse()
-                .unwrap_or_else(|_| panic!("Invalid game id: {}", game_id)),
-            rounds,
-        },
-    ))
+/// Adds up the counts of a color that is given more than once.
+impl<S: Into<String>> FromIterator<(S, u32)> for Cubes {
+    fn from_iter<I: IntoIterator<Item = (S, u32)>>(iter: I) -> Self {
+        let mut cubes = BTreeMap::new();
+        for (color, count) in iter {
+            let total: &mut u32 = cubes.entry(color.into()).or_default();
+            *total = total.saturating_add(count);
+        }
+        Self(cubes)
+    }
+}
+
+/// Parses cubes written like a round, `12 red, 13 green, 14 blue`.
+impl FromStr for Cubes {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse_all(input, parse_cubes)
+    }
 }
 
-fn parse_rounds(input: &str) -> IResult<&str, Vec<Round>> {
-    separated_list1(tag("; "), parse_round)(input)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub rounds: Vec<Cubes>,
 }
 
-fn parse_round(input: &str) -> IResult<&str, Round> {
-    let (input, round_bits) = separated_list1(
-        tag(", "),
-        tuple((digit1, space1, alt((tag("red"), tag("green"), tag("blue"))))),
-    )(input)?;
-    let mut red = 0;
-    let mut green = 0;
-    let mut blue = 0;
-    for (amount, _, color) in round_bits {
-        let amount: u32 = amount
-            .parse()
-            .unwrap_or_else(|_| panic!("Invalid amount: {}", amount));
-        match color {
-            "red" => red += amount,
-            "green" => green += amount,
-            "blue" => blue += amount,
-            _ => panic!("Invalid color: {}", color),
+impl Game {
+    /// Whether all the rounds could be drawn from `bag`.
+    pub fn is_possible(&self, bag: &Cubes) -> bool {
+        self.rounds.iter().all(|round| round.fit_in(bag))
+    }
+
+    /// The fewest cubes of every color shown that make the game possible.
+    pub fn minimum_bag(&self) -> Cubes {
+        let mut bag = Cubes::default();
+        for (color, count) in self.rounds.iter().flat_map(Cubes::iter) {
+            let most = bag.0.entry(color.to_string()).or_default();
+            *most = count.max(*most);
         }
+        bag
+    }
+}
+
+/// How often a color is shown over all the rounds of the games.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ColorStats {
+    /// Rounds that show the color.
+    pub rounds: usize,
+    /// Cubes shown over all rounds.
+    pub total: u64,
+    /// Most cubes shown in a round.
+    pub max: u32,
+}
+
+impl ColorStats {
+    /// Cubes per round that shows the color.
+    pub fn mean(&self) -> f64 {
+        self.total as f64 / self.rounds as f64
+    }
+}
+
+pub fn parse_games(input: &str) -> Result<Vec<Game>, ParseError> {
+    parse_all(input, parse_input)
+}
+
+/// The games that could be played with `bag`.
+pub fn possible_games<'a>(games: &'a [Game], bag: &'a Cubes) -> impl Iterator<Item = &'a Game> {
+    games.iter().filter(|game| game.is_possible(bag))
+}
+
+/// Statistics of every color shown in the games.
+pub fn color_stats(games: &[Game]) -> BTreeMap<String, ColorStats> {
+    let mut stats = BTreeMap::<String, ColorStats>::new();
+    for (color, count) in games
+        .iter()
+        .flat_map(|game| &game.rounds)
+        .flat_map(Cubes::iter)
+    {
+        let stats = stats.entry(color.to_string()).or_default();
+        stats.rounds += 1;
+        stats.total += u64::from(count);
+        stats.max = stats.max.max(count);
     }
-    Ok((input, Round { red, green, blue }))
+    stats
 }
 
-#[derive(Debug)]
-struct Game {
-    id: u32,
-    rounds: Vec<Round>,
+fn parse_input(input: &str) -> IResult<&str, Vec<Game>> {
+    separated_list1(line_ending, parse_game)(input)
+}
+
+fn parse_game(input: &str) -> IResult<&str, Game> {
+    let (input, ((_, id), rounds)) = separated_pair(
+        tuple((tag("Game "), complete::u32)),
+        tag(": "),
+        parse_rounds,
+    )(input)?;
+    Ok((input, Game { id, rounds }))
 }
 
-#[derive(Debug)]
-struct Round {
-    red: u32,
-    green: u32,
-    blue: u32,
+fn parse_rounds(input: &str) -> IResult<&str, Vec<Cubes>> {
+    separated_list1(tag("; "), parse_cubes)(input)
+}
+
+fn parse_cubes(input: &str) -> IResult<&str, Cubes> {
+    let (input, cubes) = separated_list1(
+        tag(", "),
+        separated_pair(complete::u32, space1, context("of a color", alpha1)),
+    )(input)?;
+    Ok((
+        input,
+        cubes
+            .into_iter()
+            .map(|(count, color)| (color, count))
+            .collect(),
+    ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     const INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
 Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
@@ -112,13 +256,126 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "8");
     }
 
+    #[test]
+    fn part_1_with_bigger_bag() {
+        let games = parse_all(INPUT, parse_input).unwrap();
+        let bag = Day02Params {
+            red: 20,
+            ..Day02Params::default()
+        };
+        assert_eq!(part_1(games, bag), "11");
+    }
+
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "2286");
     }
+
+    #[test]
+    fn missing_color_is_reported() {
+        let result = process_part_1("Game 1: 3 blue, 4 red\nGame 2: 1 blue; 3 ");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "expected letters of a color on line 2, column 19"
+        );
+    }
+
+    #[test]
+    fn games_can_be_queried() {
+        let games =
+            parse_games("Game 1: 3 blue, 4 red; 2 purple\nGame 2: 1 blue; 5 purple, 1 purple")
+                .unwrap();
+        assert_eq!(games[1].rounds[1], "6 purple".parse().unwrap());
+        let bag = "4 red, 3 blue, 2 purple".parse().unwrap();
+        let ids = possible_games(&games, &bag)
+            .map(|game| game.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [1]);
+        assert_eq!(
+            games[0].minimum_bag(),
+            Cubes::from_iter([("blue", 3), ("red", 4), ("purple", 2)])
+        );
+        assert_eq!(games[0].minimum_bag().power(&["red", "green", "blue"]), 0);
+        assert_eq!(games[0].minimum_bag().power(&["red", "purple"]), 8);
+        let stats = color_stats(&games);
+        assert_eq!(
+            stats["purple"],
+            ColorStats {
+                rounds: 2,
+                total: 8,
+                max: 6
+            }
+        );
+        assert_eq!(stats["blue"].mean(), 2.0);
+        assert!(!stats.contains_key("green"));
+    }
+
+    /// Rounds of `[red, green, blue]` counts, a count of zero leaves the colour out.
+    fn games() -> impl Strategy<Value = Vec<Vec<[u32; 3]>>> {
+        let round = prop::array::uniform3(0..20u32)
+            .prop_filter("a round shows some cubes", |round| {
+                round.iter().any(|n| *n > 0)
+            });
+        prop::collection::vec(prop::collection::vec(round, 1..6), 1..10)
+    }
+
+    fn format_games(games: &[Vec<[u32; 3]>], colour_order: &[usize]) -> String {
+        games
+            .iter()
+            .enumerate()
+            .map(|(i, rounds)| {
+                let rounds = rounds
+                    .iter()
+                    .map(|round| {
+                        colour_order
+                            .iter()
+                            .filter(|colour| round[**colour] > 0)
+                            .map(|colour| {
+                                format!("{} {}", round[*colour], ["red", "green", "blue"][*colour])
+                            })
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                format!("Game {}: {}", i + 1, rounds)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    proptest! {
+        #[test]
+        fn answers_match_the_generated_games(
+            games in games(),
+            colour_order in Just(vec![0, 1, 2]).prop_shuffle()
+        ) {
+            let input = format_games(&games, &colour_order);
+            let possible = games
+                .iter()
+                .enumerate()
+                .filter(|(_, rounds)| {
+                    rounds
+                        .iter()
+                        .all(|round| round[0] <= 12 && round[1] <= 13 && round[2] <= 14)
+                })
+                .map(|(i, _)| i as u32 + 1)
+                .sum::<u32>();
+            let power = games
+                .iter()
+                .map(|rounds| {
+                    (0..3)
+                        .map(|colour| rounds.iter().map(|round| round[colour]).max().unwrap())
+                        .product::<u32>()
+                })
+                .sum::<u32>();
+            prop_assert_eq!(process_part_1(&input).unwrap(), possible.to_string());
+            prop_assert_eq!(process_part_2(&input).unwrap(), power.to_string());
+        }
+    }
 }
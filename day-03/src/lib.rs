@@ -1,128 +1,377 @@
-use std::ops::{Not, Range};
+use std::{
+    collections::{BTreeMap, HashSet},
+    str::FromStr,
+};
 
-pub fn process_part_1(input: &str) -> String {
-    let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-    let grid_numbers = get_grid_numbers(&grid);
-    grid_numbers
-        .iter()
-        .filter(|grid_number| grid_number.is_engine_part(&grid))
-        .map(|grid_number| grid_number.number)
-        .sum::<u32>()
+use grid::{Grid, Position};
+use solver::{params, ParseError, Part, Run, Solver};
+
+pub struct Day03;
+
+/// How the numbers are read, the puzzle reads unsigned numbers from left to right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Day03Params {
+    pub format: NumberFormat,
+}
+
+impl params::Params for Day03Params {
+    const NAMES: &'static [&'static str] = &["orientation", "signed"];
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "orientation" => self.format.orientation = params::parse(name, value)?,
+            _ => self.format.signed = params::parse(name, value)?,
+        }
+        Ok(())
+    }
+}
+
+impl Solver for Day03 {
+    fn day(&self) -> u8 {
+        3
+    }
+
+    fn check_params(&self, overrides: &[(String, String)]) -> Result<(), String> {
+        params::with_overrides::<Day03Params>(overrides).map(|_| ())
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || parse_input(input);
+        let Day03Params { format } = params::current();
+        match part {
+            Part::One => Run::timed(parse, |grid| part_1(grid, format)),
+            Part::Two => Run::timed(parse, |grid| part_2(grid, format)),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day03.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day03.solve(Part::Two, input)
+}
+
+fn part_1(grid: Grid<char>, format: NumberFormat) -> String {
+    Schematic::with_format(&grid, format)
+        .part_numbers()
+        .map(|number| number.value)
+        .sum::<i64>()
         .to_string()
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-    let grid_numbers = get_grid_numbers(&grid);
-    grid.iter()
-        .enumerate()
-        .flat_map(|(y, row)| {
-            row.iter()
-                .enumerate()
-                .filter(|(_, c)| **c == '*')
-                .map(|(x, _)| (x, y))
-                .collect::<Vec<_>>()
-        })
-        .filter_map(|(x, y)| {
-            let surrounding_numbers = grid_numbers
-                .iter()
-                .filter(|grid_number| grid_number.surrounding_indices(&grid).contains(&(x, y)))
-                .collect::<Vec<_>>();
-            if surrounding_numbers.len() == 2 {
-                Some(
-                    surrounding_numbers
-                        .iter()
-                        .map(|grid_number| grid_number.number)
-                        .product::<u32>(),
-                )
-            } else {
-                None
-            }
-        })
-        .sum::<u32>()
+fn part_2(grid: Grid<char>, format: NumberFormat) -> String {
+    Schematic::with_format(&grid, format)
+        .symbols('*')
+        .filter(|(_, numbers)| numbers.len() == 2)
+        .map(|(_, numbers)| numbers.iter().map(|number| number.value).product::<i64>())
+        .sum::<i64>()
         .to_string()
 }
 
-fn get_grid_numbers(grid: &[Vec<char>]) -> Vec<GridNumber> {
-    let mut grid_numbers = vec![];
-    grid.iter().enumerate().for_each(|(y, line)| {
-        let mut is_number = false;
-        let mut x_start = 0;
-        let mut number = 0;
-        line.iter().enumerate().for_each(|(x, c)| {
-            if c.is_ascii_digit() {
-                number = number * 10 + c.to_digit(10).unwrap();
-                if !is_number {
-                    is_number = true;
-                    x_start = x;
+fn parse_input(input: &str) -> Result<Grid<char>, ParseError> {
+    Grid::parse_with(input, "a character", Some)
+}
+
+/// A number of the schematic and the cells of its digits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchematicNumber {
+    pub value: i64,
+    /// The cells of the sign, if any, and of the digits.
+    pub cells: Vec<Position>,
+}
+
+/// The numbers of an engine schematic and the symbols next to them, everything that is neither a
+/// digit nor a `.` is a symbol.
+#[derive(Debug, Clone)]
+pub struct Schematic {
+    numbers: Vec<SchematicNumber>,
+    /// The symbols with the indices of the numbers next to them.
+    symbols: BTreeMap<Position, (char, Vec<usize>)>,
+    /// The positions of the symbols next to each number.
+    adjacent_symbols: Vec<Vec<Position>>,
+}
+
+impl Schematic {
+    /// Indexes the schematic with the numbers read like in the puzzle.
+    pub fn new(grid: &Grid<char>) -> Self {
+        Self::with_format(grid, NumberFormat::default())
+    }
+
+    /// Indexes the schematic with the numbers read in `format`.
+    pub fn with_format(grid: &Grid<char>, format: NumberFormat) -> Self {
+        Self::with_strategy(grid, &format)
+    }
+
+    /// Indexes the schematic by looking around every number the `strategy` finds once.
+    pub fn with_strategy(grid: &Grid<char>, strategy: &(impl NumberStrategy + ?Sized)) -> Self {
+        let numbers = strategy.find_numbers(grid);
+        let signs = numbers
+            .iter()
+            .flat_map(|number| &number.cells)
+            .filter(|cell| grid[**cell] == '-')
+            .collect::<HashSet<_>>();
+        let mut symbols = BTreeMap::<Position, (char, Vec<usize>)>::new();
+        let adjacent_symbols = numbers
+            .iter()
+            .enumerate()
+            .map(|(i, number)| {
+                let around = number
+                    .cells
+                    .iter()
+                    .flat_map(|cell| grid.neighbors_8(*cell))
+                    .filter(|(position, c)| is_symbol(**c) && !signs.contains(position))
+                    .collect::<BTreeMap<_, _>>();
+                for (position, c) in &around {
+                    symbols.entry(*position).or_insert((**c, vec![])).1.push(i);
                 }
-            } else if is_number {
-                grid_numbers.push(GridNumber {
-                    number,
-                    x_range: x_start..x,
-                    y,
-                });
-                is_number = false;
-                number = 0;
-            }
-        });
-        if is_number {
-            grid_numbers.push(GridNumber {
-                number,
-                x_range: x_start..line.len(),
-                y,
-            });
+                around.into_keys().collect()
+            })
+            .collect();
+        Self {
+            numbers,
+            symbols,
+            adjacent_symbols,
         }
-    });
-    grid_numbers
+    }
+
+    /// All the numbers from the top left, row by row.
+    pub fn numbers(&self) -> &[SchematicNumber] {
+        &self.numbers
+    }
+
+    /// The numbers next to a symbol.
+    pub fn part_numbers(&self) -> impl Iterator<Item = &SchematicNumber> {
+        self.numbers_next_to_symbols(|count| count > 0)
+    }
+
+    /// The numbers that are not next to any symbol.
+    pub fn unused_numbers(&self) -> impl Iterator<Item = &SchematicNumber> {
+        self.numbers_next_to_symbols(|count| count == 0)
+    }
+
+    /// The `symbol`s that are next to a number, with those numbers.
+    pub fn symbols(
+        &self,
+        symbol: char,
+    ) -> impl Iterator<Item = (Position, Vec<&SchematicNumber>)> + '_ {
+        self.symbols
+            .iter()
+            .filter(move |(_, (c, _))| *c == symbol)
+            .map(|(position, (_, numbers))| {
+                let numbers = numbers.iter().map(|i| &self.numbers[*i]).collect();
+                (*position, numbers)
+            })
+    }
+
+    /// The numbers next to the symbol at `position`, none if there is no symbol.
+    pub fn numbers_at(&self, position: Position) -> impl Iterator<Item = &SchematicNumber> {
+        self.symbols
+            .get(&position)
+            .into_iter()
+            .flat_map(|(_, numbers)| numbers.iter().map(|i| &self.numbers[*i]))
+    }
+
+    /// The numbers that are next to exactly `count` `symbol`s.
+    pub fn numbers_next_to(
+        &self,
+        symbol: char,
+        count: usize,
+    ) -> impl Iterator<Item = &SchematicNumber> {
+        self.numbers
+            .iter()
+            .zip(&self.adjacent_symbols)
+            .filter(move |(_, symbols)| {
+                let next_to = symbols
+                    .iter()
+                    .filter(|position| self.symbols[*position].0 == symbol)
+                    .count();
+                next_to == count
+            })
+            .map(|(number, _)| number)
+    }
+
+    fn numbers_next_to_symbols(
+        &self,
+        count: impl Fn(usize) -> bool,
+    ) -> impl Iterator<Item = &SchematicNumber> {
+        self.numbers
+            .iter()
+            .zip(&self.adjacent_symbols)
+            .filter(move |(_, symbols)| count(symbols.len()))
+            .map(|(number, _)| number)
+    }
 }
 
-#[derive(Debug)]
-struct GridNumber {
-    number: u32,
-    x_range: Range<usize>,
-    y: usize,
+/// Which way the digits of a number run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Orientation {
+    /// Left to right on a row, like the puzzle.
+    #[default]
+    Horizontal,
+    /// Top to bottom in a column.
+    Vertical,
+    /// Top to bottom along both diagonals, so down and to the right or down and to the left.
+    Diagonal,
+    /// Horizontal and vertical, where a digit that is not part of a longer number in either
+    /// direction is a number of its own.
+    Both,
+    /// Horizontal, vertical and diagonal, with the lone digits read like [`Orientation::Both`].
+    All,
 }
 
-impl GridNumber {
-    fn is_engine_part(&self, grid: &Vec<Vec<char>>) -> bool {
-        let surrounding_chars = self.surrounding_chars(grid);
-        surrounding_chars
-            .iter()
-            .all(|c| c.is_ascii_digit() || *c == '.')
-            .not()
+impl Orientation {
+    /// The steps from one digit to the next, in the order the numbers are found.
+    pub fn steps(self) -> &'static [(i32, i32)] {
+        match self {
+            Self::Horizontal => &[(1, 0)],
+            Self::Vertical => &[(0, 1)],
+            Self::Diagonal => &[(1, 1), (-1, 1)],
+            Self::Both => &[(1, 0), (0, 1)],
+            Self::All => &[(1, 0), (0, 1), (1, 1), (-1, 1)],
+        }
     }
+}
+
+impl FromStr for Orientation {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "horizontal" => Ok(Self::Horizontal),
+            "vertical" => Ok(Self::Vertical),
+            "diagonal" => Ok(Self::Diagonal),
+            "both" => Ok(Self::Both),
+            "all" => Ok(Self::All),
+            _ => Err("expected horizontal, vertical, diagonal, both or all".to_string()),
+        }
+    }
+}
 
-    fn surrounding_chars(&self, grid: &Vec<Vec<char>>) -> Vec<char> {
-        self.surrounding_indices(grid)
+/// How the numbers are read from the schematic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NumberFormat {
+    pub orientation: Orientation,
+    /// Whether a `-` right before the first digit makes the number negative. The `-` is then
+    /// part of the number rather than a symbol.
+    pub signed: bool,
+}
+
+/// Finds the numbers of a schematic, [`NumberFormat`] reads runs of digits and any closure
+/// taking the grid can stand in for it.
+pub trait NumberStrategy {
+    /// The numbers of `grid`. A `-` that is one of their cells is a sign rather than a symbol.
+    fn find_numbers(&self, grid: &Grid<char>) -> Vec<SchematicNumber>;
+}
+
+impl NumberStrategy for NumberFormat {
+    fn find_numbers(&self, grid: &Grid<char>) -> Vec<SchematicNumber> {
+        find_numbers(grid, *self)
+    }
+}
+
+impl<F> NumberStrategy for F
+where
+    F: Fn(&Grid<char>) -> Vec<SchematicNumber>,
+{
+    fn find_numbers(&self, grid: &Grid<char>) -> Vec<SchematicNumber> {
+        self(grid)
+    }
+}
+
+fn is_symbol(c: char) -> bool {
+    !c.is_ascii_digit() && c != '.'
+}
+
+/// The numbers of the schematic in the order of their first cells, row by row.
+fn find_numbers(grid: &Grid<char>, format: NumberFormat) -> Vec<SchematicNumber> {
+    let mut numbers = format
+        .orientation
+        .steps()
+        .iter()
+        .flat_map(|step| find_runs(grid, lines(grid, *step), format.signed))
+        .collect::<Vec<_>>();
+    let digits = |number: &SchematicNumber| {
+        number
+            .cells
             .iter()
-            .map(|(x, y)| grid[*y][*x])
+            .filter(|cell| grid[**cell].is_ascii_digit())
+            .copied()
             .collect::<Vec<_>>()
-    }
+    };
+    let in_longer = numbers
+        .iter()
+        .map(digits)
+        .filter(|digits| digits.len() > 1)
+        .flatten()
+        .collect::<HashSet<_>>();
+    // a lone digit is found once in each direction, the first direction comes first
+    let mut lone = HashSet::new();
+    numbers.retain(|number| match digits(number)[..] {
+        [digit] => !in_longer.contains(&digit) && lone.insert(digit),
+        _ => true,
+    });
+    numbers.sort_by_key(|number| (number.cells[0].y, number.cells[0].x));
+    numbers
+}
 
-    fn surrounding_indices(&self, grid: &Vec<Vec<char>>) -> Vec<(usize, usize)> {
-        let mut indices = vec![];
-        let sy = self.y as i32;
-        let sx_start = self.x_range.start as i32;
-        let sx_end = self.x_range.end as i32;
-        for y in sy - 1..=sy + 1 {
-            for x in sx_start - 1..=sx_end {
-                if y < 0 || y >= grid.len() as i32 || x < 0 || x >= grid[y as usize].len() as i32 {
-                    continue;
-                }
-                if y == sy && x >= sx_start && x < sx_end {
-                    continue;
-                }
-                indices.push((x as usize, y as usize));
+/// The lines of `grid` that go `step` by `step`, each from the edge it starts at.
+pub fn lines<T>(grid: &Grid<T>, (dx, dy): (i32, i32)) -> Vec<Vec<Position>> {
+    grid.positions()
+        .filter(|start| !grid.contains(Position::new(start.x - dx, start.y - dy)))
+        .map(|start| {
+            std::iter::successors(Some(start), |cell| {
+                Some(Position::new(cell.x + dx, cell.y + dy)).filter(|next| grid.contains(*next))
+            })
+            .collect()
+        })
+        .collect()
+}
+
+/// The runs of digits along each of the `lines`, a building block of [`NumberStrategy`]s.
+pub fn find_runs(
+    grid: &Grid<char>,
+    lines: impl IntoIterator<Item = impl IntoIterator<Item = Position>>,
+    signed: bool,
+) -> Vec<SchematicNumber> {
+    let mut numbers = vec![];
+    for line in lines {
+        let line = line.into_iter().collect::<Vec<_>>();
+        let mut end = 0;
+        while end < line.len() {
+            if !grid[line[end]].is_ascii_digit() {
+                end += 1;
+                continue;
+            }
+            let start = end;
+            while end < line.len() && grid[line[end]].is_ascii_digit() {
+                end += 1;
             }
+            let value = line[start..end].iter().fold(0_i64, |value, cell| {
+                let digit = grid[*cell].to_digit(10).unwrap_or_default();
+                value.saturating_mul(10).saturating_add(digit.into())
+            });
+            let negative = signed && start > 0 && grid[line[start - 1]] == '-';
+            numbers.push(match negative {
+                true => SchematicNumber {
+                    value: -value,
+                    cells: line[start - 1..end].to_vec(),
+                },
+                false => SchematicNumber {
+                    value,
+                    cells: line[start..end].to_vec(),
+                },
+            });
         }
-        indices
     }
+    numbers
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     const INPUT: &str = "467..114..
 ...*......
@@ -137,13 +386,96 @@ mod tests {
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "4361");
     }
 
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "467835");
     }
+
+    #[test]
+    fn schematic_can_be_queried() {
+        let schematic = Schematic::new(&parse_input(INPUT).unwrap());
+        let values = |numbers: Vec<&SchematicNumber>| {
+            numbers
+                .iter()
+                .map(|number| number.value)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(schematic.unused_numbers().collect()), [114, 58]);
+        assert_eq!(
+            values(schematic.numbers_at(Position::new(3, 4)).collect()),
+            [617]
+        );
+        assert_eq!(
+            values(schematic.numbers_at(Position::new(0, 0)).collect()),
+            []
+        );
+        let symbols = schematic
+            .symbols('*')
+            .map(|(position, numbers)| (position, values(numbers)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            symbols,
+            [
+                (Position::new(3, 1), vec![467, 35]),
+                (Position::new(3, 4), vec![617]),
+                (Position::new(5, 8), vec![755, 598])
+            ]
+        );
+        assert_eq!(values(schematic.numbers_next_to('#', 1).collect()), [633]);
+        assert_eq!(schematic.numbers_next_to('$', 0).count(), 9);
+    }
+
+    #[rstest]
+    #[case(Orientation::Horizontal, false, &[(12, 0, 0), (3, 3, 0), (4, 0, 1), (5, 0, 2), (67, 2, 2)])]
+    #[case(Orientation::Horizontal, true, &[(12, 0, 0), (-3, 2, 0), (4, 0, 1), (5, 0, 2), (-67, 1, 2)])]
+    #[case(Orientation::Vertical, false, &[(145, 0, 0), (2, 1, 0), (3, 3, 0), (6, 2, 2), (7, 3, 2)])]
+    #[case(Orientation::Both, false, &[(12, 0, 0), (145, 0, 0), (3, 3, 0), (67, 2, 2)])]
+    #[case(Orientation::Both, true, &[(12, 0, 0), (145, 0, 0), (-3, 2, 0), (-67, 1, 2)])]
+    #[case(Orientation::Diagonal, false, &[(1, 0, 0), (24, 1, 0), (3, 3, 0), (5, 0, 2), (6, 2, 2), (7, 3, 2)])]
+    #[case(Orientation::All, false, &[(12, 0, 0), (145, 0, 0), (24, 1, 0), (3, 3, 0), (67, 2, 2)])]
+    fn numbers_are_read_in_format(
+        #[case] orientation: Orientation,
+        #[case] signed: bool,
+        #[case] expected: &[(i64, i32, i32)],
+    ) {
+        let grid = parse_input("12-3\n4*..\n5-67").unwrap();
+        let format = NumberFormat {
+            orientation,
+            signed,
+        };
+        let numbers = find_numbers(&grid, format)
+            .iter()
+            .map(|number| (number.value, number.cells[0].x, number.cells[0].y))
+            .collect::<Vec<_>>();
+        assert_eq!(numbers, expected);
+    }
+
+    #[test]
+    fn signs_are_not_symbols() {
+        let grid = parse_input("1-2\n...").unwrap();
+        assert_eq!(part_1(grid.clone(), NumberFormat::default()), "3");
+        let signed = NumberFormat {
+            signed: true,
+            ..NumberFormat::default()
+        };
+        assert_eq!(part_1(grid, signed), "0");
+    }
+
+    #[test]
+    fn strategies_can_be_supplied() {
+        let grid = parse_input("12.\n..*\n34.").unwrap();
+        // right to left along the rows
+        let backwards = |grid: &Grid<char>| find_runs(grid, lines(grid, (-1, 0)), false);
+        let schematic = Schematic::with_strategy(&grid, &backwards);
+        let values = schematic
+            .part_numbers()
+            .map(|number| number.value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, [21, 43]);
+    }
 }
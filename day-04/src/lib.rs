@@ -1,35 +1,169 @@
-use std::collections::BTreeMap;
+use std::fmt::{self, Display};
 
+use fixedbitset::FixedBitSet;
 use nom::{
-    bytes::complete::tag,
     character::complete::{self, line_ending, space1},
     multi::separated_list1,
     sequence::{delimited, preceded, separated_pair, terminated},
-    IResult, Parser,
+    Parser,
 };
+use num::BigUint;
+use solver::parse::{parse_all, tag, Expected, IResult, NomError};
+use solver::{ParseError, Part, Run, Solver};
 
-pub fn process_part_1(input: &str) -> String {
-    let (_, cards) = parse_input(input).unwrap();
-    cards.iter().map(Card::score).sum::<u32>().to_string()
+pub struct Day04;
+
+impl Solver for Day04 {
+    fn day(&self) -> u8 {
+        4
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        match part {
+            Part::One => Run::timed(|| parse_cards(input), part_1),
+            Part::Two => Run::timed(|| parse_cascade(input), part_2),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day04.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day04.solve(Part::Two, input)
+}
+
+fn part_1(cards: Vec<Card>) -> String {
+    // a card with n winning numbers is worth 2^(n-1), which doesn't fit any fixed width integer
+    cards.iter().map(Card::score).sum::<BigUint>().to_string()
+}
+
+fn part_2(cascade: Vec<CardCopies>) -> String {
+    // the copies of up to 2^64 cards fit
+    cascade
+        .iter()
+        .map(|card| u128::from(card.copies))
+        .sum::<u128>()
+        .to_string()
+}
+
+pub fn parse_cards(input: &str) -> Result<Vec<Card>, ParseError> {
+    parse_all(input, parse_input)
+}
+
+/// Parses the cards and plays out the cascade, a cascade that can't be played out is an error
+/// on the card that breaks it.
+fn parse_cascade(input: &str) -> Result<Vec<CardCopies>, ParseError> {
+    let cards = parse_cards(input)?;
+    cascade(&cards).map_err(|error| {
+        card_error(
+            input,
+            error.index(),
+            format!("a card that can be played out, {}", error),
+        )
+    })
+}
+
+/// An error pointing at the id of the card at `index`.
+fn card_error(input: &str, index: usize, expected: impl Into<String>) -> ParseError {
+    let line = input.lines().nth(index).unwrap_or(input);
+    let id = line
+        .strip_prefix("Card")
+        .map_or(line, |rest| rest.trim_start());
+    ParseError::at(input, id, expected)
+}
+
+/// How many copies of a card there are in the end and which cards won them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardCopies {
+    pub id: u32,
+    /// Copies including the original.
+    pub copies: u64,
+    /// The ids of the cards that won copies of this one, with how many copies each won.
+    pub sources: Vec<(u32, u64)>,
+}
+
+/// Why the cascade can't be played out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CascadeError {
+    /// A card wins copies of cards past the end of the table.
+    PastTheEnd {
+        card: u32,
+        /// Where the card is in the table.
+        index: usize,
+        /// Cards past the end the card wins copies of.
+        missing: usize,
+    },
+    /// A card ends up with more copies than fit a `u64`.
+    TooManyCopies { card: u32, index: usize },
+}
+
+impl CascadeError {
+    /// Where the card that breaks the cascade is in the table.
+    pub fn index(&self) -> usize {
+        match self {
+            Self::PastTheEnd { index, .. } | Self::TooManyCopies { index, .. } => *index,
+        }
+    }
+}
+
+impl Display for CascadeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PastTheEnd { card, missing, .. } => write!(
+                f,
+                "card {} wins copies of {} cards past the end of the table",
+                card, missing
+            ),
+            Self::TooManyCopies { card, .. } => {
+                write!(f, "card {} ends up with more than 2^64 copies", card)
+            }
+        }
+    }
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let (_, cards) = parse_input(input).unwrap();
-    let mut card_numbers = cards
+impl std::error::Error for CascadeError {}
+
+/// Plays out part 2: every copy of a card wins one copy of each of the cards after it in the
+/// table, as many as it has winning numbers.
+pub fn cascade(cards: &[Card]) -> Result<Vec<CardCopies>, CascadeError> {
+    let mut cascade = cards
         .iter()
-        .map(|card| (card.id, 1))
-        .collect::<BTreeMap<u32, u32>>();
-    for card in cards {
-        let card_count = card_numbers.get(&card.id).cloned().unwrap();
-        let winning_number_count = card.winning_number_count();
-        for i in 0..winning_number_count {
-            card_numbers
-                .entry(card.id + i + 1)
-                .and_modify(|card_number| *card_number += card_count)
-                .or_insert(card_count);
+        .map(|card| CardCopies {
+            id: card.id,
+            copies: 1,
+            sources: vec![],
+        })
+        .collect::<Vec<_>>();
+    for (i, card) in cards.iter().enumerate() {
+        let wins = card.winning_number_count() as usize;
+        let missing = (i + wins).saturating_sub(cards.len() - 1);
+        if missing > 0 {
+            return Err(CascadeError::PastTheEnd {
+                card: card.id,
+                index: i,
+                missing,
+            });
+        }
+        let copies = cascade[i].copies;
+        for (j, won) in cascade
+            .iter_mut()
+            .enumerate()
+            .take(i + wins + 1)
+            .skip(i + 1)
+        {
+            won.copies = won
+                .copies
+                .checked_add(copies)
+                .ok_or(CascadeError::TooManyCopies {
+                    card: won.id,
+                    index: j,
+                })?;
+            won.sources.push((card.id, copies));
         }
     }
-    card_numbers.values().sum::<u32>().to_string()
+    Ok(cascade)
 }
 
 fn parse_input(input: &str) -> IResult<&str, Vec<Card>> {
@@ -39,40 +173,50 @@ fn parse_input(input: &str) -> IResult<&str, Vec<Card>> {
             preceded(terminated(tag("Card"), space1), complete::u32),
             terminated(tag(":"), space1),
             separated_pair(
-                separated_list1(space1, complete::u32),
+                separated_list1(space1, parse_number),
                 delimited(space1, tag("|"), space1),
-                separated_list1(space1, complete::u32),
+                separated_list1(space1, parse_number),
             ),
         )
         .map(|(id, (winning_numbers, my_numbers))| Card {
             id,
-            winning_numbers,
+            winning_numbers: winning_numbers.into_iter().map(usize::from).collect(),
             my_numbers,
         }),
     )(input)
 }
 
-#[derive(Debug)]
-struct Card {
-    id: u32,
-    winning_numbers: Vec<u32>,
-    my_numbers: Vec<u32>,
+/// Numbers are kept small so that the winning ones fit a bitset.
+fn parse_number(input: &str) -> IResult<&str, u16> {
+    let (rest, number) = complete::u32(input)?;
+    match u16::try_from(number) {
+        Ok(number) => Ok((rest, number)),
+        Err(_) => Err(nom::Err::Failure(NomError::new(
+            input,
+            Expected::Description("a number up to 65535"),
+        ))),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    pub id: u32,
+    winning_numbers: FixedBitSet,
+    my_numbers: Vec<u16>,
 }
 
 impl Card {
-    fn score(&self) -> u32 {
-        let winning_number_count = self.winning_number_count();
-        if winning_number_count == 0 {
-            0
-        } else {
-            2_u32.pow(winning_number_count - 1)
+    pub fn score(&self) -> BigUint {
+        match self.winning_number_count() {
+            0 => BigUint::default(),
+            count => BigUint::from(1_u8) << (count - 1),
         }
     }
 
-    fn winning_number_count(&self) -> u32 {
+    pub fn winning_number_count(&self) -> u32 {
         self.my_numbers
             .iter()
-            .filter(|my_number| self.winning_numbers.contains(my_number))
+            .filter(|my_number| self.winning_numbers.contains(usize::from(**my_number)))
             .count() as u32
     }
 }
@@ -90,13 +234,85 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "13");
     }
 
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "30");
     }
+
+    #[test]
+    fn cascade_has_provenance() {
+        let cards = parse_all(INPUT, parse_input).unwrap();
+        let cascade = cascade(&cards).unwrap();
+        let copies = cascade.iter().map(|card| card.copies).collect::<Vec<_>>();
+        assert_eq!(copies, [1, 2, 4, 8, 14, 1]);
+        assert_eq!(cascade[4].sources, [(1, 1), (3, 4), (4, 8)]);
+        assert!(cascade[5].sources.is_empty());
+    }
+
+    #[test]
+    fn cascade_past_the_end_is_reported() {
+        let input = "Card 1: 1 2 | 1 3\nCard 2: 1 2 3 | 1 2 3";
+        let cards = parse_all(input, parse_input).unwrap();
+        assert_eq!(
+            cascade(&cards),
+            Err(CascadeError::PastTheEnd {
+                card: 2,
+                index: 1,
+                missing: 3
+            })
+        );
+        assert_eq!(
+            process_part_2(input).unwrap_err().to_string(),
+            "expected a card that can be played out, card 2 wins copies of 3 cards past the end \
+             of the table on line 2, column 6"
+        );
+        assert_eq!(process_part_1(input).unwrap(), "5");
+    }
+
+    /// A card with `count` matched winning numbers.
+    fn winning_card(id: u32, count: u16) -> String {
+        let numbers = (1..=count)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        match count {
+            0 => format!("Card {}: 1 | 2", id),
+            _ => format!("Card {}: {} | {}", id, numbers, numbers),
+        }
+    }
+
+    #[test]
+    fn points_are_unbounded_but_copies_are_not() {
+        let two_to_the_128 = (BigUint::from(1_u8) << 128_u32).to_string();
+        let input = [winning_card(1, 128), winning_card(2, 128)].join("\n");
+        assert_eq!(process_part_1(&input).unwrap(), two_to_the_128);
+        assert_eq!(
+            process_part_1(&winning_card(1, 129)).unwrap(),
+            two_to_the_128
+        );
+        // every card wins copies of all the cards after it, so card n ends up with 2^(n-1)
+        let cards = (1..=65)
+            .map(|id| winning_card(id, 65 - id as u16))
+            .collect::<Vec<_>>();
+        let result = process_part_2(&cards.join("\n")).unwrap_err();
+        assert_eq!(
+            result.to_string(),
+            "expected a card that can be played out, card 65 ends up with more than 2^64 copies \
+             on line 65, column 6"
+        );
+    }
+
+    #[test]
+    fn numbers_have_a_limit() {
+        let result = process_part_1("Card 1: 1 70000 | 1");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "expected a number up to 65535 on line 1, column 11"
+        );
+    }
 }
@@ -0,0 +1,478 @@
+use std::{
+    fmt::{self, Display},
+    ops::Range,
+};
+
+use nom::{
+    bytes::complete::take_till1,
+    character::complete::{self, line_ending, space1},
+    multi::{many1, separated_list1},
+    sequence::{preceded, terminated, tuple},
+    Parser,
+};
+use solver::parse::{tag, Expected, IResult, NomError};
+
+use crate::RANGE;
+
+/// Values in `source` go to the values of a range of the same length starting at
+/// `destination`, one line of a map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    pub source: Range<u64>,
+    pub destination: u64,
+}
+
+impl Mapping {
+    pub fn destination_range(&self) -> Range<u64> {
+        self.destination..self.destination + (self.source.end - self.source.start)
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.source.start == self.destination
+    }
+
+    /// The part of the mapping that covers `range`.
+    fn restrict(&self, range: &Range<u64>) -> Option<Mapping> {
+        let start = self.source.start.max(range.start);
+        let end = self.source.end.min(range.end);
+        (start < end).then(|| Mapping {
+            source: start..end,
+            destination: self.destination + (start - self.source.start),
+        })
+    }
+}
+
+/// A stage of the almanac, like `seed-to-soil`. A value is mapped by the first of the mappings
+/// that contains it and maps to itself when none does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Map {
+    pub source: String,
+    pub destination: String,
+    /// In the order of the input.
+    pub mappings: Vec<Mapping>,
+    /// What the mappings amount to: ordered, not overlapping and without identity.
+    pieces: Vec<Mapping>,
+}
+
+impl Map {
+    pub fn new(source: String, destination: String, mappings: Vec<Mapping>) -> Self {
+        let mut pieces: Vec<Mapping> = vec![];
+        for mapping in &mappings {
+            // earlier mappings win where they overlap
+            let mut uncovered = vec![mapping.source.clone()];
+            for piece in &pieces {
+                uncovered = uncovered
+                    .into_iter()
+                    .flat_map(|range| subtract(range, &piece.source))
+                    .collect();
+            }
+            pieces.extend(uncovered.iter().filter_map(|range| mapping.restrict(range)));
+        }
+        pieces.retain(|piece| !piece.is_identity());
+        pieces.sort_by_key(|piece| piece.source.start);
+        Self {
+            source,
+            destination,
+            mappings,
+            pieces,
+        }
+    }
+
+    pub fn map_value(&self, value: u64) -> u64 {
+        let i = self
+            .pieces
+            .partition_point(|piece| piece.source.end <= value);
+        match self.pieces.get(i) {
+            Some(piece) if piece.source.contains(&value) => {
+                piece.destination + (value - piece.source.start)
+            }
+            _ => value,
+        }
+    }
+
+    /// The ranges the values of `range` go to, in the order of the values.
+    pub fn map_range(&self, range: Range<u64>) -> Vec<Range<u64>> {
+        self.split(range)
+            .iter()
+            .map(Mapping::destination_range)
+            .collect()
+    }
+
+    /// Splits `range` into the parts that the map moves by the same amount, in order. The parts
+    /// that map to themselves are included.
+    pub fn split(&self, range: Range<u64>) -> Vec<Mapping> {
+        let mut parts = vec![];
+        let mut start = range.start;
+        let first = self
+            .pieces
+            .partition_point(|piece| piece.source.end <= range.start);
+        for piece in &self.pieces[first..] {
+            if piece.source.start >= range.end {
+                break;
+            }
+            if start < piece.source.start {
+                parts.push(identity(start..piece.source.start));
+            }
+            parts.extend(piece.restrict(&range));
+            start = piece.source.end;
+        }
+        if start < range.end {
+            parts.push(identity(start..range.end));
+        }
+        parts
+    }
+
+    /// The parts of all values that the map moves, ordered and not overlapping.
+    pub fn pieces(&self) -> &[Mapping] {
+        &self.pieces
+    }
+
+    /// The values that go to `range`, ordered and merged. The mappings must not overlap, or
+    /// which one a value follows would depend on their order.
+    pub fn preimage(&self, range: Range<u64>) -> Result<Vec<Range<u64>>, OverlapError> {
+        if let Some(error) = self.overlaps().into_iter().next() {
+            return Err(error);
+        }
+        let moved = self.pieces.iter().filter_map(|piece| {
+            let destination = piece.destination_range();
+            let start = destination.start.max(range.start);
+            let end = destination.end.min(range.end);
+            let source = |value| value - destination.start + piece.source.start;
+            (start < end).then(|| source(start)..source(end))
+        });
+        let kept = self
+            .split(range.clone())
+            .into_iter()
+            .filter(Mapping::is_identity)
+            .map(|part| part.source);
+        Ok(merge(moved.chain(kept).collect()))
+    }
+
+    /// The pairs of mappings whose sources overlap, ordered by the later mapping of the pair.
+    pub fn overlaps(&self) -> Vec<OverlapError> {
+        let mut overlaps = vec![];
+        for (i, second) in self.mappings.iter().enumerate() {
+            for first in &self.mappings[..i] {
+                if first.source.start.max(second.source.start)
+                    < first.source.end.min(second.source.end)
+                {
+                    overlaps.push(OverlapError {
+                        map: self.name(),
+                        first: first.source.clone(),
+                        second: second.source.clone(),
+                    });
+                }
+            }
+        }
+        overlaps
+    }
+
+    /// The name of the header, like `seed-to-soil`.
+    pub fn name(&self) -> String {
+        format!("{}-to-{}", self.source, self.destination)
+    }
+}
+
+/// Two mappings of a map take values from the same range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlapError {
+    pub map: String,
+    /// The sources of the mappings in the order of the input.
+    pub first: Range<u64>,
+    pub second: Range<u64>,
+}
+
+impl Display for OverlapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "sources {:?} and {:?} of the {} map overlap",
+            self.first, self.second, self.map
+        )
+    }
+}
+
+impl std::error::Error for OverlapError {}
+
+/// The maps of the almanac in the order of the input, each one maps the values of the one
+/// before.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Almanac {
+    pub maps: Vec<Map>,
+}
+
+impl Almanac {
+    /// Maps `value` through the whole chain.
+    pub fn map_value(&self, value: u64) -> u64 {
+        self.maps
+            .iter()
+            .fold(value, |value, map| map.map_value(value))
+    }
+
+    /// Maps `ranges` through the whole chain.
+    pub fn map_ranges(&self, ranges: impl IntoIterator<Item = Range<u64>>) -> Vec<Range<u64>> {
+        self.maps
+            .iter()
+            .fold(ranges.into_iter().collect(), |ranges, map| {
+                ranges
+                    .into_iter()
+                    .flat_map(|range| map.map_range(range))
+                    .collect()
+            })
+    }
+
+    /// The values of the first map that end up in `ranges` after the last one, ordered and
+    /// merged.
+    pub fn preimage(
+        &self,
+        ranges: impl IntoIterator<Item = Range<u64>>,
+    ) -> Result<Vec<Range<u64>>, OverlapError> {
+        self.maps
+            .iter()
+            .rev()
+            .try_fold(merge(ranges.into_iter().collect()), |ranges, map| {
+                let mut preimage = vec![];
+                for range in ranges {
+                    preimage.extend(map.preimage(range)?);
+                }
+                Ok(merge(preimage))
+            })
+    }
+
+    /// The whole chain as a single map from the source of the first map to the destination of
+    /// the last one.
+    pub fn compose(&self) -> Map {
+        // `u64::MAX` can't be in a range, but no map moves it either
+        let mut pieces = vec![identity(0..u64::MAX)];
+        for map in &self.maps {
+            pieces = pieces
+                .iter()
+                .flat_map(|piece| {
+                    map.split(piece.destination_range())
+                        .into_iter()
+                        .map(|part| {
+                            let start =
+                                piece.source.start + (part.source.start - piece.destination);
+                            let end = start + (part.source.end - part.source.start);
+                            Mapping {
+                                source: start..end,
+                                destination: part.destination,
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+        }
+        let name = |map: Option<&Map>, name: fn(&Map) -> &String| {
+            map.map(name).cloned().unwrap_or_default()
+        };
+        Map::new(
+            name(self.maps.first(), |map| &map.source),
+            name(self.maps.last(), |map| &map.destination),
+            pieces,
+        )
+    }
+}
+
+fn identity(range: Range<u64>) -> Mapping {
+    Mapping {
+        destination: range.start,
+        source: range,
+    }
+}
+
+/// Sorts `ranges` and joins the ones that overlap or touch.
+pub(crate) fn merge(mut ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    ranges.retain(|range| !range.is_empty());
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<u64>> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// The parts of `range` outside of `other`.
+fn subtract(range: Range<u64>, other: &Range<u64>) -> Vec<Range<u64>> {
+    [
+        range.start..other.start.min(range.end),
+        other.end.max(range.start)..range.end,
+    ]
+    .into_iter()
+    .filter(|part| !part.is_empty())
+    .collect()
+}
+
+/// Parses the seeds with `seeds` and then the maps.
+pub fn parse_input<'a, S>(
+    seeds: impl Parser<&'a str, S, NomError<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (S, Almanac)> {
+    let mut seeds = preceded(terminated(tag("seeds:"), space1), seeds);
+    move |input| {
+        let (input, seeds) = seeds.parse(input)?;
+        let (input, _) = many1(line_ending)(input)?;
+        let (input, maps) = separated_list1(many1(line_ending), parse_map)(input)?;
+        Ok((input, (seeds, Almanac { maps })))
+    }
+}
+
+pub fn parse_seeds(input: &str) -> IResult<&str, Vec<u64>> {
+    separated_list1(space1, complete::u64)(input)
+}
+
+pub fn parse_seed_ranges(input: &str) -> IResult<&str, Vec<Range<u64>>> {
+    separated_list1(space1, parse_seed_range)(input)
+}
+
+fn parse_seed_range(input: &str) -> IResult<&str, Range<u64>> {
+    let (length_input, start) = terminated(complete::u64, space1)(input)?;
+    let (rest, length) = complete::u64(length_input)?;
+    if length == 0 {
+        return Err(nom::Err::Failure(NomError::new(
+            length_input,
+            Expected::Description("a range with at least one seed"),
+        )));
+    }
+    match start.checked_add(length) {
+        Some(end) => Ok((rest, start..end)),
+        None => Err(nom::Err::Failure(NomError::new(
+            input,
+            Expected::Description(RANGE),
+        ))),
+    }
+}
+
+fn parse_map(input: &str) -> IResult<&str, Map> {
+    let (rest, name) = take_till1(|c: char| c.is_whitespace())(input)?;
+    let Some((source, destination)) = name.split_once("-to-") else {
+        return Err(nom::Err::Failure(NomError::new(
+            input,
+            Expected::Description("a map name like seed-to-soil"),
+        )));
+    };
+    let (rest, _) = terminated(tag(" map:"), line_ending)(rest)?;
+    let (rest, mappings) = separated_list1(line_ending, parse_mapping)(rest)?;
+    let map = Map::new(source.to_string(), destination.to_string(), mappings);
+    Ok((rest, map))
+}
+
+fn parse_mapping(input: &str) -> IResult<&str, Mapping> {
+    let (rest, (destination, source, length)) = tuple((
+        complete::u64,
+        preceded(space1, complete::u64),
+        preceded(space1, complete::u64),
+    ))(input)?;
+    let (Some(_), Some(source_end)) = (destination.checked_add(length), source.checked_add(length))
+    else {
+        return Err(nom::Err::Failure(NomError::new(
+            input,
+            Expected::Description(RANGE),
+        )));
+    };
+    let mapping = Mapping {
+        source: source..source_end,
+        destination,
+    };
+    Ok((rest, mapping))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A map from `source` to `destination` with the lines `(destination, source, length)`.
+    pub(crate) fn map(source: &str, destination: &str, mappings: &[(u64, u64, u64)]) -> Map {
+        let mappings = mappings
+            .iter()
+            .map(|(destination, source, length)| Mapping {
+                source: *source..source + length,
+                destination: *destination,
+            })
+            .collect();
+        Map::new(source.to_string(), destination.to_string(), mappings)
+    }
+
+    #[test]
+    fn first_mapping_wins() {
+        let map = map("a", "b", &[(100, 10, 10), (200, 15, 10), (5, 5, 3)]);
+        assert_eq!(
+            map.pieces(),
+            [
+                Mapping {
+                    source: 10..20,
+                    destination: 100
+                },
+                Mapping {
+                    source: 20..25,
+                    destination: 205
+                }
+            ]
+        );
+        assert_eq!(map.map_value(16), 106);
+        assert_eq!(map.map_value(22), 207);
+        assert_eq!(map.map_value(25), 25);
+        assert_eq!(map.map_range(0..30), [0..10, 100..110, 205..210, 25..30]);
+    }
+
+    #[test]
+    fn composition_maps_like_the_chain() {
+        let almanac = Almanac {
+            maps: vec![
+                map("a", "b", &[(50, 98, 2), (52, 50, 48)]),
+                map("a", "b", &[(0, 15, 37), (37, 52, 2), (39, 0, 15)]),
+                map("a", "b", &[(u64::MAX - 10, 0, 10)]),
+            ],
+        };
+        let composition = almanac.compose();
+        for value in (0..120).chain(u64::MAX - 20..u64::MAX) {
+            assert_eq!(
+                composition.map_value(value),
+                almanac.map_value(value),
+                "{}",
+                value
+            );
+        }
+        assert_eq!(
+            merge(almanac.map_ranges([0..60, 60..120])),
+            merge(composition.map_range(0..120))
+        );
+    }
+
+    #[test]
+    fn preimage_maps_back() {
+        let map = map("a", "b", &[(100, 10, 10), (15, 100, 10), (5, 5, 3)]);
+        assert_eq!(map.preimage(0..105), Ok(vec![0..15, 20..110]));
+        assert_eq!(map.preimage(105..120), Ok(vec![15..20, 110..120]));
+    }
+
+    #[test]
+    fn overlaps_are_reported() {
+        let map = map(
+            "a",
+            "b",
+            &[
+                (0, 50, 10),
+                (0, 0, 20),
+                (0, 100, 5),
+                (0, 55, 10),
+                (0, 15, 1),
+            ],
+        );
+        let error = map.preimage(0..10).unwrap_err();
+        assert_eq!(
+            error,
+            OverlapError {
+                map: "a-to-b".to_string(),
+                first: 50..60,
+                second: 55..65
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "sources 50..60 and 55..65 of the a-to-b map overlap"
+        );
+    }
+}
@@ -1,12 +1,79 @@
-mod part_1;
-mod part_2;
+mod almanac;
+mod validation;
 
-pub use part_1::process_part_1;
-pub use part_2::process_part_2;
+use std::ops::Range;
+
+use solver::parse::parse_all;
+use solver::{diagnostics, ParseError, Part, Run, Solver};
+
+pub use almanac::{Almanac, Map, Mapping, OverlapError};
+pub use validation::{ChainError, Coverage, Stage, Validation};
+
+/// Expected by the parsers when the end of a range doesn't fit into a `u64`.
+const RANGE: &str = "a range ending below 2^64";
+
+pub struct Day05;
+
+impl Solver for Day05 {
+    fn day(&self) -> u8 {
+        5
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        // the parts read the seeds differently, the maps the same
+        match part {
+            Part::One => Run::timed(|| parse_almanac(input), part_1),
+            Part::Two => Run::timed(
+                || parse_all(input, almanac::parse_input(almanac::parse_seed_ranges)),
+                part_2,
+            ),
+        }
+    }
+}
+
+/// Parses the seeds as single values and the maps.
+pub fn parse_almanac(input: &str) -> Result<(Vec<u64>, Almanac), ParseError> {
+    parse_all(input, almanac::parse_input(almanac::parse_seeds))
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day05.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day05.solve(Part::Two, input)
+}
+
+fn part_1((seeds, almanac): (Vec<u64>, Almanac)) -> String {
+    if diagnostics::enabled() {
+        let seed_ranges = seeds.iter().map(|seed| *seed..seed + 1).collect::<Vec<_>>();
+        diagnostics::print(almanac.validate(&seed_ranges));
+    }
+    seeds
+        .iter()
+        .map(|seed| almanac.map_value(*seed))
+        .min()
+        .unwrap()
+        .to_string()
+}
+
+fn part_2((seed_ranges, almanac): (Vec<Range<u64>>, Almanac)) -> String {
+    if diagnostics::enabled() {
+        diagnostics::print(almanac.validate(&seed_ranges));
+    }
+    almanac
+        .map_ranges(seed_ranges)
+        .iter()
+        .map(|location_range| location_range.start)
+        .min()
+        .unwrap()
+        .to_string()
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     const INPUT: &str = "seeds: 79 14 55 13
 
@@ -44,13 +111,219 @@ humidity-to-location map:
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "35");
     }
 
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "46");
     }
+
+    #[test]
+    fn empty_seed_ranges_are_rejected() {
+        let input = INPUT.replace("seeds: 79 14 55 13", "seeds: 79 0 55 13");
+        assert_eq!(
+            process_part_2(&input),
+            Err(ParseError::new(1, 11, "a range with at least one seed"))
+        );
+        assert_eq!(process_part_1(&input).unwrap(), "22");
+    }
+
+    #[test]
+    fn stages_are_read_from_the_headers() {
+        let input = INPUT.replace("water-to-light", "water-to-lamp").replace(
+            "\n\nlight-to-temperature",
+            "\n\nlamp-to-glow map:\n0 0 1\n\nglow-to-temperature",
+        );
+        let (seeds, almanac) = parse_almanac(&input).unwrap();
+        let names = almanac.maps.iter().map(Map::name).collect::<Vec<_>>();
+        assert_eq!(
+            names[3..6],
+            ["water-to-lamp", "lamp-to-glow", "glow-to-temperature"]
+        );
+        assert_eq!(part_1((seeds, almanac.clone())), "35");
+        let composition = almanac.compose();
+        assert_eq!(
+            (
+                composition.source.as_str(),
+                composition.destination.as_str()
+            ),
+            ("seed", "location")
+        );
+        assert_eq!(composition.map_value(79), 82);
+        assert_eq!(composition.map_range(79..93)[0], 82..85);
+    }
+
+    #[test]
+    fn malformed_maps_are_reported() {
+        let input = INPUT.replace("50 98 2", "50 98");
+        let result = process_part_1(&input).unwrap_err();
+        assert_eq!(result.to_string(), "expected a space on line 4, column 6");
+        let input = INPUT.replace("50 98 2", "50 98 18446744073709551615");
+        let result = process_part_2(&input).unwrap_err();
+        assert_eq!(
+            result.to_string(),
+            "expected a range ending below 2^64 on line 4, column 1"
+        );
+        let input = INPUT.replace("seeds: 79 14", "seeds: 18446744073709551615 14");
+        let result = process_part_2(&input).unwrap_err();
+        assert_eq!(
+            result.to_string(),
+            "expected a range ending below 2^64 on line 1, column 8"
+        );
+        let input = INPUT.replace("water-to-light", "water-light");
+        let result = process_part_1(&input).unwrap_err();
+        assert_eq!(
+            result.to_string(),
+            "expected a map name like seed-to-soil on line 18, column 1"
+        );
+    }
+
+    #[test]
+    fn seeds_are_found_from_locations() {
+        let (_, almanac) = parse_almanac(INPUT).unwrap();
+        let preimage = almanac.preimage(Some(46..47)).unwrap();
+        assert!(preimage.iter().any(|range| range.contains(&82)));
+        assert!(preimage
+            .iter()
+            .cloned()
+            .flatten()
+            .all(|seed| almanac.map_value(seed) == 46));
+        assert_eq!(almanac.preimage(Some(35..36)).unwrap()[0], 13..14);
+        let input = INPUT.replace("0 15 37", "0 15 38");
+        let (_, almanac) = parse_almanac(&input).unwrap();
+        assert_eq!(
+            almanac.preimage(Some(46..47)).unwrap_err().to_string(),
+            "sources 15..53 and 52..54 of the soil-to-fertilizer map overlap"
+        );
+    }
+
+    #[test]
+    fn wrong_header_order_is_reported() {
+        let (seeds, almanac) = parse_almanac(INPUT).unwrap();
+        let seed_ranges = seeds.iter().map(|seed| *seed..seed + 1).collect::<Vec<_>>();
+        let validation = almanac.validate(&seed_ranges);
+        assert!(validation.is_valid());
+        assert_eq!(validation.coverage[0].stages.len(), 7);
+        assert!(validation
+            .coverage
+            .iter()
+            .all(|coverage| coverage.untouched == 0));
+        // the headers of the fourth and fifth map swapped
+        let input = INPUT
+            .replace("water-to-light", "#")
+            .replace("light-to-temperature", "water-to-light")
+            .replace('#', "light-to-temperature");
+        let (_, almanac) = parse_almanac(&input).unwrap();
+        let errors = almanac
+            .validate(&[])
+            .chain
+            .iter()
+            .map(ChainError::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                "map 4 reads light instead of water",
+                "map 5 reads water instead of temperature",
+                "map 6 reads temperature instead of light"
+            ]
+        );
+    }
+
+    const MAP_NAMES: [&str; 7] = [
+        "seed-to-soil",
+        "soil-to-fertilizer",
+        "fertilizer-to-water",
+        "water-to-light",
+        "light-to-temperature",
+        "temperature-to-humidity",
+        "humidity-to-location",
+    ];
+
+    /// Lines of a map as `(destination, source, length)`.
+    type MapLines = Vec<(u64, u64, u64)>;
+
+    /// Map lines whose source ranges don't overlap.
+    fn map() -> impl Strategy<Value = MapLines> {
+        prop::collection::vec((0..10u64, 1..15u64, 0..100u64), 1..5).prop_map(|ranges| {
+            let mut source = 0;
+            ranges
+                .into_iter()
+                .map(|(gap, length, destination)| {
+                    source += gap;
+                    let line = (destination, source, length);
+                    source += length;
+                    line
+                })
+                .collect()
+        })
+    }
+
+    /// Seed ranges as `(start, length)` and the seven maps.
+    fn almanac() -> impl Strategy<Value = (Vec<(u64, u64)>, Vec<MapLines>)> {
+        (
+            prop::collection::vec((0..80u64, 1..10u64), 1..4),
+            prop::collection::vec(map(), 7),
+        )
+    }
+
+    fn format_almanac(seeds: &[u64], maps: &[MapLines]) -> String {
+        let seeds = seeds.iter().map(u64::to_string).collect::<Vec<_>>();
+        let maps = MAP_NAMES.iter().zip(maps).map(|(name, lines)| {
+            let lines = lines
+                .iter()
+                .map(|(destination, source, length)| {
+                    format!("{} {} {}", destination, source, length)
+                })
+                .collect::<Vec<_>>();
+            format!("{} map:\n{}", name, lines.join("\n"))
+        });
+        format!(
+            "seeds: {}\n\n{}",
+            seeds.join(" "),
+            maps.collect::<Vec<_>>().join("\n\n")
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn seed_ranges_map_like_single_seeds((seed_ranges, maps) in almanac()) {
+            let range_input = format_almanac(
+                &seed_ranges
+                    .iter()
+                    .flat_map(|(start, length)| [*start, *length])
+                    .collect::<Vec<_>>(),
+                &maps,
+            );
+            let single_input = format_almanac(
+                &seed_ranges
+                    .iter()
+                    .flat_map(|(start, length)| *start..start + length)
+                    .collect::<Vec<_>>(),
+                &maps,
+            );
+            prop_assert_eq!(process_part_2(&range_input), process_part_1(&single_input));
+        }
+
+        #[test]
+        fn preimages_map_into_the_locations(
+            (_, maps) in almanac(),
+            start in 0..150u64,
+            length in 1..30u64,
+        ) {
+            let (_, almanac) = parse_almanac(&format_almanac(&[0], &maps)).unwrap();
+            let locations = start..start + length;
+            let seeds = almanac.preimage([locations.clone()]).unwrap();
+            for seed in 0..250 {
+                prop_assert_eq!(
+                    seeds.iter().any(|range| range.contains(&seed)),
+                    locations.contains(&almanac.map_value(seed)),
+                    "seed {}", seed
+                );
+            }
+        }
+    }
 }
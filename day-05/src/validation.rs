@@ -0,0 +1,269 @@
+//! Checks of an almanac that the puzzle takes for granted: the stages chain from seeds to
+//! locations and no two lines of a map take the same values.
+
+use std::{
+    fmt::{self, Display},
+    ops::Range,
+};
+
+use crate::almanac::{merge, Almanac, Map, OverlapError};
+
+/// What [`Almanac::validate`] finds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Validation {
+    pub chain: Vec<ChainError>,
+    /// One per map, in the order of the input.
+    pub stages: Vec<Stage>,
+    /// One per seed range.
+    pub coverage: Vec<Coverage>,
+}
+
+impl Validation {
+    /// Whether the chain is consistent and no map has overlaps. Gaps and identity lines are
+    /// allowed, values there map to themselves.
+    pub fn is_valid(&self) -> bool {
+        self.chain.is_empty() && self.stages.iter().all(|stage| stage.overlaps.is_empty())
+    }
+}
+
+/// A place where the maps don't chain from `seed` to `location`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainError {
+    /// The first map doesn't read seeds.
+    Start { found: String },
+    /// The map at `index` doesn't read what the map before it writes.
+    Link {
+        index: usize,
+        expected: String,
+        found: String,
+    },
+    /// The last map doesn't write locations.
+    End { found: String },
+}
+
+impl Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Start { found } => write!(f, "the chain starts at {} instead of seed", found),
+            Self::Link {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "map {} reads {} instead of {}",
+                index + 1,
+                found,
+                expected
+            ),
+            Self::End { found } => write!(f, "the chain ends at {} instead of location", found),
+        }
+    }
+}
+
+/// The ranges of a map that are worth a look.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stage {
+    pub name: String,
+    pub overlaps: Vec<OverlapError>,
+    /// Values between the sources of the lines that no line covers.
+    pub gaps: Vec<Range<u64>>,
+    /// Sources of the lines that map values to themselves.
+    pub identity: Vec<Range<u64>>,
+}
+
+impl Stage {
+    fn new(map: &Map) -> Self {
+        let sources = merge(
+            map.mappings
+                .iter()
+                .map(|mapping| mapping.source.clone())
+                .collect(),
+        );
+        Self {
+            name: map.name(),
+            overlaps: map.overlaps(),
+            gaps: sources
+                .windows(2)
+                .map(|pair| pair[0].end..pair[1].start)
+                .collect(),
+            identity: map
+                .mappings
+                .iter()
+                .filter(|mapping| mapping.is_identity() && !mapping.source.is_empty())
+                .map(|mapping| mapping.source.clone())
+                .collect(),
+        }
+    }
+}
+
+/// How the values of a seed range go through the maps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    pub seeds: Range<u64>,
+    /// For each map, how many of the values a line moves and how many map to themselves.
+    pub stages: Vec<(u64, u64)>,
+    /// How many seeds no map moves, their location is the seed.
+    pub untouched: u64,
+}
+
+impl Coverage {
+    fn new(almanac: &Almanac, seeds: Range<u64>) -> Self {
+        // the ranges at the current stage, with whether a map moved them before
+        let mut ranges = vec![(seeds.clone(), false)];
+        let mut stages = vec![];
+        for map in &almanac.maps {
+            let (mut mapped, mut kept) = (0, 0);
+            ranges = ranges
+                .into_iter()
+                .flat_map(|(range, moved)| {
+                    map.split(range).into_iter().map(move |part| (part, moved))
+                })
+                .map(|(part, moved)| {
+                    let length = part.source.end - part.source.start;
+                    if part.is_identity() {
+                        kept += length;
+                    } else {
+                        mapped += length;
+                    }
+                    (part.destination_range(), moved || !part.is_identity())
+                })
+                .collect();
+            stages.push((mapped, kept));
+        }
+        let untouched = ranges
+            .iter()
+            .filter(|(_, moved)| !moved)
+            .map(|(range, _)| range.end - range.start)
+            .sum();
+        Self {
+            seeds,
+            stages,
+            untouched,
+        }
+    }
+}
+
+impl Almanac {
+    /// Checks the chain and the maps, and follows `seeds` through them.
+    pub fn validate(&self, seeds: &[Range<u64>]) -> Validation {
+        let mut chain = vec![];
+        if let Some(map) = self.maps.first().filter(|map| map.source != "seed") {
+            chain.push(ChainError::Start {
+                found: map.source.clone(),
+            });
+        }
+        for (index, pair) in self.maps.windows(2).enumerate() {
+            if pair[0].destination != pair[1].source {
+                chain.push(ChainError::Link {
+                    index: index + 1,
+                    expected: pair[0].destination.clone(),
+                    found: pair[1].source.clone(),
+                });
+            }
+        }
+        if let Some(map) = self.maps.last().filter(|map| map.destination != "location") {
+            chain.push(ChainError::End {
+                found: map.destination.clone(),
+            });
+        }
+        Validation {
+            chain,
+            stages: self.maps.iter().map(Stage::new).collect(),
+            coverage: seeds
+                .iter()
+                .map(|seeds| Coverage::new(self, seeds.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl Display for Validation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = self
+            .chain
+            .iter()
+            .map(ChainError::to_string)
+            .collect::<Vec<_>>();
+        for stage in &self.stages {
+            let mut line = format!("{}:", stage.name);
+            for overlap in &stage.overlaps {
+                line += &format!(" overlap {:?} {:?}", overlap.first, overlap.second);
+            }
+            for gap in &stage.gaps {
+                line += &format!(" gap {:?}", gap);
+            }
+            for identity in &stage.identity {
+                line += &format!(" identity {:?}", identity);
+            }
+            lines.push(line);
+        }
+        for coverage in &self.coverage {
+            let stages = coverage
+                .stages
+                .iter()
+                .map(|(mapped, kept)| format!("{}/{}", mapped, kept))
+                .collect::<Vec<_>>();
+            lines.push(format!(
+                "seeds {:?}: mapped/identity {}, {} untouched",
+                coverage.seeds,
+                stages.join(" "),
+                coverage.untouched
+            ));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::almanac::tests::map;
+
+    #[test]
+    fn stages_are_checked() {
+        let almanac = Almanac {
+            maps: vec![
+                map(
+                    "seed",
+                    "soil",
+                    &[(50, 0, 10), (20, 20, 5), (60, 30, 10), (45, 45, 3)],
+                ),
+                map("soil", "water", &[(0, 50, 5), (10, 52, 5)]),
+                map("fertilizer", "humidity", &[]),
+            ],
+        };
+        let validation = almanac.validate(&[5..25, 100..110]);
+        assert!(!validation.is_valid());
+        assert_eq!(
+            validation.chain,
+            [
+                ChainError::Link {
+                    index: 2,
+                    expected: "water".to_string(),
+                    found: "fertilizer".to_string()
+                },
+                ChainError::End {
+                    found: "humidity".to_string()
+                }
+            ]
+        );
+        assert_eq!(validation.stages[0].gaps, [10..20, 25..30, 40..45]);
+        assert_eq!(validation.stages[0].identity, [20..25, 45..48]);
+        assert_eq!(validation.stages[1].overlaps.len(), 1);
+        // 5..10 goes to 55..60, and 55..57 of that on to 13..15
+        assert_eq!(validation.coverage[0].stages, [(5, 15), (2, 18), (0, 20)]);
+        assert_eq!(validation.coverage[0].untouched, 15);
+        assert_eq!(validation.coverage[1].untouched, 10);
+        assert_eq!(
+            validation.to_string(),
+            "map 3 reads fertilizer instead of water
+the chain ends at humidity instead of location
+seed-to-soil: gap 10..20 gap 25..30 gap 40..45 identity 20..25 identity 45..48
+soil-to-water: overlap 50..55 52..57
+fertilizer-to-humidity:
+seeds 5..25: mapped/identity 5/15 2/18 0/20, 15 untouched
+seeds 100..110: mapped/identity 0/10 0/10 0/10, 10 untouched"
+        );
+    }
+}
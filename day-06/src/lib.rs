@@ -1,35 +1,86 @@
+mod model;
+
+use std::ops::RangeInclusive;
+
 use nom::{
-    bytes::complete::tag,
-    character::complete::{self, line_ending, space0, space1},
+    character::complete::{self, digit1, line_ending, space0, space1},
     multi::separated_list1,
     sequence::{preceded, terminated},
-    IResult,
 };
+use num::BigUint;
+use solver::parse::{parse_all, tag, Expected, IResult, NomError};
+use solver::{ParseError, Part, Run, Solver};
+
+pub use model::{BoatModel, Capped, Drag, Linear};
+
+pub struct Day06;
+
+impl Solver for Day06 {
+    fn day(&self) -> u8 {
+        6
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        match part {
+            Part::One => Run::timed(|| parse_races(input, Kerning::Spaced), part_1),
+            Part::Two => Run::timed(|| parse_races(input, Kerning::Joined), part_2),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day06.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day06.solve(Part::Two, input)
+}
 
-pub fn process_part_1(input: &str) -> String {
-    let (_, races) = parse_input(input).unwrap();
+fn part_1(races: Vec<Race>) -> String {
     races
         .iter()
-        .map(Race::count_winning_times)
-        .product::<usize>()
+        .map(|race| BigUint::from(race.count_winning_times(&Linear::default())))
+        .product::<BigUint>()
+        .to_string()
+}
+
+fn part_2(races: Vec<Race>) -> String {
+    races
+        .first()
+        .unwrap()
+        .count_winning_times(&Linear::default())
         .to_string()
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let (_, races) = parse_input(input.replace(' ', "").as_str()).unwrap();
-    races.first().unwrap().count_winning_times().to_string()
+/// How the numbers of a line are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kerning {
+    /// Every number is a race, like part 1.
+    Spaced,
+    /// The spaces between the numbers are bad kerning, the digits are a single race, like
+    /// part 2.
+    Joined,
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Race>> {
-    let (input, times) = preceded(
-        terminated(tag("Time:"), space0),
-        separated_list1(space1, complete::u64),
-    )(input)?;
-    let (input, _) = line_ending(input)?;
-    let (input, record_distances) = preceded(
-        terminated(tag("Distance:"), space0),
-        separated_list1(space1, complete::u64),
-    )(input)?;
+pub fn parse_races(input: &str, kerning: Kerning) -> Result<Vec<Race>, ParseError> {
+    parse_all(input, |input| parse_input(input, kerning))
+}
+
+fn parse_input(input: &str, kerning: Kerning) -> IResult<&str, Vec<Race>> {
+    let numbers = |input| match kerning {
+        Kerning::Spaced => separated_list1(space1, complete::u128)(input),
+        Kerning::Joined => parse_joined(input).map(|(input, number)| (input, vec![number])),
+    };
+    let (input, times) = preceded(terminated(tag("Time:"), space0), numbers)(input)?;
+    let (distance_line, _) = line_ending(input)?;
+    let (input, record_distances) =
+        preceded(terminated(tag("Distance:"), space0), numbers)(distance_line)?;
+    if record_distances.len() != times.len() {
+        return Err(nom::Err::Failure(NomError::new(
+            distance_line,
+            Expected::Description("as many distances as times"),
+        )));
+    }
     let races = times
         .into_iter()
         .zip(record_distances)
@@ -41,18 +92,41 @@ fn parse_input(input: &str) -> IResult<&str, Vec<Race>> {
     Ok((input, races))
 }
 
-#[derive(Debug)]
-struct Race {
-    time: u64,
-    record_distance: u64,
+/// A number whose digits may be separated by spaces.
+fn parse_joined(input: &str) -> IResult<&str, u128> {
+    let (rest, digits) = separated_list1(space1, digit1)(input)?;
+    match digits.concat().parse() {
+        Ok(number) => Ok((rest, number)),
+        Err(_) => Err(nom::Err::Failure(NomError::new(
+            input,
+            Expected::Description("a number below 2^128"),
+        ))),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Race {
+    pub time: u128,
+    pub record_distance: u128,
 }
 
 impl Race {
-    fn count_winning_times(&self) -> usize {
-        (0_u64..=self.time)
-            .map(|press_time| press_time * (self.time - press_time))
-            .filter(|distance| distance > &self.record_distance)
-            .count()
+    /// The press times that beat the record with `model`, `None` if none does.
+    pub fn winning_times(&self, model: &(impl BoatModel + ?Sized)) -> Option<RangeInclusive<u128>> {
+        model.winning_times(self)
+    }
+
+    pub fn count_winning_times(&self, model: &(impl BoatModel + ?Sized)) -> u128 {
+        self.winning_times(model)
+            .map_or(0, |times| times.end() - times.start() + 1)
+    }
+
+    /// Whether holding the button for `press_time` beats the record with `model`.
+    pub fn wins(&self, model: &(impl BoatModel + ?Sized), press_time: u128) -> bool {
+        press_time <= self.time
+            && model
+                .distance(press_time, self.time)
+                .is_none_or(|distance| distance > self.record_distance)
     }
 }
 
@@ -65,13 +139,73 @@ Distance:  9  40  200";
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "288");
     }
 
+    #[test]
+    fn every_race_has_a_distance() {
+        for input in ["Time: 7 15\nDistance: 9", "Time: 7\nDistance: 9 40"] {
+            assert_eq!(
+                process_part_1(input),
+                Err(ParseError::new(2, 1, "as many distances as times"))
+            );
+        }
+    }
+
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "71503");
     }
+
+    #[test]
+    fn winning_times_are_exact() {
+        for time in 0..40 {
+            for record_distance in 0..time * time / 4 + 2 {
+                let race = Race {
+                    time,
+                    record_distance,
+                };
+                let winning =
+                    (0..=time).filter(|press_time| race.wins(&Linear::default(), *press_time));
+                let expected = winning
+                    .clone()
+                    .min()
+                    .map(|first| first..=winning.max().unwrap());
+                assert_eq!(
+                    race.winning_times(&Linear::default()),
+                    expected,
+                    "{:?}",
+                    race
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn huge_products_are_exact() {
+        let input = "Time: 100000000000000000000 100000000000000000000\nDistance: 1 1";
+        let count = 10_u128.pow(20) - 1;
+        let result = process_part_1(input).unwrap();
+        assert_eq!(result, (BigUint::from(count) * count).to_string());
+    }
+
+    #[test]
+    fn kerning_is_read() {
+        let races = parse_races(INPUT, Kerning::Joined).unwrap();
+        assert_eq!(
+            races,
+            [Race {
+                time: 71530,
+                record_distance: 940200
+            }]
+        );
+        let input = "Time: 3402823669209384634633746 07431768211456\nDistance: 1";
+        let result = parse_races(input, Kerning::Joined).unwrap_err();
+        assert_eq!(
+            result.to_string(),
+            "expected a number below 2^128 on line 1, column 7"
+        );
+    }
 }
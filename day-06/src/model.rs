@@ -0,0 +1,235 @@
+//! How far a boat goes for how long its button is held. The puzzle's boats gain one millimeter
+//! per millisecond of speed and keep it, the other models are what-ifs.
+
+use std::ops::RangeInclusive;
+
+use num::{BigUint, Integer};
+
+use crate::Race;
+
+/// The physics of the boats.
+pub trait BoatModel {
+    /// How far the boat goes when the button is held for `press_time` of a race of `time`,
+    /// `None` if that is farther than `u128::MAX`, which beats every record.
+    fn distance(&self, press_time: u128, time: u128) -> Option<u128>;
+
+    /// The press times that beat the record of `race`, `None` if none does. The default is a
+    /// binary search, which needs the distance to be concave in the press time.
+    fn winning_times(&self, race: &Race) -> Option<RangeInclusive<u128>> {
+        // distances that don't fit come after all the others
+        let distance = |press_time| {
+            let distance = self.distance(press_time, race.time);
+            (distance.is_none(), distance.unwrap_or_default())
+        };
+        let record = (false, race.record_distance);
+        let peak = partition_point(0, race.time, |press_time| {
+            distance(press_time + 1) > distance(press_time)
+        });
+        if distance(peak) <= record {
+            return None;
+        }
+        let first = partition_point(0, peak, |press_time| distance(press_time) <= record);
+        let last = partition_point(peak, race.time, |press_time| {
+            distance(press_time + 1) > record
+        });
+        Some(first..=last)
+    }
+}
+
+/// The speed grows by `rate` for every millisecond the button is held, the puzzle's boats have
+/// a rate of 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Linear {
+    pub rate: u128,
+}
+
+impl Default for Linear {
+    fn default() -> Self {
+        Self { rate: 1 }
+    }
+}
+
+impl BoatModel for Linear {
+    fn distance(&self, press_time: u128, time: u128) -> Option<u128> {
+        u128::try_from(BigUint::from(self.rate) * press_time * (time - press_time)).ok()
+    }
+
+    fn winning_times(&self, race: &Race) -> Option<RangeInclusive<u128>> {
+        // `rate * d` beats the record when `d` beats the record divided by the rate
+        let record_distance = race.record_distance.checked_div(self.rate)?;
+        quadratic_winning_times(race.time, record_distance)
+    }
+}
+
+/// Like [`Linear`], but the speed stops growing at `max_speed`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capped {
+    pub rate: u128,
+    pub max_speed: u128,
+}
+
+impl BoatModel for Capped {
+    fn distance(&self, press_time: u128, time: u128) -> Option<u128> {
+        let speed = self
+            .rate
+            .checked_mul(press_time)
+            .map_or(self.max_speed, |speed| speed.min(self.max_speed));
+        speed.checked_mul(time - press_time)
+    }
+}
+
+/// Like [`Linear`], but the boat loses `drag` of its speed every millisecond it moves, until it
+/// stops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Drag {
+    pub rate: u128,
+    pub drag: u128,
+}
+
+impl BoatModel for Drag {
+    fn distance(&self, press_time: u128, time: u128) -> Option<u128> {
+        let speed = BigUint::from(self.rate) * press_time;
+        let mut moving = BigUint::from(time - press_time);
+        if self.drag > 0 {
+            moving = moving.min(speed.div_ceil(&BigUint::from(self.drag)));
+        }
+        if moving == BigUint::ZERO {
+            return Some(0);
+        }
+        // the speeds while moving are `speed - drag * k` for `k` below `moving`
+        let lost = BigUint::from(self.drag) * &moving * (&moving - 1_u32) / 2_u32;
+        u128::try_from(speed * moving - lost).ok()
+    }
+}
+
+/// The press times whose distance `p * (time - p)` beats `record_distance`.
+fn quadratic_winning_times(time: u128, record_distance: u128) -> Option<RangeInclusive<u128>> {
+    // the distance beats the record between the roots of `p^2 - time * p + record_distance`,
+    // which are symmetric around `time / 2`
+    let wins = |press_time: u128| {
+        press_time
+            .checked_mul(time - press_time)
+            .is_none_or(|distance| distance > record_distance)
+    };
+    let squared = BigUint::from(time) * time;
+    let record = BigUint::from(record_distance) << 2;
+    if squared <= record {
+        return None;
+    }
+    let root = (squared - record).sqrt();
+    // at most one below the first winning time
+    let estimate = u128::try_from((BigUint::from(time) - root) >> 1).expect("below the time");
+    let first = if wins(estimate) {
+        estimate
+    } else {
+        estimate + 1
+    };
+    let last = time - first;
+    (first <= last && wins(first)).then_some(first..=last)
+}
+
+/// The first value of `start..end` for which `predicate` is false, `end` if there is none.
+/// `predicate` must be true up to some value and false from there.
+fn partition_point(mut start: u128, mut end: u128, predicate: impl Fn(u128) -> bool) -> u128 {
+    while start < end {
+        let middle = start + (end - start) / 2;
+        if predicate(middle) {
+            start = middle + 1;
+        } else {
+            end = middle;
+        }
+    }
+    start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Compares the solver of `model` with trying every press time.
+    fn check(model: &dyn BoatModel) {
+        for time in 0..30 {
+            for record_distance in 0..200 {
+                let race = Race {
+                    time,
+                    record_distance,
+                };
+                let winning = (0..=time)
+                    .filter(|press_time| race.wins(model, *press_time))
+                    .collect::<Vec<_>>();
+                let expected = winning
+                    .first()
+                    .map(|first| *first..=*winning.last().unwrap());
+                assert_eq!(
+                    expected.clone().map_or(0, |times| times.count()),
+                    winning.len(),
+                    "the winning times of {:?} have gaps",
+                    race
+                );
+                assert_eq!(model.winning_times(&race), expected, "{:?}", race);
+            }
+        }
+    }
+
+    #[test]
+    fn models_are_solved() {
+        check(&Linear::default());
+        check(&Linear { rate: 3 });
+        check(&Capped {
+            rate: 2,
+            max_speed: 9,
+        });
+        check(&Drag { rate: 2, drag: 1 });
+        check(&Drag { rate: 1, drag: 3 });
+    }
+
+    #[test]
+    fn huge_races_are_solved() {
+        let capped = Capped {
+            rate: 7,
+            max_speed: 10_u128.pow(12),
+        };
+        let races: [(&dyn BoatModel, u128, u128); 6] = [
+            (&Linear::default(), 10_u128.pow(15), 10_u128.pow(29)),
+            (
+                &Linear::default(),
+                u64::MAX as u128 * 3,
+                u64::MAX as u128 * u64::MAX as u128,
+            ),
+            (&Linear::default(), u128::MAX, u128::MAX),
+            (&Linear { rate: 3 }, u128::MAX, u128::MAX / 2),
+            (&capped, 10_u128.pow(18), 10_u128.pow(28)),
+            (&Drag { rate: 5, drag: 3 }, 10_u128.pow(18), 10_u128.pow(28)),
+        ];
+        for (model, time, record_distance) in races {
+            let race = Race {
+                time,
+                record_distance,
+            };
+            let times = model.winning_times(&race).unwrap();
+            let (first, last) = (*times.start(), *times.end());
+            assert!(
+                race.wins(model, first) && !race.wins(model, first - 1),
+                "{:?}",
+                race
+            );
+            assert!(
+                race.wins(model, last) && !race.wins(model, last + 1),
+                "{:?}",
+                race
+            );
+        }
+    }
+
+    #[test]
+    fn drag_slows_the_boat_down() {
+        let drag = Drag { rate: 1, drag: 2 };
+        // speeds 5, 3 and 1 before it stops
+        assert_eq!(drag.distance(5, 20), Some(9));
+        assert_eq!(drag.distance(5, 7), Some(8));
+        assert_eq!(
+            Drag { rate: 1, drag: 0 }.distance(5, 20),
+            Linear::default().distance(5, 20)
+        );
+    }
+}
@@ -0,0 +1,10 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_07::Day07;
+use solver::Part;
+
+fn bench(c: &mut Criterion) {
+    bench::bench_day(c, &Day07, &Part::ALL);
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
@@ -1,8 +1,31 @@
 mod part_1;
 mod part_2;
 
-pub use part_1::process_part_1;
-pub use part_2::process_part_2;
+use solver::{ParseError, Part, Run, Solver};
+
+pub struct Day07;
+
+impl Solver for Day07 {
+    fn day(&self) -> u8 {
+        7
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        // jacks are valued differently in the two parts, so each has its own parser
+        match part {
+            Part::One => Run::timed(|| part_1::parse(input), part_1::solve),
+            Part::Two => Run::timed(|| part_2::parse(input), part_2::solve),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day07.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day07.solve(Part::Two, input)
+}
 
 #[cfg(test)]
 mod tests {
@@ -16,13 +39,13 @@ QQQJA 483";
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "6440");
     }
 
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "5905");
     }
 }
@@ -1,15 +1,18 @@
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     character::complete::{self, line_ending, space1},
     multi::separated_list1,
     sequence::{separated_pair, tuple},
-    IResult, Parser,
+    Parser,
 };
+use solver::parse::{parse_all, tag, IResult};
+use solver::ParseError;
 
-pub fn process_part_1(input: &str) -> String {
-    let (_, hands) = parse_input(input).unwrap();
-    let mut hands = hands;
+pub fn parse(input: &str) -> Result<Vec<Hand>, ParseError> {
+    parse_all(input, parse_input)
+}
+
+pub fn solve(mut hands: Vec<Hand>) -> String {
     hands.sort();
     hands
         .iter()
@@ -76,7 +79,7 @@ fn parse_card(input: &str) -> IResult<&str, u8> {
 }
 
 #[derive(Debug)]
-struct Hand {
+pub struct Hand {
     cards: [u8; 5],
     bid: u64,
     hand_type: HandType,
@@ -2,16 +2,19 @@ use std::collections::BTreeMap;
 
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     character::complete::{self, line_ending, space1},
     multi::separated_list1,
     sequence::{separated_pair, tuple},
-    IResult, Parser,
+    Parser,
 };
+use solver::parse::{parse_all, tag, IResult};
+use solver::ParseError;
 
-pub fn process_part_2(input: &str) -> String {
-    let (_, hands) = parse_input(input).unwrap();
-    let mut hands = hands;
+pub fn parse(input: &str) -> Result<Vec<Hand>, ParseError> {
+    parse_all(input, parse_input)
+}
+
+pub fn solve(mut hands: Vec<Hand>) -> String {
     hands.sort();
     hands
         .iter()
@@ -78,7 +81,7 @@ fn parse_card(input: &str) -> IResult<&str, u8> {
 }
 
 #[derive(Debug)]
-struct Hand {
+pub struct Hand {
     cards: [u8; 5],
     bid: u64,
     hand_type: HandType,
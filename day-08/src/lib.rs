@@ -4,15 +4,39 @@ use num::integer;
 
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     character::complete::{alphanumeric1, line_ending},
     multi::{many0, many1, separated_list1},
     sequence::{delimited, separated_pair},
-    IResult, Parser,
+    Parser,
 };
+use solver::parse::{parse_all, tag, IResult};
+use solver::{ParseError, Part, Run, Solver};
 
-pub fn process_part_1(input: &str) -> String {
-    let (_, (instructions, nodes)) = parse_input(input).unwrap();
+pub struct Day08;
+
+impl Solver for Day08 {
+    fn day(&self) -> u8 {
+        8
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || parse_all(input, parse_input);
+        match part {
+            Part::One => Run::timed(parse, part_1),
+            Part::Two => Run::timed(parse, part_2),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day08.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day08.solve(Part::Two, input)
+}
+
+fn part_1((instructions, nodes): (Vec<Instruction>, Vec<Node>)) -> String {
     let nodes = nodes
         .into_iter()
         .map(|node| (node.label, node))
@@ -29,8 +53,7 @@ pub fn process_part_1(input: &str) -> String {
     steps.to_string()
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let (_, (instructions, nodes)) = parse_input(input).unwrap();
+fn part_2((instructions, nodes): (Vec<Instruction>, Vec<Node>)) -> String {
     let nodes = nodes
         .into_iter()
         .map(|node| (node.label, node))
@@ -120,7 +143,7 @@ ZZZ = (ZZZ, ZZZ)";
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT_1);
+        let result = process_part_1(INPUT_1).unwrap();
         assert_eq!(result, "6");
     }
 
@@ -137,7 +160,7 @@ XXX = (XXX, XXX)";
 
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT_2);
+        let result = process_part_2(INPUT_2).unwrap();
         assert_eq!(result, "6");
     }
 }
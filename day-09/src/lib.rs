@@ -1,79 +1,120 @@
+use std::io::BufRead;
+
 use itertools::Itertools;
+use nom::{
+    character::complete::{self, line_ending},
+    multi::separated_list1,
+};
+use solver::parse::{parse_all, tag, IResult};
+use solver::stream::for_each_record;
+use solver::{ParseError, Part, Run, Solver, StreamError, StreamSolver};
+
+pub struct Day09;
+
+impl Solver for Day09 {
+    fn day(&self) -> u8 {
+        9
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || parse_all(input, parse_input);
+        match part {
+            Part::One => Run::timed(parse, part_1),
+            Part::Two => Run::timed(parse, part_2),
+        }
+    }
+}
+
+impl StreamSolver for Day09 {
+    fn solve_stream(&self, part: Part, reader: &mut dyn BufRead) -> Result<String, StreamError> {
+        let extrapolate = match part {
+            Part::One => next_value,
+            Part::Two => previous_value,
+        };
+        let mut sum = 0;
+        for_each_record(reader, b'\n', |line| {
+            let history = parse_all(line, parse_history)?;
+            sum += i64::from(extrapolate(&history));
+            Ok(())
+        })?;
+        Ok(sum.to_string())
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day09.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day09.solve(Part::Two, input)
+}
 
-pub fn process_part_1(input: &str) -> String {
-    let histories = parse_input(input);
+fn part_1(histories: Vec<Vec<i32>>) -> String {
     histories
         .iter()
-        .map(|history| {
-            let mut differences = vec![];
-            differences.push(history.clone());
-            loop {
-                let diff = differences
-                    .last()
-                    .unwrap()
-                    .iter()
-                    .tuple_windows()
-                    .map(|(a, b)| b - a)
-                    .collect::<Vec<i32>>();
-                if diff.iter().all(|d| *d == 0) {
-                    differences.push(diff);
-                    break;
-                } else {
-                    differences.push(diff);
-                }
-            }
-            let mut last_diff = 0;
-            differences.iter().rev().skip(1).for_each(|diff| {
-                last_diff += diff.last().unwrap();
-            });
-            last_diff
-        })
-        .sum::<i32>()
+        .map(|history| i64::from(next_value(history)))
+        .sum::<i64>()
         .to_string()
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let histories = parse_input(input);
+fn part_2(histories: Vec<Vec<i32>>) -> String {
     histories
         .iter()
-        .map(|history| {
-            let mut differences = vec![];
-            differences.push(history.clone());
-            loop {
-                let diff = differences
-                    .last()
-                    .unwrap()
-                    .iter()
-                    .tuple_windows()
-                    .map(|(a, b)| b - a)
-                    .collect::<Vec<i32>>();
-                if diff.iter().all(|d| *d == 0) {
-                    differences.push(diff);
-                    break;
-                } else {
-                    differences.push(diff);
-                }
-            }
-            let mut last_diff = 0;
-            differences.iter().rev().skip(1).for_each(|diff| {
-                last_diff = diff.first().unwrap() - last_diff;
-            });
-            last_diff
-        })
-        .sum::<i32>()
+        .map(|history| i64::from(previous_value(history)))
+        .sum::<i64>()
         .to_string()
 }
 
-fn parse_input(input: &str) -> Vec<Vec<i32>> {
-    input
-        .lines()
-        .map(|line| line.split(' ').map(|part| part.parse().unwrap()).collect())
-        .collect()
+/// The history followed by its differences, down to the row that is all zeroes.
+fn differences(history: &[i32]) -> Vec<Vec<i32>> {
+    let mut differences = vec![];
+    differences.push(history.to_vec());
+    loop {
+        let diff = differences
+            .last()
+            .unwrap()
+            .iter()
+            .tuple_windows()
+            .map(|(a, b)| b - a)
+            .collect::<Vec<i32>>();
+        if diff.iter().all(|d| *d == 0) {
+            differences.push(diff);
+            break;
+        } else {
+            differences.push(diff);
+        }
+    }
+    differences
+}
+
+fn next_value(history: &[i32]) -> i32 {
+    let mut last_diff = 0;
+    differences(history).iter().rev().skip(1).for_each(|diff| {
+        last_diff += diff.last().unwrap();
+    });
+    last_diff
+}
+
+fn previous_value(history: &[i32]) -> i32 {
+    let mut last_diff = 0;
+    differences(history).iter().rev().skip(1).for_each(|diff| {
+        last_diff = diff.first().unwrap() - last_diff;
+    });
+    last_diff
+}
+
+fn parse_input(input: &str) -> IResult<&str, Vec<Vec<i32>>> {
+    separated_list1(line_ending, parse_history)(input)
+}
+
+fn parse_history(input: &str) -> IResult<&str, Vec<i32>> {
+    separated_list1(tag(" "), complete::i32)(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     const INPUT: &str = "0 3 6 9 12 15
 1 3 6 10 15 21
@@ -81,13 +122,60 @@ mod tests {
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "114");
     }
 
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "2");
     }
+
+    #[test]
+    fn solve_stream_works() {
+        let result = Day09.solve_stream(Part::One, &mut INPUT.as_bytes());
+        assert_eq!(result.unwrap(), "114");
+        let result = Day09.solve_stream(Part::Two, &mut INPUT.as_bytes());
+        assert_eq!(result.unwrap(), "2");
+        let result = Day09.solve_stream(Part::One, &mut "0 3 6\n1 x 3\n".as_bytes());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "expected tag or a number on line 2, column 3"
+        );
+    }
+
+    fn evaluate(coefficients: &[i32], x: i32) -> i32 {
+        coefficients
+            .iter()
+            .rev()
+            .fold(0, |value, coefficient| value * x + coefficient)
+    }
+
+    /// Coefficients of a polynomial together with the number of values of its history, which is
+    /// always enough to extrapolate it.
+    fn polynomial() -> impl Strategy<Value = (Vec<i32>, usize)> {
+        prop::collection::vec(-5..=5, 1..=4).prop_flat_map(|coefficients| {
+            let degree = coefficients.len() - 1;
+            (Just(coefficients), degree + 2..degree + 10)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn polynomials_are_extrapolated((coefficients, length) in polynomial()) {
+            let history = (0..length as i32)
+                .map(|x| evaluate(&coefficients, x).to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+            prop_assert_eq!(
+                process_part_1(&history).unwrap(),
+                evaluate(&coefficients, length as i32).to_string()
+            );
+            prop_assert_eq!(
+                process_part_2(&history).unwrap(),
+                evaluate(&coefficients, -1).to_string()
+            );
+        }
+    }
 }
@@ -1,9 +1,52 @@
+use grid::{Direction, Grid, Position};
+use render::{Color, Picture};
+use solver::{diagnostics, ParseError, Part, Run, Solver};
 use std::{collections::HashSet, fmt};
 
-type TileMap = Vec<Vec<Tile>>;
+const START: Color = Color(220, 30, 30);
+const NEAR: Color = Color(120, 190, 255);
+const FAR: Color = Color(10, 30, 120);
+const LOOP: Color = Color(30, 60, 160);
+const INSIDE: Color = Color(60, 180, 75);
+const OUTSIDE: Color = Color(255, 225, 170);
+const OTHER_PIPE: Color = Color(200, 200, 200);
 
-pub fn process_part_1(input: &str) -> String {
-    let (map, start) = parse_input(input);
+const TILES: [(char, Tile); 8] = [
+    ('|', Tile::Pipe(Direction::North, Direction::South)),
+    ('-', Tile::Pipe(Direction::East, Direction::West)),
+    ('L', Tile::Pipe(Direction::North, Direction::East)),
+    ('J', Tile::Pipe(Direction::North, Direction::West)),
+    ('7', Tile::Pipe(Direction::South, Direction::West)),
+    ('F', Tile::Pipe(Direction::South, Direction::East)),
+    ('.', Tile::Ground),
+    ('S', Tile::Start),
+];
+
+pub struct Day10;
+
+impl Solver for Day10 {
+    fn day(&self) -> u8 {
+        10
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || parse_input(input);
+        match part {
+            Part::One => Run::timed(parse, part_1),
+            Part::Two => Run::timed(parse, part_2),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day10.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day10.solve(Part::Two, input)
+}
+
+fn part_1((map, start): (Grid<Tile>, Position)) -> String {
     let length = [
         Direction::North,
         Direction::South,
@@ -36,14 +79,95 @@ pub fn process_part_1(input: &str) -> String {
             None
         }
     })
-    .last()
+    .next_back()
     .unwrap();
     (length / 2).to_string()
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let (map, start) = parse_input(input);
-    let (pipe_loop, start_tile) = [
+fn part_2((map, start): (Grid<Tile>, Position)) -> String {
+    let (pipe_loop, start_tile) = find_loop(&map, start);
+    let pipe_loop = pipe_loop.into_iter().collect::<HashSet<_>>();
+    let inside_positions = find_inside(&map, &pipe_loop, start_tile);
+
+    if diagnostics::enabled() {
+        let tiles = map
+            .iter()
+            .map(|(position, tile)| {
+                if pipe_loop.contains(&position) {
+                    tile.to_string()
+                } else if inside_positions.contains(&position) {
+                    "I".to_string()
+                } else {
+                    "O".to_string()
+                }
+            })
+            .collect();
+        diagnostics::print(Grid::new(map.width(), map.height(), tiles));
+    }
+
+    inside_positions.len().to_string()
+}
+
+/// Draws the loop, for part 1 shaded by the distance from the start and for part 2 with the
+/// tiles inside and outside of it.
+pub fn render(part: Part, input: &str) -> Result<Picture, ParseError> {
+    let (map, start) = parse_input(input)?;
+    let (pipe_loop, start_tile) = find_loop(&map, start);
+    let farthest = pipe_loop.len() / 2;
+    let mut picture = match part {
+        Part::One => {
+            let mut distances = Grid::filled(map.width(), map.height(), None);
+            for (i, position) in pipe_loop.iter().enumerate() {
+                distances[*position] = Some((i + 1).min(pipe_loop.len() - i - 1));
+            }
+            let mut picture = Picture::new(
+                map.width() as f64,
+                map.height() as f64,
+                format!("farthest from the start: {} steps", farthest),
+            );
+            picture.cells(&map, |position, tile| match (tile, distances[position]) {
+                (Tile::Start, _) => Some(START),
+                (_, Some(distance)) => Some(NEAR.mix(FAR, distance as f64 / farthest as f64)),
+                (Tile::Pipe(_, _), None) => Some(OTHER_PIPE),
+                (Tile::Ground, None) => None,
+            });
+            picture.legend(NEAR, "loop, near the start");
+            picture.legend(FAR, format!("loop, {} steps away", farthest));
+            picture.legend(OTHER_PIPE, "pipe off the loop");
+            picture
+        }
+        Part::Two => {
+            let pipe_loop = pipe_loop.into_iter().collect::<HashSet<_>>();
+            let inside = find_inside(&map, &pipe_loop, start_tile)
+                .into_iter()
+                .collect::<HashSet<_>>();
+            let mut picture = Picture::new(
+                map.width() as f64,
+                map.height() as f64,
+                format!("{} tiles inside the loop", inside.len()),
+            );
+            picture.cells(&map, |position, tile| {
+                Some(match tile {
+                    Tile::Start => START,
+                    _ if pipe_loop.contains(&position) => LOOP,
+                    _ if inside.contains(&position) => INSIDE,
+                    _ => OUTSIDE,
+                })
+            });
+            picture.legend(LOOP, "loop");
+            picture.legend(INSIDE, "inside");
+            picture.legend(OUTSIDE, "outside");
+            picture
+        }
+    };
+    picture.legend(START, "start");
+    Ok(picture)
+}
+
+/// The positions of the loop through the start in the order they are visited, ending with the
+/// start, and the pipe under the start.
+fn find_loop(map: &Grid<Tile>, start: Position) -> (Vec<Position>, Tile) {
+    [
         Direction::North,
         Direction::South,
         Direction::East,
@@ -55,16 +179,16 @@ pub fn process_part_2(input: &str) -> String {
         let mut direction = *starting_direction;
         let mut length = 0;
         let mut done = false;
-        let mut positions = HashSet::new();
+        let mut positions = vec![];
         let mut start_tile = None;
         while length < 100_000_000 {
-            match next_pipe_and_direction(&position, &direction, &map) {
+            match next_pipe_and_direction(&position, &direction, map) {
                 Some((next_position, next_direction)) => {
                     let prev_direction = direction;
                     position = next_position;
                     direction = next_direction;
                     length += 1;
-                    positions.insert(position);
+                    positions.push(position);
                     if position == start {
                         done = true;
                         start_tile =
@@ -81,15 +205,19 @@ pub fn process_part_2(input: &str) -> String {
             None
         }
     })
-    .last()
-    .unwrap();
+    .next_back()
+    .unwrap()
+}
+
+/// The tiles enclosed by the loop, found by counting the crossings of the loop left of them.
+fn find_inside(map: &Grid<Tile>, pipe_loop: &HashSet<Position>, start_tile: Tile) -> Vec<Position> {
     let mut inside_positions = vec![];
-    for y in 0..map.len() as i32 {
+    for y in 0..map.height() as i32 {
         let mut entry_tile = None;
         let mut crossing_count = 0;
-        for x in 0..map[y as usize].len() as i32 {
+        for x in 0..map.width() as i32 {
             let position = Position::new(x, y);
-            let tile = &map[y as usize][x as usize];
+            let tile = &map[position];
             let tile = match tile {
                 Tile::Start => &start_tile,
                 _ => tile,
@@ -134,33 +262,16 @@ pub fn process_part_2(input: &str) -> String {
             }
         }
     }
-
-    for (y, line) in map.iter().enumerate() {
-        for (x, tile) in line.iter().enumerate() {
-            let position = Position::new(x as i32, y as i32);
-            if pipe_loop.contains(&position) {
-                print!("{}", tile);
-            } else if inside_positions.contains(&position) {
-                print!("I");
-            } else {
-                print!("O");
-            }
-        }
-        println!();
-    }
-
-    inside_positions.len().to_string()
+    inside_positions
 }
 
 fn next_pipe_and_direction(
     position: &Position,
     direction: &Direction,
-    map: &TileMap,
+    map: &Grid<Tile>,
 ) -> Option<(Position, Direction)> {
-    let next_position = direction.next_position(position);
-    let next_tile = map
-        .get(next_position.y as usize)?
-        .get(next_position.x as usize)?;
+    let next_position = position.move_in_direction(*direction);
+    let next_tile = map.get(next_position)?;
     match next_tile {
         Tile::Pipe(direction_1, direction_2) => {
             let opposite = direction.opposite();
@@ -178,90 +289,15 @@ fn next_pipe_and_direction(
 }
 
 fn is_odd(num: u32) -> bool {
-    num % 2 != 0
+    !num.is_multiple_of(2)
 }
 
-fn parse_input(input: &str) -> (Vec<Vec<Tile>>, Position) {
-    let mut start: Option<Position> = None;
-    let map = input
-        .lines()
-        .enumerate()
-        .map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(x, c)| match c {
-                    '|' => Tile::Pipe(Direction::North, Direction::South),
-                    '-' => Tile::Pipe(Direction::East, Direction::West),
-                    'L' => Tile::Pipe(Direction::North, Direction::East),
-                    'J' => Tile::Pipe(Direction::North, Direction::West),
-                    '7' => Tile::Pipe(Direction::South, Direction::West),
-                    'F' => Tile::Pipe(Direction::South, Direction::East),
-                    '.' => Tile::Ground,
-                    'S' => {
-                        start = Some(Position {
-                            x: x as i32,
-                            y: y as i32,
-                        });
-                        Tile::Start
-                    }
-                    _ => panic!("Invalid character at ({}, {}): {}", x, y, c),
-                })
-                .collect()
-        })
-        .collect();
-    (map, start.expect("No start found"))
-}
-
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
-
-impl Direction {
-    fn opposite(&self) -> Self {
-        match self {
-            Direction::North => Direction::South,
-            Direction::South => Direction::North,
-            Direction::East => Direction::West,
-            Direction::West => Direction::East,
-        }
-    }
-
-    fn next_position(&self, position: &Position) -> Position {
-        match self {
-            Direction::North => Position {
-                x: position.x,
-                y: position.y - 1,
-            },
-            Direction::South => Position {
-                x: position.x,
-                y: position.y + 1,
-            },
-            Direction::East => Position {
-                x: position.x + 1,
-                y: position.y,
-            },
-            Direction::West => Position {
-                x: position.x - 1,
-                y: position.y,
-            },
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Position {
-    x: i32,
-    y: i32,
-}
-
-impl Position {
-    fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
-    }
+fn parse_input(input: &str) -> Result<(Grid<Tile>, Position), ParseError> {
+    let map = Grid::parse(input, &TILES)?;
+    let start = map
+        .find(|tile| matches!(tile, Tile::Start))
+        .ok_or_else(|| ParseError::at_offset(input, input.len(), "a start tile 'S'"))?;
+    Ok((map, start))
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -367,17 +403,30 @@ L7JLJL-JLJLJL--JLJ.L";
     #[case(INPUT_3, "8")]
     #[case(INPUT_4, "8")]
     fn part_1_works(#[case] input: &str, #[case] expected: &str) {
-        let result = process_part_1(input);
+        let result = process_part_1(input).unwrap();
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn render_works() {
+        let svg = render(Part::One, INPUT_3).unwrap().to_svg();
+        assert!(svg.contains(">farthest from the start: 8 steps</text>"));
+        // the start and the farthest tile
+        assert!(svg.contains(r##"<rect x="0" y="2" width="1" height="1" fill="#dc1e1e"/>"##));
+        assert!(svg.contains(r##"<rect x="4" y="2" width="1" height="1" fill="#0a1e78"/>"##));
+
+        let svg = render(Part::Two, INPUT_5).unwrap().to_svg();
+        assert!(svg.contains(">4 tiles inside the loop</text>"));
+        assert!(svg.contains(r##"<rect x="2" y="6" width="2" height="1" fill="#3cb44b"/>"##));
+    }
+
     #[rstest]
     #[case(INPUT_5, "4")]
     #[case(INPUT_6, "4")]
     #[case(INPUT_7, "8")]
     #[case(INPUT_8, "10")]
     fn part_2_works(#[case] input: &str, #[case] expected: &str) {
-        let result = process_part_2(input);
+        let result = process_part_2(input).unwrap();
         assert_eq!(result, expected);
     }
 }
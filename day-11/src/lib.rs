@@ -1,79 +1,162 @@
-use std::ops::Not;
+use grid::Grid;
+use render::{Color, Picture};
+use solver::{params, ParseError, Part, Run, Solver};
 
-pub fn process_part_1(input: &str) -> String {
-    process(input, 2)
+const GALAXY: Color = Color(250, 200, 40);
+const SPACE: Color = Color(15, 20, 50);
+const EXPANDED: Color = Color(70, 40, 110);
+
+const TILES: [(char, Tile); 2] = [('#', Tile::Galaxy), ('.', Tile::Space)];
+
+pub struct Day11;
+
+/// How many rows or columns an empty one grows to in part 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Day11Params {
+    pub expansion: i64,
+}
+
+impl Default for Day11Params {
+    fn default() -> Self {
+        Self {
+            expansion: 1_000_000,
+        }
+    }
+}
+
+impl params::Params for Day11Params {
+    const NAMES: &'static [&'static str] = &["expansion"];
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match params::parse(name, value)? {
+            expansion if expansion > 0 => self.expansion = expansion,
+            _ => {
+                return Err(format!(
+                    "Invalid value {} of {}: expected at least 1",
+                    value, name
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Solver for Day11 {
+    fn day(&self) -> u8 {
+        11
+    }
+
+    fn check_params(&self, overrides: &[(String, String)]) -> Result<(), String> {
+        params::with_overrides::<Day11Params>(overrides).map(|_| ())
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || Grid::parse(input, &TILES);
+        let expansion = expansion(part);
+        Run::timed(parse, |image| sum_of_distances(&image, expansion))
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day11.solve(Part::One, input)
 }
 
-pub fn process_part_2(input: &str) -> String {
-    process(input, 1_000_000)
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day11.solve(Part::Two, input)
 }
 
-fn process(input: &str, expansion: i64) -> String {
-    let start_map = parse_input(input);
-    let expanded_star_map = expand_star_map(&start_map, expansion);
+fn expansion(part: Part) -> i64 {
+    match part {
+        Part::One => 2,
+        Part::Two => params::current::<Day11Params>().expansion,
+    }
+}
+
+fn sum_of_distances(image: &Grid<Tile>, expansion: i64) -> String {
+    let expanded_star_map = expand_star_map(image, expansion);
     expanded_star_map
         .iter()
         .enumerate()
-        .map(|(i, star)| {
+        .map(|(i, (x, y))| {
             expanded_star_map
                 .iter()
                 .skip(i + 1)
-                .map(|other_star| (star.x - other_star.x).abs() + (star.y - other_star.y).abs())
+                .map(|(other_x, other_y)| (x - other_x).abs() + (y - other_y).abs())
                 .sum::<i64>()
         })
         .sum::<i64>()
         .to_string()
 }
 
-fn parse_input(input: &str) -> Vec<Position> {
-    input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line.chars().enumerate().filter_map(move |(x, c)| {
-                if c == '#' {
-                    Some(Position::new(x as i64, y as i64))
-                } else {
-                    None
-                }
-            })
-        })
-        .collect()
+/// Draws the image with the empty rows and columns doubled like in part 1. They grow a million
+/// times in part 2, which can't be drawn to scale, so only the legend tells.
+pub fn render(part: Part, input: &str) -> Result<Picture, ParseError> {
+    let image = Grid::parse(input, &TILES)?;
+    let expansion = expansion(part);
+    let (empty_x, empty_y) = empty_columns_and_rows(&image);
+    let width = (image.width() + empty_x.len()) as f64;
+    let height = (image.height() + empty_y.len()) as f64;
+    let mut picture = Picture::new(
+        width,
+        height,
+        format!(
+            "sum of the shortest paths: {}",
+            sum_of_distances(&image, expansion)
+        ),
+    );
+    picture.rect(0.0, 0.0, width, height, SPACE);
+    for (i, x) in empty_x.iter().enumerate() {
+        picture.rect((x + i) as f64, 0.0, 2.0, height, EXPANDED);
+    }
+    for (i, y) in empty_y.iter().enumerate() {
+        picture.rect(0.0, (y + i) as f64, width, 2.0, EXPANDED);
+    }
+    for (x, y) in expand_star_map(&image, 2) {
+        picture.rect(x as f64, y as f64, 1.0, 1.0, GALAXY);
+    }
+    picture.legend(GALAXY, "galaxy");
+    picture.legend(
+        EXPANDED,
+        format!("empty row or column, counted {} times", expansion),
+    );
+    Ok(picture)
 }
 
-fn expand_star_map(star_map: &[Position], expansion: i64) -> Vec<Position> {
-    let min_x = star_map.iter().min_by(|p1, p2| p1.x.cmp(&p2.x)).unwrap().x;
-    let max_x = star_map.iter().max_by(|p1, p2| p1.x.cmp(&p2.x)).unwrap().x;
-    let min_y = star_map.iter().min_by(|p1, p2| p1.y.cmp(&p2.y)).unwrap().y;
-    let max_y = star_map.iter().max_by(|p1, p2| p1.y.cmp(&p2.y)).unwrap().y;
-    let empty_x = (min_x..=max_x)
-        .filter(|x| star_map.iter().any(|p| p.x == *x).not())
-        .collect::<Vec<i64>>();
-    let empty_y = (min_y..=max_y)
-        .filter(|y| star_map.iter().any(|p| p.y == *y).not())
-        .collect::<Vec<i64>>();
-    star_map
+/// Positions of the galaxies after every empty row and column grew to `expansion` rows or
+/// columns.
+fn expand_star_map(image: &Grid<Tile>, expansion: i64) -> Vec<(i64, i64)> {
+    let (empty_x, empty_y) = empty_columns_and_rows(image);
+    image
         .iter()
-        .map(|p| {
-            let dx = empty_x.iter().filter(|x| x < &&p.x).count() as i64;
-            let dy = empty_y.iter().filter(|y| y < &&p.y).count() as i64;
+        .filter(|(_, tile)| **tile == Tile::Galaxy)
+        .map(|(p, _)| {
+            let dx = empty_x.iter().filter(|x| **x < p.x as usize).count() as i64;
+            let dy = empty_y.iter().filter(|y| **y < p.y as usize).count() as i64;
             let dx = dx * expansion - dx;
             let dy = dy * expansion - dy;
-            Position::new(p.x + dx, p.y + dy)
+            (p.x as i64 + dx, p.y as i64 + dy)
         })
         .collect()
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Position {
-    x: i64,
-    y: i64,
+fn empty_columns_and_rows(image: &Grid<Tile>) -> (Vec<usize>, Vec<usize>) {
+    let empty_x = image
+        .columns()
+        .enumerate()
+        .filter_map(|(x, mut column)| column.all(|tile| *tile == Tile::Space).then_some(x))
+        .collect::<Vec<_>>();
+    let empty_y = image
+        .rows()
+        .enumerate()
+        .filter_map(|(y, row)| row.iter().all(|tile| *tile == Tile::Space).then_some(y))
+        .collect::<Vec<_>>();
+    (empty_x, empty_y)
 }
 
-impl Position {
-    fn new(x: i64, y: i64) -> Self {
-        Self { x, y }
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Galaxy,
+    Space,
 }
 
 #[cfg(test)]
@@ -94,15 +177,38 @@ mod tests {
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "374");
     }
 
+    #[test]
+    fn render_works() {
+        let svg = render(Part::One, INPUT).unwrap().to_svg();
+        assert!(svg.contains(">sum of the shortest paths: 374</text>"));
+        assert!(svg.contains(r##"<rect x="0" y="0" width="13" height="12" fill="#0f1432"/>"##));
+        // the first empty column and the last galaxy
+        assert!(svg.contains(r##"<rect x="2" y="0" width="2" height="12" fill="#46286e"/>"##));
+        assert!(svg.contains(r##"<rect x="5" y="11" width="1" height="1" fill="#fac828"/>"##));
+        let svg = render(Part::Two, INPUT).unwrap().to_svg();
+        assert!(svg.contains(">empty row or column, counted 1000000 times</text>"));
+    }
+
     #[rstest]
     #[case(INPUT, 10, "1030")]
     #[case(INPUT, 100, "8410")]
     fn part_2_works(#[case] input: &str, #[case] expansion: i64, #[case] expected: &str) {
-        let result = process(input, expansion);
+        let image = Grid::parse(input, &TILES).unwrap();
+        let result = sum_of_distances(&image, expansion);
         assert_eq!(result, expected);
     }
+
+    #[rstest]
+    #[case("1", Ok(Day11Params { expansion: 1 }))]
+    #[case("0", Err("Invalid value 0 of expansion: expected at least 1"))]
+    #[case("-5", Err("Invalid value -5 of expansion: expected at least 1"))]
+    fn expansion_is_positive(#[case] value: &str, #[case] expected: Result<Day11Params, &str>) {
+        let overrides = [("expansion".to_string(), value.to_string())];
+        let params = params::with_overrides::<Day11Params>(&overrides);
+        assert_eq!(params, expected.map_err(str::to_string));
+    }
 }
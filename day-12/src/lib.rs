@@ -1,17 +1,42 @@
+use std::collections::HashMap;
+
 use itertools::{repeat_n, Itertools};
-use memoize::memoize;
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     character::complete::{self, line_ending},
     multi::{many1, separated_list1},
     sequence::separated_pair,
-    IResult, Parser,
+    Parser,
 };
 use rayon::prelude::*;
+use solver::parse::{parse_all, tag, IResult};
+use solver::{ParseError, Part, Run, Solver};
+
+pub struct Day12;
+
+impl Solver for Day12 {
+    fn day(&self) -> u8 {
+        12
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || parse_all(input, parse_input);
+        match part {
+            Part::One => Run::timed(parse, part_1),
+            Part::Two => Run::timed(parse, part_2),
+        }
+    }
+}
 
-pub fn process_part_1(input: &str) -> String {
-    let (_, rows) = parse_input(input).unwrap();
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day12.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day12.solve(Part::Two, input)
+}
+
+fn part_1(rows: Vec<Row>) -> String {
     let options = vec![SpringState::Operational, SpringState::Damaged];
     rows.par_iter()
         .map(|row| {
@@ -37,8 +62,7 @@ pub fn process_part_1(input: &str) -> String {
         .to_string()
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let (_, rows) = parse_input(input).unwrap();
+fn part_2(rows: Vec<Row>) -> String {
     let rows = rows.into_iter().map(expand_row).collect::<Vec<_>>();
     rows.into_iter()
         .map(|row| count_options(row.spring_states, row.damaged_springs))
@@ -108,8 +132,31 @@ fn calc_damaged_springs(spring_states: &[SpringState]) -> Vec<u32> {
     damaged_springs
 }
 
-#[memoize]
+type OptionsCache = HashMap<(Vec<SpringState>, Vec<u32>), u64>;
+
 fn count_options(spring_states: Vec<SpringState>, damaged_springs: Vec<u32>) -> u64 {
+    count_options_cached(&spring_states, &damaged_springs, &mut HashMap::new())
+}
+
+fn count_options_cached(
+    spring_states: &[SpringState],
+    damaged_springs: &[u32],
+    cache: &mut OptionsCache,
+) -> u64 {
+    let key = (spring_states.to_vec(), damaged_springs.to_vec());
+    if let Some(count) = cache.get(&key) {
+        return *count;
+    }
+    let count = count_options_uncached(spring_states, damaged_springs, cache);
+    cache.insert(key, count);
+    count
+}
+
+fn count_options_uncached(
+    spring_states: &[SpringState],
+    damaged_springs: &[u32],
+    cache: &mut OptionsCache,
+) -> u64 {
     if spring_states.is_empty() {
         return if damaged_springs.is_empty() { 1 } else { 0 };
     }
@@ -124,7 +171,9 @@ fn count_options(spring_states: Vec<SpringState>, damaged_springs: Vec<u32>) ->
         };
     }
     match spring_states[0] {
-        SpringState::Operational => count_options(spring_states[1..].to_vec(), damaged_springs),
+        SpringState::Operational => {
+            count_options_cached(&spring_states[1..], damaged_springs, cache)
+        }
         SpringState::Damaged => {
             let damaged_count = damaged_springs[0] as usize;
             if spring_states.len() >= damaged_count
@@ -137,24 +186,25 @@ fn count_options(spring_states: Vec<SpringState>, damaged_springs: Vec<u32>) ->
                         SpringState::Operational | SpringState::Unknown
                     ))
             {
-                count_options(
+                count_options_cached(
                     if spring_states.len() == damaged_count {
-                        spring_states[damaged_count..].to_vec()
+                        &spring_states[damaged_count..]
                     } else {
-                        spring_states[damaged_count + 1..].to_vec()
+                        &spring_states[damaged_count + 1..]
                     },
-                    damaged_springs[1..].to_vec(),
+                    &damaged_springs[1..],
+                    cache,
                 )
             } else {
                 0
             }
         }
         SpringState::Unknown => {
-            let mut spring_states = spring_states;
+            let mut spring_states = spring_states.to_vec();
             spring_states[0] = SpringState::Operational;
-            let operational = count_options(spring_states.clone(), damaged_springs.clone());
+            let operational = count_options_cached(&spring_states, damaged_springs, cache);
             spring_states[0] = SpringState::Damaged;
-            let damaged = count_options(spring_states, damaged_springs);
+            let damaged = count_options_cached(&spring_states, damaged_springs, cache);
             operational + damaged
         }
     }
@@ -190,6 +240,7 @@ enum SpringState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     use rstest::rstest;
 
     const INPUT: &str = "???.### 1,1,3
@@ -201,13 +252,13 @@ mod tests {
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "21");
     }
 
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "525152");
     }
 
@@ -237,4 +288,53 @@ mod tests {
         let result = count_options(row.spring_states, row.damaged_springs);
         assert_eq!(result, expected);
     }
+
+    /// A row of up to 14 springs with its damaged groups. The groups are taken from a random
+    /// arrangement, which is then partially hidden, so most rows have at least one solution.
+    fn row() -> impl Strategy<Value = String> {
+        prop::collection::vec((any::<bool>(), any::<bool>()), 1..=14).prop_map(|springs| {
+            let arrangement = springs
+                .iter()
+                .map(|(damaged, _)| if *damaged { '#' } else { '.' })
+                .collect::<String>();
+            let mut groups = arrangement
+                .split('.')
+                .filter(|group| !group.is_empty())
+                .map(|group| group.len().to_string())
+                .collect::<Vec<_>>();
+            if groups.is_empty() {
+                groups.push("1".to_string());
+            }
+            let hidden = springs
+                .iter()
+                .zip(arrangement.chars())
+                .map(|((_, hidden), c)| if *hidden { '?' } else { c })
+                .collect::<String>();
+            format!("{} {}", hidden, groups.join(","))
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn brute_force_equals_count_options(
+            rows in prop::collection::vec(row(), 1..5)
+        ) {
+            let input = rows.join("\n");
+            let expected = parse_all(&input, parse_input)
+                .unwrap()
+                .into_iter()
+                .map(|row| count_options(row.spring_states, row.damaged_springs))
+                .sum::<u64>();
+            prop_assert_eq!(process_part_1(&input).unwrap(), expected.to_string());
+        }
+
+        #[test]
+        fn expanded_row_has_at_least_as_many_options(row in row()) {
+            let (_, row) = parse_row(&row).unwrap();
+            let options = count_options(row.spring_states.clone(), row.damaged_springs.clone());
+            let row = expand_row(row);
+            let expanded_options = count_options(row.spring_states, row.damaged_springs);
+            prop_assert!(expanded_options >= options.pow(5));
+        }
+    }
 }
@@ -1,22 +1,47 @@
+use grid::Grid;
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     character::complete::line_ending,
+    combinator::map_opt,
     multi::{many1, separated_list1},
     sequence::tuple,
-    IResult, Parser,
+    Parser,
 };
+use solver::parse::{parse_all, tag, IResult};
+use solver::{ParseError, Part, Run, Solver};
 
-pub fn process_part_1(input: &str) -> String {
-    let (input, patterns) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
+pub struct Day13;
+
+impl Solver for Day13 {
+    fn day(&self) -> u8 {
+        13
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || parse_all(input, parse_input);
+        match part {
+            Part::One => Run::timed(parse, part_1),
+            Part::Two => Run::timed(parse, part_2),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day13.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day13.solve(Part::Two, input)
+}
+
+fn part_1(patterns: Vec<Pattern>) -> String {
     patterns
         .iter()
         .map(|pattern| {
-            let vertical = (0..pattern.pattern[0].len() as i32)
+            let vertical = (0..pattern.pattern.width() as i32)
                 .filter(|&i| is_vertical_mirror(pattern, i))
                 .sum::<i32>();
-            let horizontal = (0..pattern.pattern.len() as i32)
+            let horizontal = (0..pattern.pattern.height() as i32)
                 .filter(|&i| is_horizontal_mirror(pattern, i))
                 .map(|i| i * 100)
                 .sum::<i32>();
@@ -26,16 +51,14 @@ pub fn process_part_1(input: &str) -> String {
         .to_string()
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let (input, patterns) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
+fn part_2(patterns: Vec<Pattern>) -> String {
     patterns
         .iter()
         .map(|pattern| {
-            let vertical = (0..pattern.pattern[0].len() as i32)
+            let vertical = (0..pattern.pattern.width() as i32)
                 .filter(|&i| is_smudged_vertical_mirror(pattern, i))
                 .sum::<i32>();
-            let horizontal = (0..pattern.pattern.len() as i32)
+            let horizontal = (0..pattern.pattern.height() as i32)
                 .filter(|&i| is_smudged_horizontal_mirror(pattern, i))
                 .map(|i| i * 100)
                 .sum::<i32>();
@@ -50,27 +73,30 @@ fn parse_input(input: &str) -> IResult<&str, Vec<Pattern>> {
 }
 
 fn parse_pattern(input: &str) -> IResult<&str, Pattern> {
-    separated_list1(
-        line_ending,
-        many1(alt((
-            tag("#").map(|_| Tile::Rock),
-            tag(".").map(|_| Tile::Ash),
-        ))),
+    map_opt(
+        separated_list1(
+            line_ending,
+            many1(alt((
+                tag("#").map(|_| Tile::Rock),
+                tag(".").map(|_| Tile::Ash),
+            ))),
+        ),
+        Grid::from_rows,
     )
     .map(|pattern| Pattern { pattern })
     .parse(input)
 }
 
 fn is_horizontal_mirror(pattern: &Pattern, mirror_location: i32) -> bool {
-    if mirror_location <= 0 || mirror_location >= pattern.pattern.len() as i32 {
+    if mirror_location <= 0 || mirror_location >= pattern.pattern.height() as i32 {
         return false;
     }
     let mut is_mirror = true;
     let mut top_side = mirror_location - 1;
     let mut bottom_side = mirror_location;
-    while top_side >= 0 && bottom_side < pattern.pattern.len() as i32 {
-        let top = &pattern.pattern[top_side as usize];
-        let bottom = &pattern.pattern[bottom_side as usize];
+    while top_side >= 0 && bottom_side < pattern.pattern.height() as i32 {
+        let top = pattern.pattern.row(top_side as usize);
+        let bottom = pattern.pattern.row(bottom_side as usize);
         if top != bottom {
             is_mirror = false;
             break;
@@ -78,26 +104,24 @@ fn is_horizontal_mirror(pattern: &Pattern, mirror_location: i32) -> bool {
         top_side -= 1;
         bottom_side += 1;
     }
-    return is_mirror;
+    is_mirror
 }
 
 fn is_vertical_mirror(pattern: &Pattern, mirror_location: i32) -> bool {
-    if mirror_location <= 0 || mirror_location >= pattern.pattern[0].len() as i32 {
+    if mirror_location <= 0 || mirror_location >= pattern.pattern.width() as i32 {
         return false;
     }
     let mut is_mirror = true;
     let mut left_side = mirror_location - 1;
     let mut right_side = mirror_location;
-    while left_side >= 0 && right_side < pattern.pattern[0].len() as i32 {
+    while left_side >= 0 && right_side < pattern.pattern.width() as i32 {
         let left = pattern
             .pattern
-            .iter()
-            .map(|row| &row[left_side as usize])
+            .column(left_side as usize)
             .collect::<Vec<_>>();
         let right = pattern
             .pattern
-            .iter()
-            .map(|row| &row[right_side as usize])
+            .column(right_side as usize)
             .collect::<Vec<_>>();
         if left != right {
             is_mirror = false;
@@ -106,19 +130,19 @@ fn is_vertical_mirror(pattern: &Pattern, mirror_location: i32) -> bool {
         left_side -= 1;
         right_side += 1;
     }
-    return is_mirror;
+    is_mirror
 }
 
 fn is_smudged_horizontal_mirror(pattern: &Pattern, mirror_location: i32) -> bool {
-    if mirror_location <= 0 || mirror_location >= pattern.pattern.len() as i32 {
+    if mirror_location <= 0 || mirror_location >= pattern.pattern.height() as i32 {
         return false;
     }
     let mut top_side = mirror_location - 1;
     let mut bottom_side = mirror_location;
     let mut diffs = 0;
-    while top_side >= 0 && bottom_side < pattern.pattern.len() as i32 {
-        let top = &pattern.pattern[top_side as usize];
-        let bottom = &pattern.pattern[bottom_side as usize];
+    while top_side >= 0 && bottom_side < pattern.pattern.height() as i32 {
+        let top = pattern.pattern.row(top_side as usize);
+        let bottom = pattern.pattern.row(bottom_side as usize);
         diffs += top
             .iter()
             .zip(bottom.iter())
@@ -127,26 +151,24 @@ fn is_smudged_horizontal_mirror(pattern: &Pattern, mirror_location: i32) -> bool
         top_side -= 1;
         bottom_side += 1;
     }
-    return diffs == 1;
+    diffs == 1
 }
 
 fn is_smudged_vertical_mirror(pattern: &Pattern, mirror_location: i32) -> bool {
-    if mirror_location <= 0 || mirror_location >= pattern.pattern[0].len() as i32 {
+    if mirror_location <= 0 || mirror_location >= pattern.pattern.width() as i32 {
         return false;
     }
     let mut left_side = mirror_location - 1;
     let mut right_side = mirror_location;
     let mut diffs = 0;
-    while left_side >= 0 && right_side < pattern.pattern[0].len() as i32 {
+    while left_side >= 0 && right_side < pattern.pattern.width() as i32 {
         let left = pattern
             .pattern
-            .iter()
-            .map(|row| &row[left_side as usize])
+            .column(left_side as usize)
             .collect::<Vec<_>>();
         let right = pattern
             .pattern
-            .iter()
-            .map(|row| &row[right_side as usize])
+            .column(right_side as usize)
             .collect::<Vec<_>>();
         diffs += left
             .iter()
@@ -157,12 +179,12 @@ fn is_smudged_vertical_mirror(pattern: &Pattern, mirror_location: i32) -> bool {
         left_side -= 1;
         right_side += 1;
     }
-    return diffs == 1;
+    diffs == 1
 }
 
 #[derive(Debug)]
 struct Pattern {
-    pattern: Vec<Vec<Tile>>,
+    pattern: Grid<Tile>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -193,13 +215,13 @@ mod tests {
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "405");
     }
 
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "400");
     }
 }
@@ -3,138 +3,152 @@ use std::{
     fmt::{self, Display, Formatter},
 };
 
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::line_ending,
-    multi::{many1, separated_list1},
-    IResult, Parser,
-};
+use grid::{Direction, Grid, Position};
+use solver::{diagnostics, params, recorder, ParseError, Part, Run, Solver};
+
+const TILES: [(char, Tile); 3] = [
+    ('O', Tile::RoundRock),
+    ('#', Tile::CubeRock),
+    ('.', Tile::Empty),
+];
+
+pub struct Day14;
+
+/// Spin cycles of part 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Day14Params {
+    pub cycles: usize,
+}
+
+impl Default for Day14Params {
+    fn default() -> Self {
+        Self {
+            cycles: 1_000_000_000,
+        }
+    }
+}
+
+impl params::Params for Day14Params {
+    const NAMES: &'static [&'static str] = &["cycles"];
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        self.cycles = params::parse(name, value)?;
+        Ok(())
+    }
+}
+
+impl Solver for Day14 {
+    fn day(&self) -> u8 {
+        14
+    }
+
+    fn check_params(&self, overrides: &[(String, String)]) -> Result<(), String> {
+        params::with_overrides::<Day14Params>(overrides).map(|_| ())
+    }
 
-pub fn process_part_1(input: &str) -> String {
-    let (input, platform) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
-    let mut platform = platform;
-    for _ in 0..platform.len() {
-        for y in (1..platform.len()).rev() {
-            for x in 0..platform[y].len() {
-                if matches!(platform[y][x], Tile::RoundRock)
-                    && matches!(platform[y - 1][x], Tile::Empty)
-                {
-                    platform[y - 1][x] = Tile::RoundRock;
-                    platform[y][x] = Tile::Empty;
-                }
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || Grid::parse(input, &TILES);
+        match part {
+            Part::One => Run::timed(parse, part_1),
+            Part::Two => {
+                let params = params::current();
+                Run::timed(parse, |platform| part_2(platform, params))
             }
         }
     }
+}
 
-    print_platform(&platform);
-    calc_total_load(&platform).to_string()
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day14.solve(Part::One, input)
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let (input, platform) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day14.solve(Part::Two, input)
+}
+
+fn part_1(mut platform: Grid<Tile>) -> String {
+    recorder::record("start", &platform);
+    let mut sweeps = 0;
+    while roll_north(&mut platform) {
+        sweeps += 1;
+        recorder::record(format!("tilt north, sweep {}", sweeps), &platform);
+    }
 
-    let mut platform = platform;
+    diagnostics::print(&platform);
+    calc_total_load(&platform).to_string()
+}
+
+fn part_2(mut platform: Grid<Tile>, params: Day14Params) -> String {
     let mut cycles = HashMap::new();
     cycles.insert(platform.clone(), 0_usize);
+    recorder::record("cycle 0", &platform);
 
-    for i in 1..=1_000_000_000 {
-        spin_cycle(&mut platform);
+    for i in 1..=params.cycles {
+        platform = spin_cycle(&platform);
+        recorder::record(format!("cycle {}", i), &platform);
         let key = platform.clone();
         if cycles.contains_key(&key) {
             break;
         }
         cycles.insert(platform.clone(), i);
     }
+    if cycles.len() == params.cycles + 1 {
+        // done before the platform repeated
+        diagnostics::print(&platform);
+        return calc_total_load(&platform).to_string();
+    }
 
     let loop_start = cycles[&platform];
-    let mut cycles = cycles.iter().map(|(k, v)| (k, v)).collect::<Vec<_>>();
+    let mut cycles = cycles.iter().collect::<Vec<_>>();
     cycles.sort_by(|a, b| a.1.cmp(b.1));
     let cycles = cycles.iter().map(|(k, _)| k).collect::<Vec<_>>();
     let loop_len = cycles.len() - loop_start;
-    let platform = cycles[loop_start + (1_000_000_000 - loop_start) % loop_len];
-
-    print_platform(platform);
+    let last_cycle = loop_start + (params.cycles - loop_start) % loop_len;
+    let platform = cycles[last_cycle];
+
+    recorder::record(
+        format!("cycle {}, same as cycle {}", params.cycles, last_cycle),
+        platform,
+    );
+    diagnostics::print(platform);
     calc_total_load(platform).to_string()
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Vec<Tile>>> {
-    separated_list1(
-        line_ending,
-        many1(alt((
-            tag("O").map(|_| Tile::RoundRock),
-            tag("#").map(|_| Tile::CubeRock),
-            tag(".").map(|_| Tile::Empty),
-        ))),
-    )(input)
+fn tilt_north(platform: &mut Grid<Tile>) {
+    while roll_north(platform) {}
 }
 
-#[allow(clippy::needless_range_loop)]
-fn spin_cycle(platform: &mut [Vec<Tile>]) {
-    for _ in 0..platform.len() {
-        for y in (1..platform.len()).rev() {
-            for x in 0..platform[y].len() {
-                if matches!(platform[y][x], Tile::RoundRock)
-                    && matches!(platform[y - 1][x], Tile::Empty)
-                {
-                    platform[y - 1][x] = Tile::RoundRock;
-                    platform[y][x] = Tile::Empty;
-                }
-            }
-        }
-    }
-    for _ in 0..platform[0].len() {
-        for x in (1..platform[0].len()).rev() {
-            for y in 0..platform.len() {
-                if matches!(platform[y][x], Tile::RoundRock)
-                    && matches!(platform[y][x - 1], Tile::Empty)
-                {
-                    platform[y][x - 1] = Tile::RoundRock;
-                    platform[y][x] = Tile::Empty;
-                }
-            }
-        }
-    }
-    for _ in 0..platform.len() {
-        for y in 0..platform.len() - 1 {
-            for x in 0..platform[y].len() {
-                if matches!(platform[y][x], Tile::RoundRock)
-                    && matches!(platform[y + 1][x], Tile::Empty)
-                {
-                    platform[y + 1][x] = Tile::RoundRock;
-                    platform[y][x] = Tile::Empty;
-                }
-            }
-        }
-    }
-    for _ in 0..platform[0].len() {
-        for x in 0..platform[0].len() - 1 {
-            for y in 0..platform.len() {
-                if matches!(platform[y][x], Tile::RoundRock)
-                    && matches!(platform[y][x + 1], Tile::Empty)
-                {
-                    platform[y][x + 1] = Tile::RoundRock;
-                    platform[y][x] = Tile::Empty;
-                }
+/// Sweeps the platform from the south edge to the north edge, rolling every round rock that has
+/// room north of it by a tile. Returns whether any rock moved.
+fn roll_north(platform: &mut Grid<Tile>) -> bool {
+    let mut moved = false;
+    for y in (1..platform.height()).rev() {
+        for x in 0..platform.width() {
+            let position = Position::new(x as i32, y as i32);
+            let above = position.move_in_direction(Direction::North);
+            if matches!(platform[position], Tile::RoundRock)
+                && matches!(platform[above], Tile::Empty)
+            {
+                platform.swap(position, above);
+                moved = true;
             }
         }
     }
+    moved
 }
 
-fn print_platform(platform: &[Vec<Tile>]) {
-    for row in platform {
-        for tile in row {
-            print!("{}", tile)
-        }
-        println!()
-    }
+/// Tilts the platform north, west, south and east. Rotating it clockwise after each tilt
+/// brings the next edge to the north, and after four rotations it is back in place.
+fn spin_cycle(platform: &Grid<Tile>) -> Grid<Tile> {
+    (0..4).fold(platform.clone(), |mut platform, _| {
+        tilt_north(&mut platform);
+        platform.rotate_clockwise()
+    })
 }
 
-fn calc_total_load(platform: &[Vec<Tile>]) -> usize {
-    let len = platform.len();
-    platform.iter().enumerate().fold(0, |acc, (y, row)| {
+fn calc_total_load(platform: &Grid<Tile>) -> usize {
+    let len = platform.height();
+    platform.rows().enumerate().fold(0, |acc, (y, row)| {
         acc + row
             .iter()
             .filter(|tile| matches!(tile, Tile::RoundRock))
@@ -177,13 +191,27 @@ O.#..O.#.#
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "136");
     }
 
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "64");
     }
+
+    #[test]
+    fn part_2_with_fewer_cycles() {
+        let platform = Grid::parse(INPUT, &TILES).unwrap();
+        for cycles in [0, 1, 3, 20, 21] {
+            let spun = (0..cycles).fold(platform.clone(), |platform, _| spin_cycle(&platform));
+            assert_eq!(
+                part_2(platform.clone(), Day14Params { cycles }),
+                calc_total_load(&spun).to_string(),
+                "{} cycles",
+                cycles
+            );
+        }
+    }
 }
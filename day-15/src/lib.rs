@@ -1,67 +1,83 @@
+use std::io::BufRead;
+
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     character::complete::{self, alpha1},
     multi::separated_list1,
     sequence::{separated_pair, terminated},
-    IResult, Parser,
+    Parser,
 };
+use solver::parse::{parse_all, tag, IResult};
+use solver::stream::for_each_record;
+use solver::{ParseError, Part, Run, Solver, StreamError, StreamSolver};
+
+pub struct Day15;
+
+impl Solver for Day15 {
+    fn day(&self) -> u8 {
+        15
+    }
 
-pub fn process_part_1(input: &str) -> String {
-    input.split(',').map(my_hash).sum::<u64>().to_string()
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        match part {
+            // part 1 hashes the steps as they are, without looking into them
+            Part::One => Run::timed(|| Ok(input.split(',').collect()), part_1),
+            Part::Two => Run::timed(|| parse_all(input, parse_input), part_2),
+        }
+    }
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let (input, steps) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
-    let mut my_hashmap = (0..256).map(|_| vec![]).collect::<Vec<Vec<Lens>>>();
-    steps.into_iter().for_each(|step| match step {
-        Step::Remove(label) => {
-            let hash = my_hash(label);
-            let my_box = &mut my_hashmap[hash as usize];
-            if let Some(index) = my_box.iter().position(|lens| lens.label == label) {
-                my_box.remove(index);
+impl StreamSolver for Day15 {
+    fn solve_stream(&self, part: Part, reader: &mut dyn BufRead) -> Result<String, StreamError> {
+        match part {
+            Part::One => {
+                let mut sum = 0;
+                for_each_record(reader, b',', |step| {
+                    sum += my_hash(step);
+                    Ok(())
+                })?;
+                Ok(sum.to_string())
             }
-        }
-        Step::Add(label, focal_length) => {
-            let hash = my_hash(label);
-            let my_box = &mut my_hashmap[hash as usize];
-            let lens = Lens {
-                label,
-                focal_length,
-            };
-            if let Some(index) = my_box.iter().position(|lens| lens.label == label) {
-                my_box[index] = lens;
-            } else {
-                my_box.push(lens);
+            Part::Two => {
+                let mut boxes = Boxes::new();
+                for_each_record(reader, b',', |step| {
+                    boxes.apply(parse_all(step, parse_step)?);
+                    Ok(())
+                })?;
+                Ok(boxes.focusing_power().to_string())
             }
         }
-    });
-    my_hashmap
-        .iter()
-        .enumerate()
-        .map(|(box_index, my_box)| {
-            my_box
-                .iter()
-                .enumerate()
-                .map(|(lens_index, lens)| {
-                    (1 + box_index as u64) * (lens_index as u64 + 1) * lens.focal_length
-                })
-                .sum::<u64>()
-        })
-        .sum::<u64>()
-        .to_string()
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day15.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day15.solve(Part::Two, input)
+}
+
+fn part_1(steps: Vec<&str>) -> String {
+    steps.into_iter().map(my_hash).sum::<u64>().to_string()
+}
+
+fn part_2(steps: Vec<Step>) -> String {
+    let mut boxes = Boxes::new();
+    steps.into_iter().for_each(|step| boxes.apply(step));
+    boxes.focusing_power().to_string()
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Step>> {
-    separated_list1(
-        tag(","),
-        alt((
-            separated_pair(alpha1, tag("="), complete::u64)
-                .map(|(label, focal_length)| Step::Add(label, focal_length)),
-            terminated(alpha1, tag("-")).map(Step::Remove),
-        )),
-    )(input)
+fn parse_input(input: &str) -> IResult<&str, Vec<Step<'_>>> {
+    separated_list1(tag(","), parse_step)(input)
+}
+
+fn parse_step(input: &str) -> IResult<&str, Step<'_>> {
+    alt((
+        separated_pair(alpha1, tag("="), complete::u64)
+            .map(|(label, focal_length)| Step::Add(label, focal_length)),
+        terminated(alpha1, tag("-")).map(Step::Remove),
+    ))(input)
 }
 
 fn my_hash(input: &str) -> u64 {
@@ -73,11 +89,60 @@ enum Step<'a> {
     Add(&'a str, u64),
 }
 
-struct Lens<'a> {
-    label: &'a str,
+struct Lens {
+    label: String,
     focal_length: u64,
 }
 
+/// The 256 boxes of lenses, the labels are owned so that steps can be applied while streaming.
+struct Boxes(Vec<Vec<Lens>>);
+
+impl Boxes {
+    fn new() -> Self {
+        Self((0..256).map(|_| vec![]).collect())
+    }
+
+    fn apply(&mut self, step: Step) {
+        match step {
+            Step::Remove(label) => {
+                let hash = my_hash(label);
+                let my_box = &mut self.0[hash as usize];
+                if let Some(index) = my_box.iter().position(|lens| lens.label == label) {
+                    my_box.remove(index);
+                }
+            }
+            Step::Add(label, focal_length) => {
+                let hash = my_hash(label);
+                let my_box = &mut self.0[hash as usize];
+                if let Some(index) = my_box.iter().position(|lens| lens.label == label) {
+                    my_box[index].focal_length = focal_length;
+                } else {
+                    my_box.push(Lens {
+                        label: label.to_string(),
+                        focal_length,
+                    });
+                }
+            }
+        }
+    }
+
+    fn focusing_power(&self) -> u64 {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(box_index, my_box)| {
+                my_box
+                    .iter()
+                    .enumerate()
+                    .map(|(lens_index, lens)| {
+                        (1 + box_index as u64) * (lens_index as u64 + 1) * lens.focal_length
+                    })
+                    .sum::<u64>()
+            })
+            .sum::<u64>()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,16 +152,30 @@ mod tests {
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "1320");
     }
 
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "145");
     }
 
+    #[test]
+    fn solve_stream_works() {
+        let input = format!("{}\n", INPUT);
+        let result = Day15.solve_stream(Part::One, &mut input.as_bytes());
+        assert_eq!(result.unwrap(), "1320");
+        let result = Day15.solve_stream(Part::Two, &mut input.as_bytes());
+        assert_eq!(result.unwrap(), "145");
+        let result = Day15.solve_stream(Part::Two, &mut "rn=1,cm+".as_bytes());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "expected '=' or '-' on line 1, column 8"
+        );
+    }
+
     #[rstest]
     #[case("rn=1", 30)]
     #[case("cm-", 253)]
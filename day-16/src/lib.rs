@@ -4,61 +4,97 @@ use std::{
     sync::{Arc, RwLock},
 };
 
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::line_ending,
-    multi::{many1, separated_list1},
-    IResult, Parser,
-};
+use grid::{Direction, Grid, Position};
 use rayon::prelude::*;
 use rayon::Scope;
+use render::{Color, Picture};
+use solver::{diagnostics, recorder, ParseError, Part, Run, Solver};
+
+type BeamState = Grid<HashSet<Direction>>;
+
+const ENERGIZED: Color = Color(255, 210, 60);
+const MIRROR: Color = Color(90, 90, 110);
+const ENERGIZED_MIRROR: Color = Color(230, 100, 20);
+
+const TILES: [(char, Tile); 5] = [
+    ('.', Tile::Space),
+    ('-', Tile::HorizontalSplitter),
+    ('|', Tile::VerticalSplitter),
+    ('\\', Tile::LeftMirror),
+    ('/', Tile::RightMirror),
+];
+
+pub struct Day16;
+
+impl Solver for Day16 {
+    fn day(&self) -> u8 {
+        16
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || Grid::parse(input, &TILES);
+        match part {
+            Part::One => Run::timed(parse, part_1),
+            Part::Two => Run::timed(parse, part_2),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day16.solve(Part::One, input)
+}
 
-type BeamState = Vec<Vec<HashSet<Direction>>>;
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day16.solve(Part::Two, input)
+}
 
-pub fn process_part_1(input: &str) -> String {
-    let (input, map) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
+fn part_1(map: Grid<Tile>) -> String {
     let map = Arc::new(map);
-    let width = map[0].len();
-    let height = map.len();
     let stating_position = Position { x: 0, y: 0 };
     let stating_direction = Direction::East;
 
-    let state = start_beam(
-        map.clone(),
-        width,
-        height,
-        stating_position,
-        stating_direction,
-    );
+    let record = recorder::recording();
+    let state = start_beam(map.clone(), stating_position, stating_direction, record);
+
+    if diagnostics::enabled() {
+        diagnostics::print(draw_beams(&map, &state));
+    }
+
+    count_energized_tiles(&state).to_string()
+}
 
-    map.iter().enumerate().for_each(|(y, line)| {
-        line.iter().enumerate().for_each(|(x, tile)| {
-            let state = &state[y][x];
+/// Draws the beams onto the map, an empty tile shows the direction of the beam passing it or
+/// the number of beams if there are more.
+fn draw_beams(map: &Grid<Tile>, state: &BeamState) -> Grid<String> {
+    let tiles = map
+        .iter()
+        .map(|(position, tile)| {
+            let state = &state[position];
             if matches!(tile, Tile::Space) && !state.is_empty() {
                 let len = state.len();
                 if len == 1 {
-                    print!("{}", state.iter().next().unwrap());
+                    state.iter().next().unwrap().to_string()
                 } else {
-                    print!("{}", len);
+                    len.to_string()
                 }
             } else {
-                print!("{}", tile);
+                tile.to_string()
             }
-        });
-        println!();
-    });
+        })
+        .collect();
+    Grid::new(map.width(), map.height(), tiles)
+}
 
-    count_energized_tiles(&state).to_string()
+fn part_2(map: Grid<Tile>) -> String {
+    let (energized, _, _) = best_entry(Arc::new(map));
+    energized.to_string()
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let (input, map) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
-    let map = Arc::new(map);
-    let width = map[0].len();
-    let height = map.len();
+/// The number of tiles energized by the beam entering from the edge that energizes the most,
+/// and where and in which direction that beam enters.
+fn best_entry(map: Arc<Grid<Tile>>) -> (usize, Position, Direction) {
+    let width = map.width();
+    let height = map.height();
 
     [
         (
@@ -87,46 +123,74 @@ pub fn process_part_2(input: &str) -> String {
                     x: *x as i32,
                     y: *y as i32,
                 };
-                let state = start_beam(map.clone(), width, height, starting_position, *direction);
-                count_energized_tiles(&state)
+                let state = start_beam(map.clone(), starting_position, *direction, false);
+                (count_energized_tiles(&state), starting_position, *direction)
             })
             .collect::<Vec<_>>()
     })
-    .max()
+    .max_by_key(|(energized, _, _)| *energized)
     .unwrap()
-    .to_string()
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Vec<Tile>>> {
-    separated_list1(
-        line_ending,
-        many1(alt((
-            tag(".").map(|_| Tile::Space),
-            tag("-").map(|_| Tile::HorizontalSplitter),
-            tag("|").map(|_| Tile::VerticalSplitter),
-            tag("\\").map(|_| Tile::LeftMirror),
-            tag("/").map(|_| Tile::RightMirror),
-        ))),
-    )(input)
+/// Draws the energized tiles, for part 1 of the beam entering at the top left and for part 2
+/// of the beam energizing the most.
+pub fn render(part: Part, input: &str) -> Result<Picture, ParseError> {
+    let map = Arc::new(Grid::parse(input, &TILES)?);
+    let (position, direction) = match part {
+        Part::One => (Position::new(0, 0), Direction::East),
+        Part::Two => {
+            let (_, position, direction) = best_entry(map.clone());
+            (position, direction)
+        }
+    };
+    let state = start_beam(map.clone(), position, direction, false);
+    let mut picture = Picture::new(
+        map.width() as f64,
+        map.height() as f64,
+        format!(
+            "{} tiles energized by the beam entering at {} going {}",
+            count_energized_tiles(&state),
+            position,
+            format!("{:?}", direction).to_lowercase()
+        ),
+    );
+    picture.cells(&map, |position, tile| {
+        let energized = !state[position].is_empty();
+        match (tile, energized) {
+            (Tile::Space, true) => Some(ENERGIZED),
+            (Tile::Space, false) => None,
+            (_, true) => Some(ENERGIZED_MIRROR),
+            (_, false) => Some(MIRROR),
+        }
+    });
+    picture.legend(ENERGIZED, "energized");
+    picture.legend(MIRROR, "mirror or splitter");
+    picture.legend(ENERGIZED_MIRROR, "energized mirror or splitter");
+    Ok(picture)
 }
 
+/// Follows the beam entering at `stating_position`, recording a frame whenever a tile gets
+/// energized if `record` is set. Split beams are followed in parallel, so with more than one beam
+/// the order of the frames depends on the threads.
 fn start_beam(
-    map: Arc<Vec<Vec<Tile>>>,
-    width: usize,
-    height: usize,
+    map: Arc<Grid<Tile>>,
     stating_position: Position,
     stating_direction: Direction,
+    record: bool,
 ) -> BeamState {
-    let state = Arc::new(RwLock::new(vec![vec![HashSet::new(); width]; height]));
+    let state = Arc::new(RwLock::new(Grid::filled(
+        map.width(),
+        map.height(),
+        HashSet::new(),
+    )));
     rayon::scope(|scope| {
         beam(
             scope,
             map,
-            width as i32,
-            height as i32,
             state.clone(),
             stating_position,
             stating_direction,
+            record,
         );
     });
     Arc::try_unwrap(state).unwrap().into_inner().unwrap()
@@ -135,37 +199,31 @@ fn start_beam(
 fn next_positions(
     position: &Position,
     direction: &Direction,
-    map: &[Vec<Tile>],
+    map: &Grid<Tile>,
 ) -> ((Position, Direction), Option<(Position, Direction)>) {
-    let tile = &map[position.y as usize][position.x as usize];
+    let tile = &map[*position];
     match tile {
-        Tile::Space => ((position.move_in_direction(direction), *direction), None),
+        Tile::Space => ((position.move_in_direction(*direction), *direction), None),
         Tile::HorizontalSplitter => match direction {
             Direction::West | Direction::East => {
-                ((position.move_in_direction(direction), *direction), None)
+                ((position.move_in_direction(*direction), *direction), None)
             }
             Direction::North | Direction::South => (
-                (
-                    position.move_in_direction(&Direction::East),
-                    Direction::East,
-                ),
-                Some((
-                    position.move_in_direction(&Direction::West),
-                    Direction::West,
-                )),
+                (position.move_in_direction(Direction::East), Direction::East),
+                Some((position.move_in_direction(Direction::West), Direction::West)),
             ),
         },
         Tile::VerticalSplitter => match direction {
             Direction::North | Direction::South => {
-                ((position.move_in_direction(direction), *direction), None)
+                ((position.move_in_direction(*direction), *direction), None)
             }
             Direction::West | Direction::East => (
                 (
-                    position.move_in_direction(&Direction::South),
+                    position.move_in_direction(Direction::South),
                     Direction::South,
                 ),
                 Some((
-                    position.move_in_direction(&Direction::North),
+                    position.move_in_direction(Direction::North),
                     Direction::North,
                 )),
             ),
@@ -176,7 +234,7 @@ fn next_positions(
                 Direction::West | Direction::East => direction.turn_right(),
             };
             (
-                (position.move_in_direction(&next_direction), next_direction),
+                (position.move_in_direction(next_direction), next_direction),
                 None,
             )
         }
@@ -186,7 +244,7 @@ fn next_positions(
                 Direction::West | Direction::East => direction.turn_left(),
             };
             (
-                (position.move_in_direction(&next_direction), next_direction),
+                (position.move_in_direction(next_direction), next_direction),
                 None,
             )
         }
@@ -195,23 +253,34 @@ fn next_positions(
 
 fn beam(
     scope: &Scope,
-    map: Arc<Vec<Vec<Tile>>>,
-    width: i32,
-    height: i32,
+    map: Arc<Grid<Tile>>,
     state: Arc<RwLock<BeamState>>,
     position: Position,
     direction: Direction,
+    record: bool,
 ) {
     let mut position = position;
     let mut direction = direction;
     loop {
-        if position.x >= width || position.x < 0 || position.y >= height || position.y < 0 {
+        if !map.contains(position) {
             return;
         }
-        if state.read().unwrap()[position.y as usize][position.x as usize].contains(&direction) {
+        if state.read().unwrap()[position].contains(&direction) {
             return;
         }
-        state.write().unwrap()[position.y as usize][position.x as usize].insert(direction);
+        let energized = {
+            let directions = &mut state.write().unwrap()[position];
+            let energized = directions.is_empty();
+            directions.insert(direction);
+            energized
+        };
+        if record && energized {
+            let state = state.read().unwrap();
+            recorder::record(
+                format!("{} tiles energized", count_energized_tiles(&state)),
+                draw_beams(&map, &state),
+            );
+        }
         let ((next_position, next_direction), split_next_position) =
             next_positions(&position, &direction, &map);
         position = next_position;
@@ -223,11 +292,10 @@ fn beam(
                 beam(
                     scope,
                     map,
-                    width,
-                    height,
                     state,
                     split_next_position,
                     split_next_direction,
+                    record,
                 );
             });
         }
@@ -236,11 +304,12 @@ fn beam(
 
 fn count_energized_tiles(state: &BeamState) -> usize {
     state
-        .iter()
-        .map(|line| line.iter().filter(|position| !position.is_empty()).count())
-        .sum()
+        .values()
+        .filter(|directions| !directions.is_empty())
+        .count()
 }
 
+#[derive(Clone, Copy)]
 enum Tile {
     Space,
     HorizontalSplitter,
@@ -261,73 +330,6 @@ impl Display for Tile {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
-impl Direction {
-    fn turn_left(&self) -> Direction {
-        match self {
-            Direction::North => Direction::West,
-            Direction::East => Direction::North,
-            Direction::South => Direction::East,
-            Direction::West => Direction::South,
-        }
-    }
-
-    fn turn_right(&self) -> Direction {
-        match self {
-            Direction::North => Direction::East,
-            Direction::East => Direction::South,
-            Direction::South => Direction::West,
-            Direction::West => Direction::North,
-        }
-    }
-}
-
-impl Display for Direction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Direction::North => write!(f, "^"),
-            Direction::East => write!(f, ">"),
-            Direction::South => write!(f, "v"),
-            Direction::West => write!(f, "<"),
-        }
-    }
-}
-
-struct Position {
-    x: i32,
-    y: i32,
-}
-
-impl Position {
-    fn move_in_direction(&self, direction: &Direction) -> Position {
-        match direction {
-            Direction::North => Position {
-                x: self.x,
-                y: self.y - 1,
-            },
-            Direction::East => Position {
-                x: self.x + 1,
-                y: self.y,
-            },
-            Direction::South => Position {
-                x: self.x,
-                y: self.y + 1,
-            },
-            Direction::West => Position {
-                x: self.x - 1,
-                y: self.y,
-            },
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -345,13 +347,27 @@ mod tests {
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "46");
     }
 
+    #[test]
+    fn render_works() {
+        let svg = render(Part::One, INPUT).unwrap().to_svg();
+        assert!(svg.contains(">46 tiles energized by the beam entering at (0,0) going east</text>"));
+        assert!(svg.contains(r##"<rect x="0" y="0" width="1" height="1" fill="#ffd23c"/>"##));
+        assert!(svg.contains(r##"<rect x="1" y="0" width="1" height="1" fill="#e66414"/>"##));
+        assert!(svg.contains(r##"<rect x="8" y="3" width="1" height="1" fill="#5a5a6e"/>"##));
+
+        let svg = render(Part::Two, INPUT).unwrap().to_svg();
+        assert!(
+            svg.contains(">51 tiles energized by the beam entering at (3,0) going south</text>")
+        );
+    }
+
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "51");
     }
 }
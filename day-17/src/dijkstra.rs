@@ -9,7 +9,6 @@ use rustc_hash::{FxHashMap, FxHashSet};
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 use std::hash::Hash;
-use std::usize;
 
 use indexmap::IndexMap;
 use rustc_hash::FxHasher;
@@ -26,9 +25,9 @@ pub type FxIndexMap<K, V> = IndexMap<K, V, BuildHasherDefault<FxHasher>>;
 ///
 /// - `start` is the starting node.
 /// - `successors` returns a list of successors for a given node, along with the cost for moving
-/// from the node to the successor. This cost must be non-negative.
+///   from the node to the successor. This cost must be non-negative.
 /// - `success` checks whether the goal has been reached. It is not a node as some problems require
-/// a dynamic solution instead of a fixed node.
+///   a dynamic solution instead of a fixed node.
 ///
 /// A node will never be included twice in the path as determined by the `Eq` relationship.
 ///
@@ -41,7 +40,7 @@ pub type FxIndexMap<K, V> = IndexMap<K, V, BuildHasherDefault<FxHasher>>;
 ///
 /// The first version uses an explicit type `Pos` on which the required traits are derived.
 ///
-/// ```
+/// ```ignore
 /// use pathfinding::prelude::dijkstra;
 ///
 /// #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -64,7 +63,7 @@ pub type FxIndexMap<K, V> = IndexMap<K, V, BuildHasherDefault<FxHasher>>;
 /// The second version does not declare a `Pos` type, makes use of more closures,
 /// and is thus shorter.
 ///
-/// ```
+/// ```ignore
 /// use pathfinding::prelude::dijkstra;
 ///
 /// static GOAL: (i32, i32) = (4, 6);
@@ -129,7 +128,7 @@ where
         index: 0,
     });
     let mut parents: FxIndexMap<N, (usize, C)> = FxIndexMap::default();
-    parents.insert(start.clone(), (usize::max_value(), Zero::zero()));
+    parents.insert(start.clone(), (usize::MAX, Zero::zero()));
     let mut target_reached = None;
     while let Some(SmallestHolder { cost, index }) = to_see.pop() {
         let successors = {
@@ -193,6 +192,7 @@ impl<K: Ord> Ord for SmallestHolder<K> {
 }
 
 /// Struct returned by [`dijkstra_reach`].
+#[allow(dead_code)]
 pub struct DijkstraReachable<N, C, FN> {
     to_see: BinaryHeap<SmallestHolder<C>>,
     seen: FxHashSet<usize>,
@@ -202,6 +202,7 @@ pub struct DijkstraReachable<N, C, FN> {
 }
 
 /// Information about a node reached by [`dijkstra_reach`].
+#[allow(dead_code)]
 #[derive(Debug, Hash, PartialEq, Eq, Clone)]
 pub struct DijkstraReachableItem<N, C> {
     /// The node that was reached by [`dijkstra_reach`].
@@ -1,61 +1,98 @@
-use std::fmt::Display;
+use std::collections::HashSet;
 
 use dijkstra::{path_n, FxIndexMap};
+use grid::{Direction, Grid, Position};
+use render::{Color, Picture};
 
 use crate::dijkstra::dijkstra;
+use solver::{diagnostics, recorder, ParseError, Part, Run, Solver};
 
 mod dijkstra;
 
 type PathItem = (Position, Direction, Vec<Direction>);
 
-pub fn process_part_1(input: &str) -> String {
-    let map = parse_input(input);
+const LOW_HEAT_LOSS: Color = Color(255, 240, 200);
+const HIGH_HEAT_LOSS: Color = Color(120, 40, 10);
+const PATH: Color = Color(20, 110, 230);
+
+pub struct Day17;
+
+impl Solver for Day17 {
+    fn day(&self) -> u8 {
+        17
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || parse_input(input);
+        match part {
+            Part::One => Run::timed(parse, part_1),
+            Part::Two => Run::timed(parse, part_2),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day17.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day17.solve(Part::Two, input)
+}
+
+fn part_1(map: Grid<u32>) -> String {
+    let (path, cost) = crucible_path(&map);
+
+    if recorder::recording() {
+        record_path(&map, &path);
+    }
+    if diagnostics::enabled() {
+        diagnostics::print(draw_path(&map, &path));
+    }
+
+    cost.to_string()
+}
+
+fn part_2(map: Grid<u32>) -> String {
+    let (path, cost) = ultra_crucible_path(&map);
+
+    if recorder::recording() {
+        record_path(&map, &path);
+    }
+    if diagnostics::enabled() {
+        diagnostics::print(draw_path(&map, &path));
+    }
+
+    cost.to_string()
+}
+
+/// The path of the least heat loss for a crucible and the heat loss.
+fn crucible_path(map: &Grid<u32>) -> (Vec<PathItem>, u32) {
     let start = (Position { x: -1, y: -1 }, Direction::North, vec![]);
     let target_position = Position {
-        x: map[0].len() as i32 - 1,
-        y: map.len() as i32 - 1,
+        x: map.width() as i32 - 1,
+        y: map.height() as i32 - 1,
     };
 
-    let (path, cost) = dijkstra(
+    dijkstra(
         &start,
-        |(position, direction, _), path, index| position.successors(&map, path, index, direction),
+        |(position, direction, _), path, index| successors(position, map, path, index, direction),
         |(position, _, _)| *position == target_position,
     )
-    .unwrap();
-
-    let mut map = map
-        .iter()
-        .map(|row| {
-            row.iter()
-                .map(|c| format!("{}", c))
-                .collect::<Vec<String>>()
-        })
-        .collect::<Vec<Vec<String>>>();
-    path.iter().skip(1).for_each(|(position, direction, _)| {
-        map[position.y as usize][position.x as usize] = format!("{}", direction);
-    });
-    let result_map = map
-        .iter()
-        .map(|row| row.join(""))
-        .collect::<Vec<String>>()
-        .join("\n");
-    println!("{}", result_map);
-
-    cost.to_string()
+    .unwrap()
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let map = parse_input(input);
+/// The path of the least heat loss for an ultra crucible and the heat loss.
+fn ultra_crucible_path(map: &Grid<u32>) -> (Vec<PathItem>, u32) {
     let start = (Position { x: -1, y: -1 }, Direction::North, vec![]);
     let target_position = Position {
-        x: map[0].len() as i32 - 1,
-        y: map.len() as i32 - 1,
+        x: map.width() as i32 - 1,
+        y: map.height() as i32 - 1,
     };
 
-    let (path, cost) = dijkstra(
+    dijkstra(
         &start,
         |(position, direction, _), path, index| {
-            position.successors_part_2(&map, path, index, direction)
+            successors_part_2(position, map, path, index, direction)
         },
         |(position, direction, path)| {
             *position == target_position && {
@@ -68,215 +105,179 @@ pub fn process_part_2(input: &str) -> String {
             }
         },
     )
-    .unwrap();
+    .unwrap()
+}
 
-    let mut map = map
+/// Draws the heat loss of the blocks and the path of the crucible, the ultra crucible for
+/// part 2.
+pub fn render(part: Part, input: &str) -> Result<Picture, ParseError> {
+    let map = parse_input(input)?;
+    let (path, cost) = match part {
+        Part::One => crucible_path(&map),
+        Part::Two => ultra_crucible_path(&map),
+    };
+    let on_path = path
         .iter()
-        .map(|row| {
-            row.iter()
-                .map(|c| format!("{}", c))
-                .collect::<Vec<String>>()
+        .skip(1)
+        .map(|(position, _, _)| *position)
+        .collect::<HashSet<_>>();
+    let heat_loss_color =
+        |heat_loss: u32| LOW_HEAT_LOSS.mix(HIGH_HEAT_LOSS, (heat_loss as f64 - 1.0) / 8.0);
+    let mut picture = Picture::new(
+        map.width() as f64,
+        map.height() as f64,
+        format!("least heat loss: {}", cost),
+    );
+    picture.cells(&map, |position, heat_loss| {
+        Some(if on_path.contains(&position) {
+            PATH
+        } else {
+            heat_loss_color(*heat_loss)
         })
-        .collect::<Vec<Vec<String>>>();
-    path.iter().skip(1).for_each(|(position, direction, _)| {
-        map[position.y as usize][position.x as usize] = format!("{}", direction);
     });
-    let result_map = map
-        .iter()
-        .map(|row| row.join(""))
-        .collect::<Vec<String>>()
-        .join("\n");
-    println!("{}", result_map);
-
-    cost.to_string()
-}
-
-fn parse_input(input: &str) -> Vec<Vec<u32>> {
-    input
-        .lines()
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
-        .collect()
-}
-
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
-impl Direction {
-    fn turn_left(&self) -> Direction {
-        match self {
-            Direction::North => Direction::West,
-            Direction::East => Direction::North,
-            Direction::South => Direction::East,
-            Direction::West => Direction::South,
-        }
+    for heat_loss in [1, 5, 9] {
+        picture.legend(
+            heat_loss_color(heat_loss),
+            format!("heat loss {}", heat_loss),
+        );
     }
+    picture.legend(PATH, "path");
+    Ok(picture)
+}
 
-    fn turn_right(&self) -> Direction {
-        match self {
-            Direction::North => Direction::East,
-            Direction::East => Direction::South,
-            Direction::South => Direction::West,
-            Direction::West => Direction::North,
+/// Records the crucible moving along `path`, a frame per block.
+fn record_path(map: &Grid<u32>, path: &[PathItem]) {
+    let mut heat_loss = 0;
+    // the first item is the virtual start outside of the map, and the first block is free
+    for (i, (position, _, _)) in path.iter().enumerate().skip(1) {
+        if i > 1 {
+            heat_loss += map[*position];
         }
+        recorder::record(
+            format!("heat loss {}", heat_loss),
+            draw_path(map, &path[..=i]),
+        );
     }
 }
 
-impl Display for Direction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Direction::North => write!(f, "^"),
-            Direction::East => write!(f, ">"),
-            Direction::South => write!(f, "v"),
-            Direction::West => write!(f, "<"),
-        }
-    }
+/// Draws `path` onto the map, showing the direction the crucible moved in.
+fn draw_path(map: &Grid<u32>, path: &[PathItem]) -> Grid<String> {
+    let mut map = map.map(|c| format!("{}", c));
+    path.iter().skip(1).for_each(|(position, direction, _)| {
+        map[*position] = format!("{}", direction);
+    });
+    map
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-struct Position {
-    x: i32,
-    y: i32,
+fn parse_input(input: &str) -> Result<Grid<u32>, ParseError> {
+    Grid::parse_with(input, "a digit", |c| c.to_digit(10))
 }
 
-impl Position {
-    fn new(x: i32, y: i32) -> Position {
-        Position { x, y }
+fn successors(
+    position: &Position,
+    map: &Grid<u32>,
+    path: &FxIndexMap<PathItem, (usize, u32)>,
+    index: usize,
+    direction: &Direction,
+) -> Vec<(PathItem, u32)> {
+    if position.x == -1 && position.y == -1 {
+        return vec![
+            ((Position::new(0, 0), Direction::East, vec![]), 0),
+            ((Position::new(0, 0), Direction::South, vec![]), 0),
+        ];
     }
-
-    fn move_in_direction(&self, direction: &Direction) -> Position {
-        match direction {
-            Direction::North => Position {
-                x: self.x,
-                y: self.y - 1,
-            },
-            Direction::East => Position {
-                x: self.x + 1,
-                y: self.y,
-            },
-            Direction::South => Position {
-                x: self.x,
-                y: self.y + 1,
-            },
-            Direction::West => Position {
-                x: self.x - 1,
-                y: self.y,
-            },
+    let path: Vec<PathItem> = path_n(path, |&(p, _)| p, index, 0, 3);
+    let directions = if path.is_empty() {
+        vec![
+            Direction::North,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+        ]
+    } else {
+        let continue_straight = if path.len() == 3 {
+            !path.iter().all(|(_, d, _)| d == direction)
+        } else {
+            true
+        };
+        if continue_straight {
+            vec![*direction, direction.turn_right(), direction.turn_left()]
+        } else {
+            vec![direction.turn_right(), direction.turn_left()]
         }
-    }
+    };
+    directions
+        .iter()
+        .map(|d| (position.move_in_direction(*d), d))
+        .filter_map(|(p, d)| {
+            let cost = map.get(p);
+            let prev_directions = path
+                .iter()
+                .map(|(_, d, _)| d)
+                .rev()
+                .cloned()
+                .collect::<Vec<_>>();
+            cost.map(|cost| ((p, *d, prev_directions), *cost))
+        })
+        .collect()
+}
 
-    fn successors(
-        &self,
-        map: &[Vec<u32>],
-        path: &FxIndexMap<PathItem, (usize, u32)>,
-        index: usize,
-        direction: &Direction,
-    ) -> Vec<(PathItem, u32)> {
-        if self.x == -1 && self.y == -1 {
-            return vec![
-                ((Position::new(0, 0), Direction::East, vec![]), 0),
-                ((Position::new(0, 0), Direction::South, vec![]), 0),
-            ];
-        }
-        let path: Vec<PathItem> = path_n(path, |&(p, _)| p, index, 0, 3);
-        let directions = if path.is_empty() {
-            vec![
-                Direction::North,
-                Direction::East,
-                Direction::South,
-                Direction::West,
-            ]
+fn successors_part_2(
+    position: &Position,
+    map: &Grid<u32>,
+    path: &FxIndexMap<PathItem, (usize, u32)>,
+    index: usize,
+    direction: &Direction,
+) -> Vec<(PathItem, u32)> {
+    if position.x == -1 && position.y == -1 {
+        return vec![
+            ((Position::new(0, 0), Direction::East, vec![]), 0),
+            ((Position::new(0, 0), Direction::South, vec![]), 0),
+        ];
+    }
+    let path: Vec<PathItem> = path_n(path, |&(p, _)| p, index, 0, 10);
+    let directions = if path.is_empty() {
+        vec![
+            Direction::North,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+        ]
+    } else {
+        let mut directions = vec![];
+        let continue_straight = if path.len() == 10 {
+            !path.iter().all(|(_, d, _)| d == direction)
         } else {
-            let continue_straight = if path.len() == 3 {
-                !path.iter().all(|(_, d, _)| d == direction)
-            } else {
-                true
-            };
-            if continue_straight {
-                vec![*direction, direction.turn_right(), direction.turn_left()]
-            } else {
-                vec![direction.turn_right(), direction.turn_left()]
-            }
+            true
         };
-        directions
-            .iter()
-            .map(|d| (self.move_in_direction(d), d))
-            .filter(|(p, _)| p.x >= 0 && p.y >= 0)
-            .filter_map(|(p, d)| {
-                let cost = map.get(p.y as usize).and_then(|row| row.get(p.x as usize));
-                let prev_directions = path
-                    .iter()
-                    .map(|(_, d, _)| d)
-                    .rev()
-                    .cloned()
-                    .collect::<Vec<_>>();
-                cost.map(|cost| ((p, *d, prev_directions), *cost))
-            })
-            .collect()
-    }
-
-    fn successors_part_2(
-        &self,
-        map: &[Vec<u32>],
-        path: &FxIndexMap<PathItem, (usize, u32)>,
-        index: usize,
-        direction: &Direction,
-    ) -> Vec<(PathItem, u32)> {
-        if self.x == -1 && self.y == -1 {
-            return vec![
-                ((Position::new(0, 0), Direction::East, vec![]), 0),
-                ((Position::new(0, 0), Direction::South, vec![]), 0),
-            ];
-        }
-        let path: Vec<PathItem> = path_n(path, |&(p, _)| p, index, 0, 10);
-        let directions = if path.is_empty() {
-            vec![
-                Direction::North,
-                Direction::East,
-                Direction::South,
-                Direction::West,
-            ]
+        let turn = if path.len() >= 4 {
+            path.iter().take(4).all(|(_, d, _)| d == direction)
         } else {
-            let mut directions = vec![];
-            let continue_straight = if path.len() == 10 {
-                !path.iter().all(|(_, d, _)| d == direction)
-            } else {
-                true
-            };
-            let turn = if path.len() >= 4 {
-                path.iter().take(4).all(|(_, d, _)| d == direction)
-            } else {
-                false
-            };
-            if continue_straight {
-                directions.push(*direction);
-            }
-            if turn {
-                directions.push(direction.turn_right());
-                directions.push(direction.turn_left());
-            }
-            directions
+            false
         };
+        if continue_straight {
+            directions.push(*direction);
+        }
+        if turn {
+            directions.push(direction.turn_right());
+            directions.push(direction.turn_left());
+        }
         directions
-            .iter()
-            .map(|d| (self.move_in_direction(d), d))
-            .filter(|(p, _)| p.x >= 0 && p.y >= 0)
-            .filter_map(|(p, d)| {
-                let cost = map.get(p.y as usize).and_then(|row| row.get(p.x as usize));
-                let prev_directions = path
-                    .iter()
-                    .map(|(_, d, _)| d)
-                    .rev()
-                    .cloned()
-                    .collect::<Vec<_>>();
-                cost.map(|cost| ((p, *d, prev_directions), *cost))
-            })
-            .collect()
-    }
+    };
+    directions
+        .iter()
+        .map(|d| (position.move_in_direction(*d), d))
+        .filter_map(|(p, d)| {
+            let cost = map.get(p);
+            let prev_directions = path
+                .iter()
+                .map(|(_, d, _)| d)
+                .rev()
+                .cloned()
+                .collect::<Vec<_>>();
+            cost.map(|cost| ((p, *d, prev_directions), *cost))
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -299,13 +300,32 @@ mod tests {
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "102");
     }
 
+    #[test]
+    fn render_works() {
+        let svg = render(Part::One, INPUT).unwrap().to_svg();
+        assert!(svg.contains(">least heat loss: 102</text>"));
+        // the path runs from the top left to the bottom right
+        let row = |y: usize| {
+            svg.lines()
+                .filter(move |line| line.contains(&format!(r#"y="{}" "#, y)))
+                .filter(|line| line.starts_with("<rect x="))
+        };
+        assert!(row(0).next().unwrap().ends_with(r##"fill="#146ee6"/>"##));
+        assert!(row(12)
+            .next_back()
+            .unwrap()
+            .ends_with(r##"fill="#146ee6"/>"##));
+        let svg = render(Part::Two, INPUT).unwrap().to_svg();
+        assert!(svg.contains(">least heat loss: 94</text>"));
+    }
+
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "94");
     }
 }
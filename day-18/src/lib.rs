@@ -4,19 +4,43 @@ use std::{collections::HashMap, fmt::Display};
 
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::{
-        complete::{self, line_ending, space1},
-        streaming::hex_digit1,
-    },
+    character::complete::{self, hex_digit1, line_ending, space1},
     multi::separated_list1,
     sequence::{delimited, preceded, tuple},
-    IResult, Parser,
+    Parser,
 };
+use render::{Color, Picture};
+use solver::parse::{parse_all, tag, Expected, IResult, NomError};
+use solver::{recorder, ParseError, Part, Run, Solver};
 
-pub fn process_part_1(input: &str) -> String {
-    let (input, steps) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
+const LAGOON: Color = Color(90, 160, 230);
+const TRENCH: Color = Color(110, 70, 40);
+
+pub struct Day18;
+
+impl Solver for Day18 {
+    fn day(&self) -> u8 {
+        18
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        // part 2 reads the instructions from the colour codes
+        match part {
+            Part::One => Run::timed(|| parse_all(input, parse_input), part_1),
+            Part::Two => Run::timed(|| parse_all(input, parse_input_part_2), part_2),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day18.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day18.solve(Part::Two, input)
+}
+
+fn part_1(steps: Vec<Step>) -> String {
     let mut trench: HashMap<Position, Tile> = HashMap::new();
     let mut position = Position::new(0, 0);
     trench.insert(
@@ -26,7 +50,7 @@ pub fn process_part_1(input: &str) -> String {
             end: Direction::Unknown,
         },
     );
-    steps.iter().for_each(|step| {
+    steps.iter().enumerate().for_each(|(i, step)| {
         (0..step.amount).for_each(|_| {
             let next_position = position.move_in_direction(&step.direction, 1);
             if let Some(Tile::Edge { start: _, end }) = trench.get_mut(&position) {
@@ -43,6 +67,12 @@ pub fn process_part_1(input: &str) -> String {
 
             position = next_position;
         });
+        if recorder::recording() {
+            recorder::record(
+                format!("instruction {} of {}", i + 1, steps.len()),
+                draw_trench(&trench),
+            );
+        }
     });
     trench.entry(Position::new(0, 0)).and_modify(|tile| {
         if let Tile::Edge { start, end: _ } = tile {
@@ -52,13 +82,16 @@ pub fn process_part_1(input: &str) -> String {
         }
     });
     let filled_trench = fill_trench(&trench);
-    // print_trench(&filled_trench);
+    if recorder::recording() {
+        recorder::record(
+            format!("{} cubic meters dug out", filled_trench.len()),
+            draw_trench(&filled_trench),
+        );
+    }
     filled_trench.len().to_string()
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let (input, steps) = parse_input_part_2(input).unwrap();
-    debug_assert_eq!(input, "");
+fn part_2(steps: Vec<Step>) -> String {
     let mut position = Position::new(0, 0);
     let mut vertical_edges = vec![];
     let mut horizontal_edges = vec![];
@@ -87,7 +120,7 @@ pub fn process_part_2(input: &str) -> String {
             std::mem::swap(&mut edge.start, &mut edge.end);
         }
     });
-    vertical_edges.sort_by(|a, b| a.start.x.cmp(&b.start.x));
+    vertical_edges.sort_by_key(|a| a.start.x);
     let min_y = vertical_edges.iter().map(|e| e.start.y).min().unwrap();
     let max_y = vertical_edges.iter().map(|e| e.end.y).max().unwrap();
     let mut area = 0;
@@ -141,6 +174,38 @@ pub fn process_part_2(input: &str) -> String {
     area.to_string()
 }
 
+/// Draws the lagoon, with the trench through the middle of the outermost blocks.
+pub fn render(part: Part, input: &str) -> Result<Picture, ParseError> {
+    let steps = match part {
+        Part::One => parse_all(input, parse_input)?,
+        Part::Two => parse_all(input, parse_input_part_2)?,
+    };
+    let mut position = Position::new(0, 0);
+    let mut corners = vec![position];
+    for step in &steps {
+        position = position.move_in_direction(&step.direction, step.amount as i64);
+        corners.push(position);
+    }
+    let min_x = corners.iter().map(|p| p.x).min().unwrap();
+    let max_x = corners.iter().map(|p| p.x).max().unwrap();
+    let min_y = corners.iter().map(|p| p.y).min().unwrap();
+    let max_y = corners.iter().map(|p| p.y).max().unwrap();
+    let points = corners
+        .iter()
+        .map(|p| ((p.x - min_x) as f64 + 0.5, (p.y - min_y) as f64 + 0.5))
+        .collect::<Vec<_>>();
+    let mut picture = Picture::new(
+        (max_x - min_x + 1) as f64,
+        (max_y - min_y + 1) as f64,
+        format!("{} cubic meters dug out", part_2(steps)),
+    );
+    picture.polygon(points.clone(), LAGOON);
+    picture.outline(points, TRENCH, 1.0);
+    picture.legend(LAGOON, "lagoon");
+    picture.legend(TRENCH, "trench");
+    Ok(picture)
+}
+
 fn parse_input(input: &str) -> IResult<&str, Vec<Step>> {
     separated_list1(line_ending, parse_step)(input)
 }
@@ -165,24 +230,44 @@ fn parse_input_part_2(input: &str) -> IResult<&str, Vec<Step>> {
 }
 
 fn parse_step_part_2(input: &str) -> IResult<&str, Step> {
-    let (input, (_direction, _amount, color)) = tuple((
+    let (input, (_direction, _amount, (amount, direction))) = tuple((
         alt((tag("U"), tag("D"), tag("L"), tag("R"))),
         delimited(space1, complete::u32, space1),
-        delimited(tag("("), preceded(tag("#"), hex_digit1), tag(")")),
+        delimited(
+            tag("("),
+            preceded(
+                tag("#"),
+                tuple((
+                    parse_hex_amount,
+                    alt((
+                        tag("0").map(|_| Direction::Right),
+                        tag("1").map(|_| Direction::Down),
+                        tag("2").map(|_| Direction::Left),
+                        tag("3").map(|_| Direction::Up),
+                    )),
+                )),
+            ),
+            tag(")"),
+        ),
     ))(input)?;
-    let amount = u32::from_str_radix(&color[0..5], 16).unwrap();
-    let direction = u32::from_str_radix(&color[5..6], 16).unwrap();
-    let direction = match direction {
-        0 => Direction::Right,
-        1 => Direction::Down,
-        2 => Direction::Left,
-        3 => Direction::Up,
-        _ => panic!("Invalid direction"),
-    };
     let step = Step { direction, amount };
     Ok((input, step))
 }
 
+/// The first five digits of the colour code are the distance in hexadecimal.
+fn parse_hex_amount(input: &str) -> IResult<&str, u32> {
+    match input
+        .get(..5)
+        .filter(|digits| digits.chars().all(|c| c.is_ascii_hexdigit()))
+    {
+        Some(digits) => Ok((&input[5..], u32::from_str_radix(digits, 16).unwrap())),
+        None => Err(nom::Err::Error(NomError::new(
+            input,
+            Expected::Description("five hexadecimal digits"),
+        ))),
+    }
+}
+
 fn fill_trench(trench: &HashMap<Position, Tile>) -> HashMap<Position, Tile> {
     let mut filled_trench = trench.clone();
 
@@ -196,15 +281,13 @@ fn fill_trench(trench: &HashMap<Position, Tile>) -> HashMap<Position, Tile> {
         for x in min_x..max_x {
             let position = Position::new(x, y);
             match trench.get(&position) {
-                Some(Tile::Edge { start, end }) => {
-                    if matches!(start, Direction::Up) || matches!(end, Direction::Up) {
-                        crossed += 1;
-                    }
+                Some(Tile::Edge { start, end })
+                    if (matches!(start, Direction::Up) || matches!(end, Direction::Up)) =>
+                {
+                    crossed += 1;
                 }
-                None => {
-                    if crossed % 2 == 1 {
-                        filled_trench.insert(position, Tile::Fill);
-                    }
+                None if crossed % 2 == 1 => {
+                    filled_trench.insert(position, Tile::Fill);
                 }
                 _ => {}
             }
@@ -214,8 +297,7 @@ fn fill_trench(trench: &HashMap<Position, Tile>) -> HashMap<Position, Tile> {
     filled_trench
 }
 
-#[allow(dead_code)]
-fn print_trench(tiles: &HashMap<Position, Tile>) {
+fn draw_trench(tiles: &HashMap<Position, Tile>) -> String {
     let min_x = tiles.keys().map(|p| p.x).min().unwrap();
     let max_x = tiles.keys().map(|p| p.x).max().unwrap();
     let min_y = tiles.keys().map(|p| p.y).min().unwrap();
@@ -225,14 +307,13 @@ fn print_trench(tiles: &HashMap<Position, Tile>) {
     tiles.iter().for_each(|(position, tile)| {
         map[(position.y - min_y) as usize][(position.x - min_x) as usize] = *tile;
     });
-    let result_map = map.iter().fold(String::new(), |mut output, row| {
+    map.iter().fold(String::new(), |mut output, row| {
         row.iter().for_each(|c| {
             let _ = write!(output, "{}", c);
         });
         let _ = writeln!(output);
         output
-    });
-    println!("{}", result_map);
+    })
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -291,6 +372,8 @@ impl Display for Tile {
                 (Direction::Left, Direction::Right) | (Direction::Right, Direction::Left) => {
                     write!(f, "━")
                 }
+                // the end of the trench while it is being dug
+                (Direction::Unknown, _) | (_, Direction::Unknown) => write!(f, "█"),
                 _ => panic!("Invalid edge: {:?} {:?}", start, end),
             },
             Tile::Fill => write!(f, "#"),
@@ -342,6 +425,7 @@ struct Edge {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     const INPUT: &str = "R 6 (#70c710)
 D 5 (#0dc571)
@@ -360,13 +444,33 @@ U 2 (#7a21e3)";
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "62");
     }
 
+    #[test]
+    fn render_works() {
+        let svg = render(Part::One, INPUT).unwrap().to_svg();
+        assert!(svg.contains(">62 cubic meters dug out</text>"));
+        assert!(svg.contains(r##"<polygon points="0.5,0.5 6.5,0.5 6.5,5.5 4.5,5.5"##));
+        assert!(svg.contains(r##"fill="none" stroke="#6e4628" stroke-width="1"/>"##));
+        let svg = render(Part::Two, INPUT).unwrap().to_svg();
+        assert!(svg.contains(">952408144115 cubic meters dug out</text>"));
+    }
+
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "952408144115");
     }
+
+    #[rstest]
+    #[case("R 6 (#70c71)", "expected '0', '1', '2' or '3' on line 1, column 12")]
+    #[case("R 6 (#70c714)", "expected '0', '1', '2' or '3' on line 1, column 12")]
+    #[case("R 6 (#70c7)", "expected five hexadecimal digits on line 1, column 7")]
+    #[case("R 6 (#70c7100)", "expected ')' on line 1, column 13")]
+    fn malformed_colour_is_reported(#[case] input: &str, #[case] expected: &str) {
+        let result = process_part_2(input).unwrap_err();
+        assert_eq!(result.to_string(), expected);
+    }
 }
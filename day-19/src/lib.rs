@@ -1,22 +1,80 @@
 use std::collections::HashMap;
 use std::fmt::Display;
-use std::fs;
+use std::io::Write;
 use std::ops::{Add, RangeInclusive};
 
 use nom::character;
 use nom::character::complete::{alpha1, line_ending};
 use nom::multi::many1;
-use nom::{
-    branch::alt, bytes::complete::tag, multi::separated_list1, sequence::delimited, IResult, Parser,
-};
+use nom::{branch::alt, multi::separated_list1, sequence::delimited, Parser};
 use petgraph::dot::Dot;
 use petgraph::graph::DiGraph;
 use petgraph::stable_graph::NodeIndex;
+use petgraph::visit::EdgeRef;
 use petgraph::Graph;
+use solver::parse::{parse_all, tag, IResult};
+use solver::{params, ExportError, ParseError, Run, Solver};
 
-pub fn process_part_1(input: &str) -> String {
-    let (input, (workflows, parts)) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
+pub struct Day19;
+
+/// The ratings a part can have in part 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Day19Params {
+    pub min_rating: i64,
+    pub max_rating: i64,
+}
+
+impl Default for Day19Params {
+    fn default() -> Self {
+        Self {
+            min_rating: 1,
+            max_rating: 4000,
+        }
+    }
+}
+
+impl params::Params for Day19Params {
+    const NAMES: &'static [&'static str] = &["min_rating", "max_rating"];
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "min_rating" => self.min_rating = params::parse(name, value)?,
+            _ => self.max_rating = params::parse(name, value)?,
+        }
+        Ok(())
+    }
+}
+
+impl Solver for Day19 {
+    fn day(&self) -> u8 {
+        19
+    }
+
+    fn check_params(&self, overrides: &[(String, String)]) -> Result<(), String> {
+        params::with_overrides::<Day19Params>(overrides).map(|_| ())
+    }
+
+    fn run(&self, part: solver::Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || parse_workflows(input);
+        match part {
+            solver::Part::One => Run::timed(parse, part_1),
+            solver::Part::Two => {
+                let params = params::current();
+                Run::timed(parse, |input| part_2(input, params))
+            }
+        }
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day19.solve(solver::Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day19.solve(solver::Part::Two, input)
+}
+
+fn part_1((workflows, parts): (HashMap<&str, Workflow>, Vec<Part>)) -> String {
     parts
         .iter()
         .filter(|part| {
@@ -62,21 +120,17 @@ pub fn process_part_1(input: &str) -> String {
         .to_string()
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let (input, (workflows, _)) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
+fn part_2((workflows, _): (HashMap<&str, Workflow>, Vec<Part>), params: Day19Params) -> String {
     let graph = make_graph(&workflows);
-    fs::write("./graph.dot", format!("{}", Dot::with_config(&graph, &[]))).unwrap();
     let root = graph.node_indices().find(|i| graph[*i] == "in").unwrap();
     let paths = walk(&graph, root, Conditions(vec![]));
     paths
         .iter()
         .map(|path| {
             let mut ranges: HashMap<&str, RangeInclusive<i64>> = HashMap::new();
-            ranges.insert("x", 1..=4000);
-            ranges.insert("m", 1..=4000);
-            ranges.insert("a", 1..=4000);
-            ranges.insert("s", 1..=4000);
+            for category in ["x", "m", "a", "s"] {
+                ranges.insert(category, params.min_rating..=params.max_rating);
+            }
             for condition in &path.0 {
                 ranges.entry(condition.part_category).and_modify(|range| {
                     match condition.relation {
@@ -95,13 +149,37 @@ pub fn process_part_2(input: &str) -> String {
             }
             ranges
                 .values()
-                .map(|r| r.end() - r.start() + 1)
+                .map(|r| (r.end() - r.start() + 1).max(0))
                 .product::<i64>()
         })
         .sum::<i64>()
         .to_string()
 }
 
+/// Writes the workflows as a Graphviz digraph. The accepted and rejected end states are
+/// highlighted and every edge is labelled with the conditions to follow it.
+pub fn export_dot(input: &str, writer: &mut dyn Write) -> Result<(), ExportError> {
+    let (workflows, _) = parse_workflows(input)?;
+    let graph = make_graph(&workflows);
+    let dot = Dot::with_attr_getters(
+        &graph,
+        &[],
+        &|graph, edge| match graph[edge.target()] {
+            "A" => "color=darkgreen".to_string(),
+            "R" => "color=red".to_string(),
+            _ => String::new(),
+        },
+        &|_, (_, name)| match *name {
+            "in" => "shape=doublecircle".to_string(),
+            "A" => "shape=box, style=filled, fillcolor=palegreen".to_string(),
+            "R" => "shape=box, style=filled, fillcolor=lightcoral".to_string(),
+            _ => "shape=ellipse".to_string(),
+        },
+    );
+    writeln!(writer, "{}", dot)?;
+    Ok(())
+}
+
 fn walk<'a>(
     graph: &'a Graph<&str, Conditions<'a>>,
     node_index: NodeIndex,
@@ -123,15 +201,19 @@ fn walk<'a>(
 
 fn make_graph<'a>(workflows: &'a HashMap<&str, Workflow<'a>>) -> DiGraph<&'a str, Conditions<'a>> {
     let mut graph = DiGraph::<&str, Conditions<'_>>::new();
-    for workflow_name in workflows.keys() {
+    // sorted to get the same graph, and the same Graphviz export, on every run
+    let mut workflow_names = workflows.keys().copied().collect::<Vec<_>>();
+    workflow_names.sort();
+    for workflow_name in &workflow_names {
         graph.add_node(workflow_name);
     }
     graph.add_node("A");
     graph.add_node("R");
-    for (workflow_name, workflow) in workflows {
+    for workflow_name in workflow_names {
+        let workflow = &workflows[workflow_name];
         let a = graph
             .node_indices()
-            .find(|i| graph[*i] == *workflow_name)
+            .find(|i| graph[*i] == workflow_name)
             .unwrap();
         let mut conditions = vec![];
         for rule in workflow.rules.iter() {
@@ -151,7 +233,33 @@ fn make_graph<'a>(workflows: &'a HashMap<&str, Workflow<'a>>) -> DiGraph<&'a str
     graph
 }
 
-fn parse_input(input: &str) -> IResult<&str, (HashMap<&str, Workflow>, Vec<Part>)> {
+type Workflows<'a> = (HashMap<&'a str, Workflow<'a>>, Vec<Part<'a>>);
+
+/// Parses the input and checks that there is an `in` workflow and that every rule sends parts to
+/// a workflow that exists.
+fn parse_workflows(input: &str) -> Result<Workflows<'_>, ParseError> {
+    let (workflows, parts) = parse_all(input, parse_input)?;
+    if !workflows.contains_key("in") {
+        return Err(ParseError::at_offset(input, 0, "a workflow named in"));
+    }
+    let mut names = workflows.keys().copied().collect::<Vec<_>>();
+    names.sort_by_key(|name| name.as_ptr());
+    for name in names {
+        for rule in &workflows[name].rules {
+            let (Rule::Jump { target } | Rule::ConditionalJump { target, .. }) = rule;
+            if !matches!(*target, "A" | "R") && !workflows.contains_key(target) {
+                return Err(ParseError::at(
+                    input,
+                    target,
+                    "A, R or the name of a workflow",
+                ));
+            }
+        }
+    }
+    Ok((workflows, parts))
+}
+
+fn parse_input(input: &str) -> IResult<&str, (HashMap<&str, Workflow<'_>>, Vec<Part<'_>>)> {
     let (input, workflows) = separated_list1(line_ending, parse_workflow)(input)?;
     let (input, _) = many1(line_ending)(input)?;
     let (input, parts) = separated_list1(line_ending, parse_part)(input)?;
@@ -159,7 +267,7 @@ fn parse_input(input: &str) -> IResult<&str, (HashMap<&str, Workflow>, Vec<Part>
     Ok((input, (workflows, parts)))
 }
 
-fn parse_workflow(input: &str) -> IResult<&str, Workflow> {
+fn parse_workflow(input: &str) -> IResult<&str, Workflow<'_>> {
     let (input, name) = alpha1(input)?;
     let (input, rules) =
         delimited(tag("{"), separated_list1(tag(","), parse_rule), tag("}"))(input)?;
@@ -167,17 +275,17 @@ fn parse_workflow(input: &str) -> IResult<&str, Workflow> {
     Ok((input, workflow))
 }
 
-fn parse_rule(input: &str) -> IResult<&str, Rule> {
+fn parse_rule(input: &str) -> IResult<&str, Rule<'_>> {
     alt((parse_rule_conditional_jump, parse_rule_jump))(input)
 }
 
-fn parse_rule_jump(input: &str) -> IResult<&str, Rule> {
+fn parse_rule_jump(input: &str) -> IResult<&str, Rule<'_>> {
     let (input, target) = alpha1(input)?;
     let rule = Rule::Jump { target };
     Ok((input, rule))
 }
 
-fn parse_rule_conditional_jump(input: &str) -> IResult<&str, Rule> {
+fn parse_rule_conditional_jump(input: &str) -> IResult<&str, Rule<'_>> {
     let (input, part_category) = alpha1(input)?;
     let (input, relation) = alt((
         tag("<").map(|_| Relation::Lt),
@@ -195,7 +303,7 @@ fn parse_rule_conditional_jump(input: &str) -> IResult<&str, Rule> {
     Ok((input, rule))
 }
 
-fn parse_part(input: &str) -> IResult<&str, Part> {
+fn parse_part(input: &str) -> IResult<&str, Part<'_>> {
     let (input, ratings) =
         delimited(tag("{"), separated_list1(tag(","), parse_rating), tag("}"))(input)?;
     let part = Part {
@@ -336,13 +444,70 @@ hdj{m>838:A,pv}
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "19114");
     }
 
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "167409079868000");
     }
+
+    #[test]
+    fn workflows_are_checked() {
+        let ratings = "\n\n{x=1,m=1,a=1,s=1}";
+        assert_eq!(
+            process_part_1(&format!("px{{x<3:A,R}}{}", ratings)),
+            Err(ParseError::new(1, 1, "a workflow named in"))
+        );
+        assert_eq!(
+            process_part_2(&format!("in{{x<3:px,R}}\npx{{a>1:qq,A}}{}", ratings)),
+            Err(ParseError::new(2, 8, "A, R or the name of a workflow"))
+        );
+        assert_eq!(
+            process_part_1(&format!("in{{x<3:A,qq}}{}", ratings)),
+            Err(ParseError::new(1, 10, "A, R or the name of a workflow"))
+        );
+    }
+
+    #[test]
+    fn part_2_with_other_ratings() {
+        let input = "in{x<3:A,R}\n\n{x=1,m=1,a=1,s=1}";
+        let part_2 = |min_rating, max_rating| {
+            let params = Day19Params {
+                min_rating,
+                max_rating,
+            };
+            part_2(parse_workflows(input).unwrap(), params)
+        };
+        // x is 1 or 2, the others anything
+        assert_eq!(part_2(1, 10), (2 * 10 * 10 * 10).to_string());
+        assert_eq!(part_2(0, 1), (2 * 2 * 2 * 2).to_string());
+        assert_eq!(part_2(5, 10), "0");
+    }
+
+    #[test]
+    fn export_dot_works() {
+        let mut dot = vec![];
+        export_dot(INPUT, &mut dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.contains(r#"[ label = "A" shape=box, style=filled, fillcolor=palegreen]"#));
+        assert!(dot.contains(r#"label = "[x>2662]" color=darkgreen"#));
+        assert_eq!(dot.matches("->").count(), 25);
+    }
+
+    #[test]
+    fn export_dot_is_deterministic() {
+        let export = || {
+            let mut dot = vec![];
+            export_dot(INPUT, &mut dot).unwrap();
+            dot
+        };
+        let first = export();
+        for _ in 0..5 {
+            assert_eq!(export(), first);
+        }
+    }
 }
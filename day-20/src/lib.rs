@@ -1,20 +1,72 @@
 use std::collections::HashMap;
 use std::fmt::Display;
-use std::fs;
+use std::io::Write;
 
 use nom::character::complete::{alpha1, line_ending};
-use nom::{branch::alt, bytes::complete::tag, multi::separated_list1, IResult};
+use nom::{branch::alt, error::context, multi::separated_list1};
 use petgraph::dot::Dot;
 use petgraph::graph::DiGraph;
+use solver::parse::{parse_all, tag, IResult};
+use solver::{params, ExportError, ParseError, Part, Run, Solver};
 
-pub fn process_part_1(input: &str) -> String {
-    let (input, parsed_modules) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
+pub struct Day20;
+
+/// Button presses of part 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Day20Params {
+    pub presses: usize,
+}
+
+impl Default for Day20Params {
+    fn default() -> Self {
+        Self { presses: 1000 }
+    }
+}
+
+impl params::Params for Day20Params {
+    const NAMES: &'static [&'static str] = &["presses"];
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        self.presses = params::parse(name, value)?;
+        Ok(())
+    }
+}
+
+impl Solver for Day20 {
+    fn day(&self) -> u8 {
+        20
+    }
+
+    fn check_params(&self, overrides: &[(String, String)]) -> Result<(), String> {
+        params::with_overrides::<Day20Params>(overrides).map(|_| ())
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || parse_all(input, parse_input);
+        match part {
+            Part::One => {
+                let params = params::current();
+                Run::timed(parse, |modules| part_1(modules, params))
+            }
+            Part::Two => Run::timed(parse, part_2),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day20.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day20.solve(Part::Two, input)
+}
+
+fn part_1(parsed_modules: Vec<ParsedModule>, params: Day20Params) -> String {
     let modules = make_modules(parsed_modules);
     let mut modules = modules;
     let mut high_pulses: u64 = 0;
     let mut low_pulses: u64 = 0;
-    for _ in 0..1000 {
+    for _ in 0..params.presses {
         let mut pulses = vec![Pulse {
             source: "button".to_string(),
             target: "broadcaster".to_string(),
@@ -33,9 +85,18 @@ pub fn process_part_1(input: &str) -> String {
     (high_pulses * low_pulses).to_string()
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let (input, parsed_modules) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
+fn part_2(_parsed_modules: Vec<ParsedModule>) -> String {
+    "Look at the graph written by `aoc graph --day 20`. \
+    In this example there are four binary counters with a conjuction node in the middle. \
+    Some parts of the binary counter nodes have an output toward the middle conjuction (these are the 1-s), others don't (these are the zeroes). \
+    Figure out the four binary numbers from the counters. (When all the counter parts, that have a connection to the center conjunction are on, that is when the conjunction triggers) \
+    Once you have the four binary numbers, find their least common multiple, that is the answer".to_string()
+}
+
+/// Writes the module configuration as a Graphviz digraph, the shape of a node shows the type of
+/// its module.
+pub fn export_dot(input: &str, writer: &mut dyn Write) -> Result<(), ExportError> {
+    let parsed_modules = parse_all(input, parse_input)?;
     let graph = make_graph(&parsed_modules);
     let dot = Dot::with_attr_getters(
         &graph,
@@ -48,12 +109,8 @@ pub fn process_part_2(input: &str) -> String {
             ModuleType::Output => "shape=box".to_string(),
         },
     );
-    fs::write("./graph.dot", format!("{}", dot)).unwrap();
-    "Look at the graph in graph.dot. \
-    In this example there are four binary counters with a conjuction node in the middle. \
-    Some parts of the binary counter nodes have an output toward the middle conjuction (these are the 1-s), others don't (these are the zeroes). \
-    Figure out the four binary numbers from the counters. (When all the counter parts, that have a connection to the center conjunction are on, that is when the conjunction triggers) \
-    Once you have the four binary numbers, find their least common multiple, that is the answer".to_string()
+    writeln!(writer, "{}", dot)?;
+    Ok(())
 }
 
 fn make_graph<'a>(parsed_modules: &[ParsedModule<'a>]) -> DiGraph<Node<'a>, String> {
@@ -117,7 +174,7 @@ fn parse_module(input: &str) -> IResult<&str, ParsedModule<'_>> {
 
 fn parse_broadcast_module(input: &str) -> IResult<&str, ParsedModule<'_>> {
     let (input, name) = tag("broadcaster")(input)?;
-    let (input, _) = tag(" -> ")(input)?;
+    let (input, _) = context("after module name", tag(" -> "))(input)?;
     let (input, output) = parse_outputs(input)?;
     let module = ParsedModule {
         module_type: ModuleType::Broadcast,
@@ -130,7 +187,7 @@ fn parse_broadcast_module(input: &str) -> IResult<&str, ParsedModule<'_>> {
 fn parse_flip_flop_module(input: &str) -> IResult<&str, ParsedModule<'_>> {
     let (input, _) = tag("%")(input)?;
     let (input, name) = alpha1(input)?;
-    let (input, _) = tag(" -> ")(input)?;
+    let (input, _) = context("after module name", tag(" -> "))(input)?;
     let (input, output) = parse_outputs(input)?;
     let module = ParsedModule {
         module_type: ModuleType::FlipFlop,
@@ -143,7 +200,7 @@ fn parse_flip_flop_module(input: &str) -> IResult<&str, ParsedModule<'_>> {
 fn parse_conjunction_module(input: &str) -> IResult<&str, ParsedModule<'_>> {
     let (input, _) = tag("&")(input)?;
     let (input, name) = alpha1(input)?;
-    let (input, _) = tag(" -> ")(input)?;
+    let (input, _) = context("after module name", tag(" -> "))(input)?;
     let (input, output) = parse_outputs(input)?;
     let module = ParsedModule {
         module_type: ModuleType::Conjunction,
@@ -347,7 +404,42 @@ mod tests {
     #[case(INPUT_1, "32000000")]
     #[case(INPUT_2, "11687500")]
     fn part_1_works(#[case] input: &str, #[case] expected: &str) {
-        let result = process_part_1(input);
+        let result = process_part_1(input).unwrap();
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn part_1_with_one_press() {
+        let modules = parse_all(INPUT_1, parse_input).unwrap();
+        assert_eq!(part_1(modules, Day20Params { presses: 1 }), "32");
+    }
+
+    #[rstest]
+    #[case(
+        "broadcaster -> a\n%a -< b",
+        "expected ' -> ' after module name on line 2, column 3"
+    )]
+    #[case(
+        "broadcaster -> a\n$a -> b",
+        "expected 'broadcaster', '%' or '&' on line 2, column 1"
+    )]
+    #[case(
+        "broadcaster -> a\n%a -> b\n\nx",
+        "expected 'broadcaster', '%' or '&' on line 3, column 1"
+    )]
+    fn malformed_input_is_reported(#[case] input: &str, #[case] expected: &str) {
+        let result = process_part_1(input).unwrap_err();
+        assert_eq!(result.to_string(), expected);
+    }
+
+    #[test]
+    fn export_dot_works() {
+        let mut dot = vec![];
+        export_dot(INPUT_2, &mut dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        assert!(dot.contains(r#"[ label = "broadcaster" shape=house]"#));
+        assert!(dot.contains(r#"[ label = "inv" shape=oval]"#));
+        assert!(dot.contains(r#"[ label = "output" shape=box]"#));
+        assert_eq!(dot.matches("->").count(), 6);
+    }
 }
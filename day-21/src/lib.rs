@@ -1,70 +1,217 @@
 use std::collections::{HashMap, HashSet};
 
+use grid::{Grid, Position};
 use pathfinding::directed::dijkstra::dijkstra_all;
+use solver::{params, recorder, ParseError, Part, Run, Solver};
 
-pub fn process_part_1(input: &str, steps: i32) -> String {
-    let (start, map) = parse_input(input);
+const TILES: [(char, Tile); 3] = [
+    ('.', Tile::GardenPlot),
+    ('#', Tile::Rock),
+    ('S', Tile::Start),
+];
+
+pub struct Day21;
+
+/// Steps of both parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Day21Params {
+    pub steps: i32,
+    pub extended_steps: i32,
+}
+
+impl Default for Day21Params {
+    fn default() -> Self {
+        Self {
+            steps: 64,
+            extended_steps: 26501365,
+        }
+    }
+}
+
+impl params::Params for Day21Params {
+    const NAMES: &'static [&'static str] = &["steps", "extended_steps"];
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "steps" => self.steps = params::parse(name, value)?,
+            _ => self.extended_steps = params::parse(name, value)?,
+        }
+        Ok(())
+    }
+}
+
+impl Solver for Day21 {
+    fn day(&self) -> u8 {
+        21
+    }
+
+    fn check_params(&self, overrides: &[(String, String)]) -> Result<(), String> {
+        params::with_overrides::<Day21Params>(overrides).map(|_| ())
+    }
+
+    fn check_input_params(&self, part: Part, input: &str) -> Result<(), String> {
+        let params: Day21Params = params::current();
+        match (part, parse_input(input)) {
+            (Part::Two, Ok((_, map))) => maps_across(&map, params.extended_steps)
+                .map(|_| ())
+                .map_err(|expected| {
+                    format!(
+                        "Invalid value {} of extended_steps: expected {}",
+                        params.extended_steps, expected
+                    )
+                }),
+            // a map that doesn't parse is reported when running
+            _ => Ok(()),
+        }
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || parse_input(input);
+        let params: Day21Params = params::current();
+        match part {
+            Part::One => Run::timed(parse, |(start, map)| part_1(start, map, params.steps)),
+            Part::Two => Run::timed(
+                || parse_extended(input, params.extended_steps),
+                |(start, map, n)| part_2(start, map, n),
+            ),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str, steps: i32) -> Result<String, ParseError> {
+    let (start, map) = parse_input(input)?;
+    Ok(part_1(start, map, steps))
+}
+
+pub fn process_part_2(input: &str, steps: i32) -> Result<String, ParseError> {
+    let (start, map, n) = parse_extended(input, steps)?;
+    Ok(part_2(start, map, n))
+}
+
+fn part_1(start: Position, map: Grid<Tile>, steps: i32) -> String {
+    if recorder::recording() {
+        record_frontier(&map, start, steps);
+    }
     visit(&map, start, steps).len().to_string()
 }
 
-// https://github.com/villuna/aoc23/wiki/A-Geometric-solution-to-advent-of-code-2023,-day-21
-pub fn process_part_2(input: &str, steps: i32) -> String {
-    let (start, map) = parse_input(input);
+/// Records the garden plots reachable in exactly 0, 1, ... `steps` steps.
+fn record_frontier(map: &Grid<Tile>, start: Position, steps: i32) {
+    let mut frontier = HashSet::from([start]);
+    for step in 0..=steps {
+        recorder::record(
+            format!("{} plots after {} steps", frontier.len(), step),
+            draw_plots(map, |position| frontier.contains(&position)),
+        );
+        frontier = frontier
+            .iter()
+            .flat_map(|position| map.neighbors(*position))
+            .filter(|(_, tile)| matches!(tile, Tile::GardenPlot))
+            .map(|(position, _)| position)
+            .collect();
+    }
+}
+
+/// Draws the map with the plots for which `reached` holds marked with `O`.
+fn draw_plots(map: &Grid<Tile>, reached: impl Fn(Position) -> bool) -> Grid<char> {
+    let tiles = map
+        .iter()
+        .map(|(position, tile)| match tile {
+            _ if reached(position) => 'O',
+            Tile::Rock => '#',
+            _ => '.',
+        })
+        .collect();
+    Grid::new(map.width(), map.height(), tiles)
+}
 
-    let height = map.len() as i32;
-    let width = map[0].len() as i32;
+// https://github.com/villuna/aoc23/wiki/A-Geometric-solution-to-advent-of-code-2023,-day-21
+/// Counts the plots after walking across `n` whole maps in every direction from the middle of the
+/// first one.
+fn part_2(start: Position, map: Grid<Tile>, n: usize) -> String {
+    // a plot past half the width from the start is in a corner of the map
+    let corner_distance = map.width() as u64 / 2;
     let shortest_distances = shortest_distances(&map, start);
+    if recorder::recording() {
+        let farthest = shortest_distances
+            .values()
+            .max()
+            .copied()
+            .unwrap_or_default();
+        // on a single map the plots reachable in exactly `steps` steps are the ones that are at
+        // most as far and have the same parity
+        for steps in 0..=farthest {
+            let reached = |position| {
+                shortest_distances
+                    .get(&position)
+                    .is_some_and(|distance| *distance <= steps && distance % 2 == steps % 2)
+            };
+            recorder::record(
+                format!("distance {} of {}", steps, farthest),
+                draw_plots(&map, reached),
+            );
+        }
+    }
 
     // shortest_distances is a HashMap<Coord, usize> which maps tiles in the input-square to their distance from the starting tile
-    // So read this as "even_corners is the number of tiles which have a distance that is even and greater than 65"
+    // So read this as "even_corners is the number of tiles which have a distance that is even and greater than corner_distance"
     let even_corners = shortest_distances
         .values()
-        .filter(|v| **v % 2 == 0 && **v > 65)
+        .filter(|v| **v % 2 == 0 && **v > corner_distance)
         .count();
     let odd_corners = shortest_distances
         .values()
-        .filter(|v| **v % 2 == 1 && **v > 65)
+        .filter(|v| **v % 2 == 1 && **v > corner_distance)
         .count();
 
     let even_full = shortest_distances.values().filter(|v| **v % 2 == 0).count();
     let odd_full = shortest_distances.values().filter(|v| **v % 2 == 1).count();
 
-    // This is 202300 but im writing it out here to show the process
-    let n = ((steps - (width / 2)) / height) as usize;
-    assert_eq!(n, 202300);
-
     let p2 = ((n + 1) * (n + 1)) * odd_full + (n * n) * even_full - (n + 1) * odd_corners
         + n * even_corners;
     p2.to_string()
 }
 
-fn parse_input(input: &str) -> (Position, Vec<Vec<Tile>>) {
-    let mut start = Position { x: 0, y: 0 };
-    let map = input
-        .lines()
-        .enumerate()
-        .map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(x, c)| match c {
-                    '.' => Tile::GardenPlot,
-                    '#' => Tile::Rock,
-                    'S' => {
-                        start = Position {
-                            x: x as i32,
-                            y: y as i32,
-                        };
-                        Tile::GardenPlot
-                    }
-                    _ => panic!("Unexpected character: {}", c),
-                })
-                .collect()
-        })
-        .collect();
-    (start, map)
+fn parse_input(input: &str) -> Result<(Position, Grid<Tile>), ParseError> {
+    let mut map = Grid::parse(input, &TILES)?;
+    let start = map
+        .find(|tile| matches!(tile, Tile::Start))
+        .ok_or_else(|| ParseError::at_offset(input, input.len(), "a start tile 'S'"))?;
+    map[start] = Tile::GardenPlot;
+    Ok((start, map))
+}
+
+/// Parses the map and the number of whole maps walked across in `steps`. Runners check `steps`
+/// against the map first, see [`Solver::check_input_params`], so that a mismatch is reported as
+/// a parameter error; here it can only be an error at the end of the input.
+fn parse_extended(input: &str, steps: i32) -> Result<(Position, Grid<Tile>, usize), ParseError> {
+    let (start, map) = parse_input(input)?;
+    let n = maps_across(&map, steps).map_err(|expected| {
+        ParseError::at_offset(
+            input,
+            input.len(),
+            format!("{} steps to be {}", steps, expected),
+        )
+    })?;
+    Ok((start, map, n))
 }
 
-fn visit(map: &[Vec<Tile>], start: Position, max_depth: i32) -> HashSet<Position> {
+/// The number of whole maps walked across in `steps`, which is 202300 for the puzzle. Fails
+/// with what was expected unless `steps` is half the width of the map plus a multiple of its
+/// height.
+fn maps_across(map: &Grid<Tile>, steps: i32) -> Result<usize, String> {
+    let (width, height) = (map.width() as i32, map.height() as i32);
+    let across = steps - width / 2;
+    if across < 0 || across % height != 0 {
+        return Err(format!(
+            "half the width {} plus a multiple of the height {}",
+            width, height
+        ));
+    }
+    Ok((across / height) as usize)
+}
+
+fn visit(map: &Grid<Tile>, start: Position, max_depth: i32) -> HashSet<Position> {
     let start = (start, 0);
     let mut seen = HashSet::new();
     seen.insert(start);
@@ -74,11 +221,8 @@ fn visit(map: &[Vec<Tile>], start: Position, max_depth: i32) -> HashSet<Position
         if depth >= max_depth {
             continue;
         }
-        for neighbor in position.neighbors() {
-            if let Some(Tile::GardenPlot) = map
-                .get(neighbor.y as usize)
-                .and_then(|row| row.get(neighbor.x as usize))
-            {
+        for (neighbor, tile) in map.neighbors(position) {
+            if let Tile::GardenPlot = tile {
                 let neighbor = (neighbor, depth + 1);
                 if !seen.contains(&neighbor) {
                     seen.insert(neighbor);
@@ -98,26 +242,17 @@ fn visit(map: &[Vec<Tile>], start: Position, max_depth: i32) -> HashSet<Position
         .collect()
 }
 
-fn shortest_distances(map: &[Vec<Tile>], start: Position) -> HashMap<Position, u64> {
-    let height = map.len() as i32;
-    let width = map[0].len() as i32;
+fn shortest_distances(map: &Grid<Tile>, start: Position) -> HashMap<Position, u64> {
     dijkstra_all(&start, |position| {
-        position
-            .neighbors()
-            .into_iter()
-            .filter(|position: &Position| {
-                position.x >= 0 && position.y >= 0 && position.x < width && position.y < height
-            })
-            .filter_map(|neighbor| {
-                if let Some(Tile::GardenPlot) = map
-                    .get(neighbor.y as usize)
-                    .and_then(|row| row.get(neighbor.x as usize))
-                {
+        map.neighbors(*position)
+            .filter_map(|(neighbor, tile)| {
+                if let Tile::GardenPlot = tile {
                     Some((neighbor, 1))
                 } else {
                     None
                 }
             })
+            .collect::<Vec<_>>()
     })
     .into_iter()
     .map(|(position, (_, distance))| (position, distance as u64))
@@ -125,38 +260,11 @@ fn shortest_distances(map: &[Vec<Tile>], start: Position) -> HashMap<Position, u
     .collect()
 }
 
+#[derive(Clone, Copy)]
 enum Tile {
     GardenPlot,
     Rock,
-}
-
-#[derive(Hash, Eq, PartialEq, Clone, Copy, Debug)]
-struct Position {
-    x: i32,
-    y: i32,
-}
-
-impl Position {
-    fn neighbors(&self) -> Vec<Position> {
-        vec![
-            Position {
-                x: self.x - 1,
-                y: self.y,
-            },
-            Position {
-                x: self.x + 1,
-                y: self.y,
-            },
-            Position {
-                x: self.x,
-                y: self.y - 1,
-            },
-            Position {
-                x: self.x,
-                y: self.y + 1,
-            },
-        ]
-    }
+    Start,
 }
 
 #[cfg(test)]
@@ -177,7 +285,38 @@ mod tests {
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT, 6);
+        let result = process_part_1(INPUT, 6).unwrap();
         assert_eq!(result, "16");
     }
+
+    #[test]
+    fn part_2_needs_whole_maps() {
+        for steps in [7, 4, -6] {
+            let expected = format!(
+                "{} steps to be half the width 11 plus a multiple of the height 11",
+                steps
+            );
+            assert_eq!(
+                process_part_2(INPUT, steps),
+                Err(ParseError::new(11, 12, &expected))
+            );
+        }
+        assert!(process_part_2(INPUT, 16).is_ok());
+    }
+
+    #[test]
+    fn extended_steps_are_checked_against_the_map() {
+        params::set_overrides(vec![("extended_steps".to_string(), "7".to_string())]);
+        assert_eq!(
+            Day21.check_input_params(Part::Two, INPUT),
+            Err(
+                "Invalid value 7 of extended_steps: expected half the width 11 plus a multiple \
+                 of the height 11"
+                    .to_string()
+            )
+        );
+        assert_eq!(Day21.check_input_params(Part::One, INPUT), Ok(()));
+        params::set_overrides(vec![("extended_steps".to_string(), "16".to_string())]);
+        assert_eq!(Day21.check_input_params(Part::Two, INPUT), Ok(()));
+    }
 }
@@ -0,0 +1,11 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_22::Day22;
+use solver::Part;
+
+fn bench(c: &mut Criterion) {
+    // part 2 takes over ten minutes per run
+    bench::bench_day(c, &Day22, &[Part::One]);
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
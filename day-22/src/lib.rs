@@ -2,15 +2,39 @@ use std::collections::{HashMap, HashSet};
 
 use indicatif::ParallelProgressIterator;
 use nom::character::complete::{self, line_ending};
+use nom::multi::separated_list1;
 use nom::sequence::separated_pair;
-use nom::{bytes::complete::tag, multi::separated_list1, IResult};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use solver::parse::{parse_all, tag, IResult};
+use solver::{recorder, ParseError, Part, Run, Solver};
 use uuid::Uuid;
 
-pub fn process_part_1(input: &str) -> String {
-    let (input, bricks) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
-    let settled = settle(&bricks);
+pub struct Day22;
+
+impl Solver for Day22 {
+    fn day(&self) -> u8 {
+        22
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || parse_all(input, parse_input);
+        match part {
+            Part::One => Run::timed(parse, part_1),
+            Part::Two => Run::timed(parse, part_2),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day22.solve(Part::One, input)
+}
+
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day22.solve(Part::Two, input)
+}
+
+fn part_1(bricks: Vec<Brick>) -> String {
+    let settled = settle(&bricks, recorder::recording());
     settled
         .par_iter()
         .progress_count(settled.len() as u64)
@@ -28,10 +52,8 @@ pub fn process_part_1(input: &str) -> String {
         .to_string()
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let (input, bricks) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
-    let settled = settle(&bricks);
+fn part_2(bricks: Vec<Brick>) -> String {
+    let settled = settle(&bricks, recorder::recording());
     settled
         .par_iter()
         .map(|brick_to_remove| {
@@ -40,7 +62,7 @@ pub fn process_part_2(input: &str) -> String {
                 .filter(|other_brick| *other_brick != brick_to_remove)
                 .copied()
                 .collect::<Vec<_>>();
-            let removed_settled = settle(&removed)
+            let removed_settled = settle(&removed, false)
                 .into_iter()
                 .map(|brick| (brick.id, brick))
                 .collect::<HashMap<_, _>>();
@@ -58,10 +80,17 @@ pub fn process_part_2(input: &str) -> String {
         .to_string()
 }
 
-fn settle(bricks: &[Brick]) -> Vec<Brick> {
+/// Lets the bricks fall a tile at a time until all of them are supported, recording a frame
+/// per tile if `record` is set.
+fn settle(bricks: &[Brick], record: bool) -> Vec<Brick> {
     let mut bricks = bricks.to_owned();
     let mut moved = true;
+    let mut fallen = 0;
     while moved {
+        if record {
+            recorder::record(format!("fallen {} tiles", fallen), draw_side_views(&bricks));
+        }
+        fallen += 1;
         moved = false;
         let not_supported_brick_ids = bricks
             .iter()
@@ -85,6 +114,52 @@ fn settle(bricks: &[Brick]) -> Vec<Brick> {
     bricks
 }
 
+/// Draws the bricks seen from the front and from the side like in the puzzle description, with
+/// the x axis on the left and the y axis on the right. Bricks are lettered in input order and a
+/// tile where different bricks are lined up shows `?`.
+fn draw_side_views(bricks: &[Brick]) -> String {
+    const SEVERAL: usize = usize::MAX;
+    let size = |axis: fn(&Position) -> i32| {
+        bricks
+            .iter()
+            .map(|brick| axis(&brick.start).max(axis(&brick.end)) as usize + 1)
+            .max()
+            .unwrap_or_default()
+    };
+    let (width, depth, height) = (size(|p| p.x), size(|p| p.y), size(|p| p.z));
+    let mut front = vec![vec![None; width]; height];
+    let mut side = vec![vec![None; depth]; height];
+    for (i, brick) in bricks.iter().enumerate() {
+        for cube in brick.cubes() {
+            let z = cube.z as usize;
+            for tile in [
+                &mut front[z][cube.x as usize],
+                &mut side[z][cube.y as usize],
+            ] {
+                *tile = match tile {
+                    Some(other) if *other != i => Some(SEVERAL),
+                    _ => Some(i),
+                };
+            }
+        }
+    }
+    let row = |tiles: &[Option<usize>]| {
+        tiles
+            .iter()
+            .map(|tile| match tile {
+                None => '.',
+                Some(SEVERAL) => '?',
+                Some(i) => (b'A' + (i % 26) as u8) as char,
+            })
+            .collect::<String>()
+    };
+    let mut output = String::new();
+    for z in (1..height).rev() {
+        output += &format!("{}   {} {}\n", row(&front[z]), row(&side[z]), z);
+    }
+    output + &format!("{}   {} 0", "-".repeat(width), "-".repeat(depth))
+}
+
 fn parse_input(input: &str) -> IResult<&str, Vec<Brick>> {
     separated_list1(line_ending, parse_brick)(input)
 }
@@ -181,6 +256,7 @@ impl Position {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     const INPUT: &str = "1,0,1~1,2,1
 0,0,2~2,0,2
@@ -192,13 +268,83 @@ mod tests {
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "5");
     }
 
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "7");
     }
+
+    /// A snapshot of up to 8 bricks in a 4x4 column. Each brick gets its own layers, so that
+    /// no two bricks overlap, like in the real input.
+    fn snapshot() -> impl Strategy<Value = String> {
+        prop::collection::vec((0..4i32, 0..4i32, 0..3usize, 0..4i32), 1..=8).prop_map(|bricks| {
+            let mut z = 1;
+            bricks
+                .into_iter()
+                .map(|(x, y, axis, length)| {
+                    let start = [x, y, z];
+                    let mut end = start;
+                    end[axis] = if axis == 2 {
+                        z + length
+                    } else {
+                        (start[axis] + length).min(3)
+                    };
+                    z = end[2] + 1 + length;
+                    format!(
+                        "{},{},{}~{},{},{}",
+                        start[0], start[1], start[2], end[0], end[1], end[2]
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+
+    #[test]
+    fn side_views_are_drawn() {
+        let bricks = parse_all(INPUT, parse_input).unwrap();
+        assert_eq!(
+            draw_side_views(&bricks),
+            ".G.   .G. 9
+.G.   .G. 8
+...   ... 7
+FFF   .F. 6
+..E   EEE 5
+D..   DDD 4
+CCC   ..C 3
+BBB   B.. 2
+.A.   AAA 1
+---   --- 0"
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn settle_is_idempotent(snapshot in snapshot()) {
+            let bricks = parse_all(&snapshot, parse_input).unwrap();
+            let settled = settle(&bricks, false);
+            prop_assert_eq!(settle(&settled, false), settled.clone());
+            prop_assert!(settled.iter().all(|brick| brick.is_supported(&settled)));
+            let cubes = settled.iter().flat_map(Brick::cubes).collect::<Vec<_>>();
+            let unique_cubes = cubes
+                .iter()
+                .map(|cube| (cube.x, cube.y, cube.z))
+                .collect::<HashSet<_>>();
+            prop_assert_eq!(unique_cubes.len(), cubes.len());
+        }
+
+        #[test]
+        fn disintegrated_bricks_do_not_make_others_fall(snapshot in snapshot()) {
+            let bricks = parse_all(&snapshot, parse_input).unwrap();
+            let safe = process_part_1(&snapshot).unwrap().parse::<usize>().unwrap();
+            let falling = process_part_2(&snapshot).unwrap().parse::<usize>().unwrap();
+            prop_assert!(safe <= bricks.len());
+            // every brick that is not safe to disintegrate makes at least one other brick fall
+            prop_assert!(falling >= bricks.len() - safe);
+        }
+    }
 }
@@ -1,42 +1,75 @@
 use std::{
-    collections::HashSet,
-    fmt::Display,
-    fs::{self},
+    collections::{BTreeSet, HashSet},
+    io::Write,
 };
 
+use grid::{Direction, Grid, Position};
 use itertools::Itertools;
 use petgraph::{algo::all_simple_paths, dot::Dot, Graph};
+use solver::{ExportError, ParseError, Part, Run, Solver};
 
-pub fn process_part_1(input: &str) -> String {
-    let map = parse_input(input);
-    let start = start_position(&map);
-    let end = end_position(&map);
-    let longest_path = visit(&map, start, end);
-    (longest_path.len() - 1).to_string()
+const TILES: [(char, Tile); 6] = [
+    ('#', Tile::Forest),
+    ('.', Tile::Path),
+    (
+        '>',
+        Tile::Slope {
+            direction: Direction::East,
+        },
+    ),
+    (
+        '<',
+        Tile::Slope {
+            direction: Direction::West,
+        },
+    ),
+    (
+        '^',
+        Tile::Slope {
+            direction: Direction::North,
+        },
+    ),
+    (
+        'v',
+        Tile::Slope {
+            direction: Direction::South,
+        },
+    ),
+];
+
+pub struct Day23;
+
+impl Solver for Day23 {
+    fn day(&self) -> u8 {
+        23
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || parse_input(input);
+        match part {
+            Part::One => Run::timed(parse, part_1),
+            Part::Two => Run::timed(parse, part_2),
+        }
+    }
 }
 
-pub fn process_part_2(input: &str) -> String {
-    let map = parse_input(input);
-    let map = map
-        .into_iter()
-        .map(|row| {
-            row.into_iter()
-                .map(|tile| match tile {
-                    Tile::Slope { direction: _ } => Tile::Path,
-                    _ => tile,
-                })
-                .collect()
-        })
-        .collect::<Vec<Vec<Tile>>>();
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day23.solve(Part::One, input)
+}
 
+pub fn process_part_2(input: &str) -> Result<String, ParseError> {
+    Day23.solve(Part::Two, input)
+}
+
+fn part_1(map: Grid<Tile>) -> String {
     let start = start_position(&map);
     let end = end_position(&map);
-    let reduced = reduce(&map, start, end);
-
-    let graph = make_graph(&reduced);
-    let dot = Dot::new(&graph);
-    fs::write("./graph.dot", format!("{}", dot)).unwrap();
+    let longest_path = visit(&map, start, end);
+    (longest_path.len() - 1).to_string()
+}
 
+fn part_2(map: Grid<Tile>) -> String {
+    let (graph, start, end) = junction_graph(map);
     let start_node = graph.node_indices().find(|i| graph[*i] == start).unwrap();
     let end_node = graph.node_indices().find(|i| graph[*i] == end).unwrap();
     all_simple_paths::<Vec<_>, _>(&graph, start_node, end_node, 0, None)
@@ -55,35 +88,48 @@ pub fn process_part_2(input: &str) -> String {
         .to_string()
 }
 
-fn parse_input(input: &str) -> Vec<Vec<Tile>> {
-    input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| match c {
-                    '#' => Tile::Forest,
-                    '.' => Tile::Path,
-                    '>' => Tile::Slope {
-                        direction: Direction::East,
-                    },
-                    '<' => Tile::Slope {
-                        direction: Direction::West,
-                    },
-                    '^' => Tile::Slope {
-                        direction: Direction::North,
-                    },
-                    'v' => Tile::Slope {
-                        direction: Direction::South,
-                    },
-                    _ => panic!("Invalid character in input: {}", c),
-                })
-                .collect()
-        })
-        .collect()
+/// Writes the graph of junctions searched in part 2 as Graphviz, edges are labelled with the
+/// length of the path between two junctions. The start and the end are highlighted.
+pub fn export_dot(input: &str, writer: &mut dyn Write) -> Result<(), ExportError> {
+    let (graph, start, end) = junction_graph(parse_input(input)?);
+    let node_attributes = |_, (_, position): (_, &Position)| {
+        if *position == start || *position == end {
+            "shape=doublecircle, style=filled, fillcolor=lightblue".to_string()
+        } else {
+            String::new()
+        }
+    };
+    let dot = Dot::with_attr_getters(&graph, &[], &|_, _| String::new(), &node_attributes);
+    writeln!(writer, "{}", dot)?;
+    Ok(())
 }
 
-fn start_position(map: &[Vec<Tile>]) -> Position {
-    map[0]
+/// Graph of the junctions of the map with the slopes ignored, together with the start and end
+/// positions.
+fn junction_graph(
+    map: Grid<Tile>,
+) -> (
+    Graph<Position, i32, petgraph::prelude::Undirected>,
+    Position,
+    Position,
+) {
+    let map = map.map(|tile| match tile {
+        Tile::Slope { direction: _ } => Tile::Path,
+        _ => *tile,
+    });
+
+    let start = start_position(&map);
+    let end = end_position(&map);
+    let reduced = reduce(&map, start, end);
+    (make_graph(&reduced), start, end)
+}
+
+fn parse_input(input: &str) -> Result<Grid<Tile>, ParseError> {
+    Grid::parse(input, &TILES)
+}
+
+fn start_position(map: &Grid<Tile>) -> Position {
+    map.row(0)
         .iter()
         .enumerate()
         .find_map(|(x, tile)| {
@@ -96,15 +142,15 @@ fn start_position(map: &[Vec<Tile>]) -> Position {
         .unwrap()
 }
 
-fn end_position(map: &[Vec<Tile>]) -> Position {
-    map[map.len() - 1]
+fn end_position(map: &Grid<Tile>) -> Position {
+    map.row(map.height() - 1)
         .iter()
         .enumerate()
         .find_map(|(x, tile)| {
             if matches!(tile, Tile::Path) {
                 Some(Position {
                     x: x as i32,
-                    y: (map.len() - 1) as i32,
+                    y: (map.height() - 1) as i32,
                 })
             } else {
                 None
@@ -113,7 +159,7 @@ fn end_position(map: &[Vec<Tile>]) -> Position {
         .unwrap()
 }
 
-fn visit(map: &[Vec<Tile>], start_position: Position, end_position: Position) -> HashSet<Position> {
+fn visit(map: &Grid<Tile>, start_position: Position, end_position: Position) -> HashSet<Position> {
     let mut max_path = HashSet::new();
     let mut start_path = HashSet::new();
     start_path.insert(start_position);
@@ -121,7 +167,7 @@ fn visit(map: &[Vec<Tile>], start_position: Position, end_position: Position) ->
     let mut to_see = vec![start];
     while let Some(next) = to_see.pop() {
         let (position, path) = next;
-        let next_steps = position.next_steps(map);
+        let next_steps = next_steps(&position, map);
         if position == end_position {
             if path.len() > max_path.len() {
                 // println!("New longest path: {}", path.len() - 1);
@@ -142,7 +188,7 @@ fn visit(map: &[Vec<Tile>], start_position: Position, end_position: Position) ->
 }
 
 fn reduce(
-    map: &[Vec<Tile>],
+    map: &Grid<Tile>,
     start_position: Position,
     end_position: Position,
 ) -> Vec<(Position, Position, i32)> {
@@ -154,8 +200,7 @@ fn reduce(
     start_path.insert(start_position);
     let mut to_see = vec![(start_position, start_path, start_position, 0)];
     while let Some((next, path, prev_intersection, length)) = to_see.pop() {
-        let next_steps = next
-            .next_steps(map)
+        let next_steps = next_steps(&next, map)
             .into_iter()
             .filter(|step| !path.contains(step))
             .collect::<Vec<_>>();
@@ -193,7 +238,7 @@ fn make_graph(
     let nodes = reduced
         .iter()
         .flat_map(|(from, to, _)| vec![from, to])
-        .collect::<HashSet<_>>();
+        .collect::<BTreeSet<_>>();
     nodes.iter().for_each(|node| {
         graph.add_node(**node);
     });
@@ -205,93 +250,32 @@ fn make_graph(
     graph
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 enum Tile {
     Path,
     Forest,
     Slope { direction: Direction },
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
-#[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
-struct Position {
-    x: i32,
-    y: i32,
-}
-
-impl Position {
-    fn next_steps(&self, map: &[Vec<Tile>]) -> Vec<Position> {
-        [
-            (
-                Position {
-                    x: self.x + 1,
-                    y: self.y,
-                },
-                Direction::East,
-            ),
-            (
-                Position {
-                    x: self.x - 1,
-                    y: self.y,
-                },
-                Direction::West,
-            ),
-            (
-                Position {
-                    x: self.x,
-                    y: self.y + 1,
-                },
-                Direction::South,
-            ),
-            (
-                Position {
-                    x: self.x,
-                    y: self.y - 1,
-                },
-                Direction::North,
-            ),
-        ]
-        .iter()
-        .filter_map(|(position, direction)| {
-            if position.x < 0 || position.y < 0 {
-                None
-            } else {
-                match map.get(position.y as usize).and_then(|row| {
-                    if position.x as usize >= row.len() {
-                        None
-                    } else {
-                        row.get(position.x as usize)
-                    }
-                }) {
-                    Some(Tile::Path) => Some(*position),
-                    Some(Tile::Slope {
-                        direction: slope_direction,
-                    }) => {
-                        if slope_direction == direction {
-                            Some(*position)
-                        } else {
-                            None
-                        }
-                    }
-                    _ => None,
-                }
-            }
-        })
-        .collect()
-    }
-}
-
-impl Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({},{})", self.x, self.y)
-    }
+fn next_steps(position: &Position, map: &Grid<Tile>) -> Vec<Position> {
+    [
+        Direction::East,
+        Direction::West,
+        Direction::South,
+        Direction::North,
+    ]
+    .into_iter()
+    .filter_map(|direction| {
+        let next_position = position.move_in_direction(direction);
+        match map.get(next_position)? {
+            Tile::Path => Some(next_position),
+            Tile::Slope {
+                direction: slope_direction,
+            } if *slope_direction == direction => Some(next_position),
+            _ => None,
+        }
+    })
+    .collect()
 }
 
 #[cfg(test)]
@@ -324,13 +308,27 @@ mod tests {
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "94");
     }
 
     #[test]
     fn part_2_works() {
-        let result = process_part_2(INPUT);
+        let result = process_part_2(INPUT).unwrap();
         assert_eq!(result, "154");
     }
+
+    #[test]
+    fn export_dot_works() {
+        let mut dot = vec![];
+        export_dot(INPUT, &mut dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        assert!(dot.starts_with("graph {"));
+        assert!(dot.contains(
+            r#"[ label = "(1,0)" shape=doublecircle, style=filled, fillcolor=lightblue]"#
+        ));
+        assert!(dot.contains(
+            r#"[ label = "(21,22)" shape=doublecircle, style=filled, fillcolor=lightblue]"#
+        ));
+    }
 }
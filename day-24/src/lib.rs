@@ -3,18 +3,42 @@ use indicatif::ProgressIterator;
 use ndarray::prelude::*;
 use ndarray_linalg::Solve;
 use nom::{
-    bytes::complete::tag,
     character::complete::{self, line_ending, space0, space1},
     multi::separated_list1,
     sequence::{delimited, separated_pair},
-    IResult,
 };
+use solver::parse::{parse_all, tag, Expected, IResult, NomError};
+use solver::{ParseError, Part, Run, Solver};
 
 const MAX_OFFSET: f64 = 0.04;
+// The limits keep the cross products of part 2 within an i64, real inputs stay below them.
+const MAX_POSITION: i64 = 1 << 50;
+const MAX_VELOCITY: i64 = 1 << 10;
 
-pub fn process_part_1(input: &str, area_min: i64, area_max: i64) -> String {
-    let (input, hailstones) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
+pub struct Day24;
+
+impl Solver for Day24 {
+    fn day(&self) -> u8 {
+        24
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        let parse = || parse_all(input, parse_input);
+        match part {
+            Part::One => Run::timed(parse, |hailstones| {
+                part_1(&hailstones, 200000000000000, 400000000000000)
+            }),
+            Part::Two => Run::timed(parse, |hailstones| part_2_linalg(&hailstones)),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str, area_min: i64, area_max: i64) -> Result<String, ParseError> {
+    let hailstones = parse_all(input, parse_input)?;
+    Ok(part_1(&hailstones, area_min, area_max))
+}
+
+fn part_1(hailstones: &[HailStone], area_min: i64, area_max: i64) -> String {
     let intersections = hailstones
         .iter()
         .enumerate()
@@ -44,22 +68,27 @@ pub fn process_part_1(input: &str, area_min: i64, area_max: i64) -> String {
 }
 
 // https://aoc.csokavar.hu/?day=24
-pub fn process_part_2_brute_force(input: &str, stone_velocity_range: i64) -> String {
-    let (input, hailstones) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
+pub fn process_part_2_brute_force(
+    input: &str,
+    stone_velocity_range: i64,
+) -> Result<String, ParseError> {
+    let hailstones = parse_all(input, parse_input)?;
     let stone = stone_position_brute_force(hailstones, stone_velocity_range);
     if let Some(stone) = stone {
-        (stone.x.round() as i64 + stone.y.round() as i64 + stone.z.round() as i64).to_string()
+        Ok((stone.x.round() as i64 + stone.y.round() as i64 + stone.z.round() as i64).to_string())
     } else {
-        "No solution found".to_string()
+        Ok("No solution found".to_string())
     }
 }
 
 // https://www.reddit.com/r/adventofcode/comments/18pnycy/comment/kepu26z/
-pub fn process_part_2_linalg(input: &str) -> String {
-    let (input, hailstones) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
-    let stone = stone_position_linalg(&hailstones);
+pub fn process_part_2_linalg(input: &str) -> Result<String, ParseError> {
+    let hailstones = parse_all(input, parse_input)?;
+    Ok(part_2_linalg(&hailstones))
+}
+
+fn part_2_linalg(hailstones: &[HailStone]) -> String {
+    let stone = stone_position_linalg(hailstones);
     if let Some(stone) = stone {
         (stone.x.round() as i64 + stone.y.round() as i64 + stone.z.round() as i64).to_string()
     } else {
@@ -146,18 +175,43 @@ fn parse_input(input: &str) -> IResult<&str, Vec<HailStone>> {
 }
 
 fn parse_hailstone(input: &str) -> IResult<&str, HailStone> {
-    let (input, (position, velocity)) =
-        separated_pair(parse_vec, delimited(space1, tag("@"), space1), parse_vec)(input)?;
+    let (input, (position, velocity)) = separated_pair(
+        parse_vec(MAX_POSITION, "a position between -2^50 and 2^50"),
+        delimited(space1, tag("@"), space1),
+        parse_vec(MAX_VELOCITY, "a velocity between -1024 and 1024"),
+    )(input)?;
     Ok((input, HailStone { position, velocity }))
 }
 
-fn parse_vec(input: &str) -> IResult<&str, I64Vec3> {
-    let (input, x) = complete::i64(input)?;
-    let (input, _) = delimited(space0, tag(","), space0)(input)?;
-    let (input, y) = complete::i64(input)?;
-    let (input, _) = delimited(space0, tag(","), space0)(input)?;
-    let (input, z) = complete::i64(input)?;
-    Ok((input, I64Vec3::new(x, y, z)))
+fn parse_vec<'a>(
+    max: i64,
+    expected: &'static str,
+) -> impl Fn(&'a str) -> IResult<&'a str, I64Vec3> {
+    move |input: &'a str| {
+        let coordinate = parse_coordinate(max, expected);
+        let (input, x) = coordinate(input)?;
+        let (input, _) = delimited(space0, tag(","), space0)(input)?;
+        let (input, y) = coordinate(input)?;
+        let (input, _) = delimited(space0, tag(","), space0)(input)?;
+        let (input, z) = coordinate(input)?;
+        Ok((input, I64Vec3::new(x, y, z)))
+    }
+}
+
+fn parse_coordinate<'a>(
+    max: i64,
+    expected: &'static str,
+) -> impl Fn(&'a str) -> IResult<&'a str, i64> {
+    move |input: &'a str| {
+        let (rest, value) = complete::i64(input)?;
+        if value.unsigned_abs() > max as u64 {
+            return Err(nom::Err::Failure(NomError::new(
+                input,
+                Expected::Description(expected),
+            )));
+        }
+        Ok((rest, value))
+    }
 }
 
 // #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
@@ -279,19 +333,37 @@ mod tests {
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT, 7, 27);
+        let result = process_part_1(INPUT, 7, 27).unwrap();
         assert_eq!(result, "2");
     }
 
     #[test]
     fn part_2_works_brute_force_works() {
-        let result = process_part_2_brute_force(INPUT, 10);
+        let result = process_part_2_brute_force(INPUT, 10).unwrap();
         assert_eq!(result, "47");
     }
 
+    #[test]
+    fn out_of_range_coordinates_are_reported() {
+        let result = process_part_1(
+            "19, 13, 30 @ -2, 1, -2\n18, 19, 1125899906842625 @ -1, -1, -2",
+            7,
+            27,
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "expected a position between -2^50 and 2^50 on line 2, column 9"
+        );
+        let result = process_part_1("19, 13, 30 @ -2, 1025, -2", 7, 27);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "expected a velocity between -1024 and 1024 on line 1, column 18"
+        );
+    }
+
     #[test]
     fn part_2_works_linalg() {
-        let result = process_part_2_linalg(INPUT);
+        let result = process_part_2_linalg(INPUT).unwrap();
         assert_eq!(result, "47");
     }
 
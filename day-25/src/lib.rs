@@ -1,24 +1,41 @@
-use std::{
-    collections::HashSet,
-    fs::{self},
-};
+use std::{collections::BTreeSet, io::Write};
 
 use nom::{
-    bytes::complete::tag,
     character::complete::{alpha1, line_ending, space1},
     multi::separated_list1,
     sequence::separated_pair,
-    IResult,
 };
-use petgraph::{dot::Dot, Graph};
+use petgraph::{
+    dot::Dot,
+    graph::{EdgeReference, NodeIndex},
+    visit::EdgeRef,
+    Graph,
+};
 use rustworkx_core::connectivity::stoer_wagner_min_cut;
+use solver::parse::{parse_all, tag, IResult};
+use solver::{ExportError, ParseError, Part, Run, Solver};
+
+pub struct Day25;
+
+impl Solver for Day25 {
+    fn day(&self) -> u8 {
+        25
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+        match part {
+            Part::One => Run::timed(|| parse_all(input, parse_input), part_1),
+            Part::Two => Run::timed(|| Ok(()), |_| "Day 25 has no second puzzle".to_string()),
+        }
+    }
+}
+
+pub fn process_part_1(input: &str) -> Result<String, ParseError> {
+    Day25.solve(Part::One, input)
+}
 
-pub fn process_part_1(input: &str) -> String {
-    let (input, lines) = parse_input(input).unwrap();
-    debug_assert_eq!(input, "");
+fn part_1(lines: Vec<(&str, Vec<&str>)>) -> String {
     let graph = make_graph(&lines);
-    let dot = Dot::new(&graph);
-    fs::write("./graph.dot", format!("{}", dot)).unwrap();
     let min_cut_res: rustworkx_core::Result<Option<(usize, Vec<_>)>> =
         stoer_wagner_min_cut(&graph, |_| Ok(1));
     if let Ok(Some((_size, cut))) = min_cut_res {
@@ -28,6 +45,36 @@ pub fn process_part_1(input: &str) -> String {
     }
 }
 
+/// Writes the wiring diagram as Graphviz. The two groups left after cutting the minimum cut are
+/// filled with different colours and the wires to cut are drawn in red.
+pub fn export_dot(input: &str, writer: &mut dyn Write) -> Result<(), ExportError> {
+    let lines = parse_all(input, parse_input)?;
+    let graph = make_graph(&lines);
+    let min_cut_res: rustworkx_core::Result<Option<(usize, Vec<_>)>> =
+        stoer_wagner_min_cut(&graph, |_| Ok(1));
+    let cut = match min_cut_res {
+        Ok(Some((_size, cut))) => cut.into_iter().collect(),
+        _ => BTreeSet::new(),
+    };
+    let edge_attributes = |_, edge: EdgeReference<'_, &str>| {
+        if cut.contains(&edge.source()) != cut.contains(&edge.target()) {
+            "color=red, penwidth=3".to_string()
+        } else {
+            String::new()
+        }
+    };
+    let node_attributes = |_, (node, _): (NodeIndex, &&str)| {
+        if cut.contains(&node) {
+            "style=filled, fillcolor=lightblue".to_string()
+        } else {
+            "style=filled, fillcolor=palegreen".to_string()
+        }
+    };
+    let dot = Dot::with_attr_getters(&graph, &[], &edge_attributes, &node_attributes);
+    writeln!(writer, "{}", dot)?;
+    Ok(())
+}
+
 fn parse_input(input: &str) -> IResult<&str, Vec<(&str, Vec<&str>)>> {
     separated_list1(line_ending, parse_line)(input)
 }
@@ -39,7 +86,7 @@ fn parse_line(input: &str) -> IResult<&str, (&str, Vec<&str>)> {
 fn make_graph<'a>(
     lines: &'a [(&'a str, Vec<&'a str>)],
 ) -> Graph<&'a str, &'a str, petgraph::prelude::Undirected> {
-    let mut nodes = HashSet::new();
+    let mut nodes = BTreeSet::new();
     let mut edges = vec![];
     lines.iter().for_each(|(node, node_edges)| {
         nodes.insert(*node);
@@ -80,7 +127,21 @@ frs: qnr lhk lsr";
 
     #[test]
     fn part_1_works() {
-        let result = process_part_1(INPUT);
+        let result = process_part_1(INPUT).unwrap();
         assert_eq!(result, "54");
     }
+
+    #[test]
+    fn export_dot_works() {
+        let mut dot = vec![];
+        export_dot(INPUT, &mut dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        assert_eq!(dot.matches("color=red").count(), 3);
+        let mut groups = [
+            dot.matches("fillcolor=lightblue").count(),
+            dot.matches("fillcolor=palegreen").count(),
+        ];
+        groups.sort();
+        assert_eq!(groups, [6, 9]);
+    }
 }
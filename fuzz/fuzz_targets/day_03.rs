@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use solver::{Part, Solver};
+
+fuzz_target!(|input: &str| {
+    for part in Part::ALL {
+        let _ = day_03::Day03.parse(part, input);
+    }
+});
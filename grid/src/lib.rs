@@ -0,0 +1,365 @@
+use std::{
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+};
+
+use solver::ParseError;
+
+mod position;
+
+pub use position::{Direction, Position};
+
+/// Rectangular 2D grid stored row by row in a single `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Panics if `cells` doesn't hold exactly `width * height` items.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "a {}x{} grid needs {} cells",
+            width,
+            height,
+            width * height
+        );
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(width, height, vec![value; width * height])
+    }
+
+    /// Returns `None` if the rows are not all of the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first().map(Vec::len).unwrap_or_default();
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        let height = rows.len();
+        Some(Self::new(
+            width,
+            height,
+            rows.into_iter().flatten().collect(),
+        ))
+    }
+
+    /// Parses a map with one character per tile, `tiles` tells which character stands for
+    /// which tile.
+    pub fn parse(input: &str, tiles: &[(char, T)]) -> Result<Self, ParseError>
+    where
+        T: Clone,
+    {
+        let mut expected = tiles
+            .iter()
+            .map(|(c, _)| format!("'{}'", c.escape_debug()))
+            .collect::<Vec<_>>();
+        let last = expected.pop().unwrap_or_default();
+        let expected = if expected.is_empty() {
+            last
+        } else {
+            format!("{} or {}", expected.join(", "), last)
+        };
+        Self::parse_with(input, &expected, |c| {
+            tiles
+                .iter()
+                .find(|(tile_char, _)| *tile_char == c)
+                .map(|(_, tile)| tile.clone())
+        })
+    }
+
+    /// Parses a map with one character per tile, `tile` returns `None` for characters that
+    /// are not valid tiles, which is reported as `expected`.
+    pub fn parse_with(
+        input: &str,
+        expected: &str,
+        tile: impl Fn(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+        for (y, line) in input.trim_end_matches(['\r', '\n']).lines().enumerate() {
+            let mut x = 0;
+            for c in line.chars() {
+                if width == Some(x) {
+                    return Err(ParseError::new(y + 1, x + 1, "a line break"));
+                }
+                cells.push(tile(c).ok_or_else(|| ParseError::new(y + 1, x + 1, expected))?);
+                x += 1;
+            }
+            if x == 0 || width.is_some_and(|width| width != x) {
+                return Err(ParseError::new(y + 1, x + 1, expected));
+            }
+            width = Some(x);
+            height += 1;
+        }
+        match width {
+            Some(width) => Ok(Self::new(width, height, cells)),
+            None => Err(ParseError::new(1, 1, expected)),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        position.x >= 0
+            && position.y >= 0
+            && (position.x as usize) < self.width
+            && (position.y as usize) < self.height
+    }
+
+    fn index_of(&self, position: Position) -> Option<usize> {
+        self.contains(position)
+            .then(|| position.y as usize * self.width + position.x as usize)
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.index_of(position).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.index_of(position).map(|index| &mut self.cells[index])
+    }
+
+    /// Swaps the tiles at `a` and `b`, panics if any of them is outside the grid.
+    pub fn swap(&mut self, a: Position, b: Position) {
+        let a = self.index_of(a).expect("position outside of the grid");
+        let b = self.index_of(b).expect("position outside of the grid");
+        self.cells.swap(a, b);
+    }
+
+    /// All positions of the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height)
+            .flat_map(move |y| (0..width).map(move |x| Position::new(x as i32, y as i32)))
+    }
+
+    /// All tiles with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Position of the first tile, row by row, that matches `predicate`.
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<Position> {
+        self.iter()
+            .find(|(_, tile)| predicate(tile))
+            .map(|(position, _)| position)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on 0, an empty grid has no rows anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} outside of the grid", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// In-bounds orthogonal neighbours of `position`, in the order of [`Direction::ALL`].
+    pub fn neighbors(&self, position: Position) -> impl Iterator<Item = (Position, &T)> {
+        position
+            .neighbors()
+            .into_iter()
+            .filter_map(|neighbor| self.get(neighbor).map(|tile| (neighbor, tile)))
+    }
+
+    /// In-bounds neighbours of `position` including diagonals.
+    pub fn neighbors_8(&self, position: Position) -> impl Iterator<Item = (Position, &T)> {
+        position
+            .neighbors_8()
+            .into_iter()
+            .filter_map(|neighbor| self.get(neighbor).map(|tile| (neighbor, tile)))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+
+    /// Mirrors the grid along its main diagonal, rows become columns.
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::new(
+            self.height,
+            self.width,
+            self.columns().flat_map(|column| column.cloned()).collect(),
+        )
+    }
+
+    pub fn rotate_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::new(
+            self.height,
+            self.width,
+            (0..self.width)
+                .flat_map(|x| (0..self.height).rev().map(move |y| (x, y)))
+                .map(|(x, y)| self.cells[y * self.width + x].clone())
+                .collect(),
+        )
+    }
+
+    pub fn rotate_counter_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::new(
+            self.height,
+            self.width,
+            (0..self.width)
+                .rev()
+                .flat_map(|x| (0..self.height).map(move |y| (x, y)))
+                .map(|(x, y)| self.cells[y * self.width + x].clone())
+                .collect(),
+        )
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &Self::Output {
+        self.get(position)
+            .unwrap_or_else(|| panic!("position {} outside of the grid", position))
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut Self::Output {
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("position {} outside of the grid", position))
+    }
+}
+
+/// Prints the tiles row by row, without a trailing line break.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for tile in row {
+                write!(f, "{}", tile)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "#..
+.#S";
+
+    fn grid() -> Grid<char> {
+        Grid::parse(INPUT, &[('#', '#'), ('.', '.'), ('S', 'S')]).unwrap()
+    }
+
+    #[test]
+    fn parse_works() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Position::new(2, 1)], 'S');
+        assert_eq!(grid.find(|c| *c == 'S'), Some(Position::new(2, 1)));
+        assert_eq!(grid.to_string(), INPUT);
+    }
+
+    #[test]
+    fn parse_errors_are_located() {
+        let tiles = [('#', true), ('.', false)];
+        assert_eq!(
+            Grid::parse("#.\n#x", &tiles),
+            Err(ParseError::new(2, 2, "'#' or '.'"))
+        );
+        assert_eq!(
+            Grid::parse("#.\n#.#", &tiles),
+            Err(ParseError::new(2, 3, "a line break"))
+        );
+        assert_eq!(
+            Grid::parse("#.\n#\n", &tiles),
+            Err(ParseError::new(2, 2, "'#' or '.'"))
+        );
+        assert_eq!(
+            Grid::parse("", &tiles),
+            Err(ParseError::new(1, 1, "'#' or '.'"))
+        );
+    }
+
+    #[test]
+    fn rows_and_columns_work() {
+        let grid = grid();
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&['#', '.', '.'], &['.', '#', 'S']]
+        );
+        assert_eq!(
+            grid.columns()
+                .map(|column| column.collect::<String>())
+                .collect::<Vec<_>>(),
+            vec!["#.", ".#", ".S"]
+        );
+    }
+
+    #[test]
+    fn transpose_and_rotation_work() {
+        let grid = grid();
+        assert_eq!(grid.transpose().to_string(), "#.\n.#\n.S");
+        assert_eq!(grid.rotate_clockwise().to_string(), ".#\n#.\nS.");
+        assert_eq!(grid.rotate_counter_clockwise().to_string(), ".S\n.#\n#.");
+        assert_eq!(
+            grid.rotate_clockwise()
+                .rotate_clockwise()
+                .rotate_counter_clockwise()
+                .rotate_counter_clockwise(),
+            grid
+        );
+    }
+
+    #[test]
+    fn neighbors_are_bounds_checked() {
+        let grid = grid();
+        assert_eq!(
+            grid.neighbors(Position::new(0, 0)).collect::<Vec<_>>(),
+            vec![(Position::new(1, 0), &'.'), (Position::new(0, 1), &'.')]
+        );
+        assert_eq!(grid.neighbors_8(Position::new(1, 0)).count(), 5);
+        assert_eq!(grid.get(Position::new(-1, 0)), None);
+        assert_eq!(grid.get(Position::new(3, 0)), None);
+    }
+}
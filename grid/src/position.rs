@@ -0,0 +1,165 @@
+use std::fmt::{self, Display};
+
+/// Position on a grid, `y` grows downwards. Coordinates are signed so that stepping off the
+/// edge of a grid gives a position that is simply not contained in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Position {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn move_in_direction(&self, direction: Direction) -> Position {
+        self.move_by(direction, 1)
+    }
+
+    pub fn move_by(&self, direction: Direction, amount: i32) -> Position {
+        let (dx, dy) = direction.offset();
+        Position::new(self.x + dx * amount, self.y + dy * amount)
+    }
+
+    /// The 4 orthogonally adjacent positions, in the order of [`Direction::ALL`].
+    pub fn neighbors(&self) -> [Position; 4] {
+        Direction::ALL.map(|direction| self.move_in_direction(direction))
+    }
+
+    /// The 8 adjacent positions including diagonals, row by row.
+    pub fn neighbors_8(&self) -> [Position; 8] {
+        [
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ]
+        .map(|(dx, dy)| Position::new(self.x + dx, self.y + dy))
+    }
+
+    pub fn manhattan_distance(&self, other: &Position) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({},{})", self.x, self.y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Clockwise, starting with north.
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub fn turn_left(&self) -> Direction {
+        match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        }
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    /// `(dx, dy)` of a single step.
+    pub fn offset(&self) -> (i32, i32) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::East => (1, 0),
+            Direction::South => (0, 1),
+            Direction::West => (-1, 0),
+        }
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, Direction::East | Direction::West)
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        !self.is_horizontal()
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::North => write!(f, "^"),
+            Direction::East => write!(f, ">"),
+            Direction::South => write!(f, "v"),
+            Direction::West => write!(f, "<"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns_work() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_eq!(direction.opposite().opposite(), direction);
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+    }
+
+    #[test]
+    fn neighbors_work() {
+        let position = Position::new(3, 5);
+        assert_eq!(
+            position.neighbors(),
+            [
+                Position::new(3, 4),
+                Position::new(4, 5),
+                Position::new(3, 6),
+                Position::new(2, 5),
+            ]
+        );
+        assert!(position
+            .neighbors_8()
+            .iter()
+            .all(|neighbor| neighbor != &position
+                && neighbor.x.abs_diff(3) <= 1
+                && neighbor.y.abs_diff(5) <= 1));
+        assert_eq!(position.move_by(Direction::West, 4), Position::new(-1, 5));
+        assert_eq!(position.manhattan_distance(&Position::new(0, 0)), 8);
+    }
+}
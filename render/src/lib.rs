@@ -0,0 +1,535 @@
+//! Pictures of the grids and polygons of the solutions, written as SVG or PNG with the answer as
+//! caption and a legend of the colours. The PNG is rasterized here, text included, so neither
+//! needs anything installed to be written.
+
+use std::{
+    fmt::{self, Display, Write as _},
+    io::{self, Write},
+};
+
+use grid::{Grid, Position};
+
+mod font;
+
+/// Pixels per unit, unless that makes the drawing larger than `MAX_PIXELS`.
+const UNIT_PIXELS: f64 = 8.0;
+/// Pixels of the longer side of the drawings that have to be scaled down.
+const MAX_PIXELS: f64 = 2000.0;
+const MIN_WIDTH: u32 = 320;
+const MARGIN: u32 = 8;
+/// Height of a line of the caption or the legend.
+const LINE: u32 = 20;
+const SWATCH: u32 = 14;
+/// The bitmap font is drawn at twice its size.
+const FONT_SCALE: u32 = 2;
+const CHAR_WIDTH: u32 = font::ADVANCE * FONT_SCALE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl Color {
+    pub const WHITE: Color = Color(255, 255, 255);
+    pub const BLACK: Color = Color(0, 0, 0);
+
+    /// The colour `t` of the way from `self` to `other`, with `t` from 0 to 1.
+    pub fn mix(self, other: Color, t: f64) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Color(
+            channel(self.0, other.0),
+            channel(self.1, other.1),
+            channel(self.2, other.2),
+        )
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    Rect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        fill: Color,
+    },
+    Polygon {
+        points: Vec<(f64, f64)>,
+        fill: Color,
+    },
+    /// Closed line through the points.
+    Outline {
+        points: Vec<(f64, f64)>,
+        stroke: Color,
+        width: f64,
+    },
+}
+
+/// A drawing measured in units, like the tiles of a grid, with a caption and a legend below.
+/// A unit is 8 pixels, or less if the drawing would get larger than 2000 pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Picture {
+    width: f64,
+    height: f64,
+    caption: String,
+    shapes: Vec<Shape>,
+    legend: Vec<(Color, String)>,
+}
+
+/// Where the parts of a picture go, in pixels.
+struct Layout {
+    scale: f64,
+    drawing_height: u32,
+    width: u32,
+    height: u32,
+    /// Top of the caption.
+    caption: u32,
+    /// Top left corners of the legend entries.
+    entries: Vec<(u32, u32)>,
+}
+
+impl Picture {
+    pub fn new(width: f64, height: f64, caption: impl Into<String>) -> Self {
+        Self {
+            width,
+            height,
+            caption: caption.into(),
+            shapes: vec![],
+            legend: vec![],
+        }
+    }
+
+    /// Draws the tiles of `grid` as unit squares in the colour returned for them, or not at all
+    /// for `None`. Runs of a colour in a row become a single rectangle.
+    pub fn cells<T>(
+        &mut self,
+        grid: &Grid<T>,
+        mut color: impl FnMut(Position, &T) -> Option<Color>,
+    ) {
+        for (y, row) in grid.rows().enumerate() {
+            let colors = row
+                .iter()
+                .enumerate()
+                .map(|(x, tile)| color(Position::new(x as i32, y as i32), tile))
+                .collect::<Vec<_>>();
+            let mut start = 0;
+            for x in 1..=colors.len() {
+                if x == colors.len() || colors[x] != colors[start] {
+                    if let Some(fill) = colors[start] {
+                        self.rect(start as f64, y as f64, (x - start) as f64, 1.0, fill);
+                    }
+                    start = x;
+                }
+            }
+        }
+    }
+
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, fill: Color) {
+        self.shapes.push(Shape::Rect {
+            x,
+            y,
+            width,
+            height,
+            fill,
+        });
+    }
+
+    /// Fills the polygon with the even-odd rule.
+    pub fn polygon(&mut self, points: Vec<(f64, f64)>, fill: Color) {
+        self.shapes.push(Shape::Polygon { points, fill });
+    }
+
+    /// Draws the border of the polygon, `width` units wide but at least a pixel.
+    pub fn outline(&mut self, points: Vec<(f64, f64)>, stroke: Color, width: f64) {
+        self.shapes.push(Shape::Outline {
+            points,
+            stroke,
+            width,
+        });
+    }
+
+    pub fn legend(&mut self, color: Color, label: impl Into<String>) {
+        self.legend.push((color, label.into()));
+    }
+
+    fn layout(&self) -> Layout {
+        let scale = (MAX_PIXELS / self.width.max(self.height).max(1.0)).min(UNIT_PIXELS);
+        let drawing_width = (self.width * scale).round() as u32;
+        let drawing_height = (self.height * scale).round() as u32;
+        let width = drawing_width.max(MIN_WIDTH) + 2 * MARGIN;
+        let caption = MARGIN + drawing_height + MARGIN;
+        let (mut x, mut y) = (MARGIN, caption + LINE);
+        let mut entries = vec![];
+        for (_, label) in &self.legend {
+            let entry_width = SWATCH + 6 + label.chars().count() as u32 * CHAR_WIDTH + 12;
+            if x > MARGIN && x + entry_width > width - MARGIN {
+                x = MARGIN;
+                y += LINE;
+            }
+            entries.push((x, y));
+            x += entry_width;
+        }
+        let bottom = if entries.is_empty() { y } else { y + LINE };
+        Layout {
+            scale,
+            drawing_height,
+            width,
+            height: bottom + MARGIN,
+            caption,
+            entries,
+        }
+    }
+
+    pub fn to_svg(&self) -> String {
+        let layout = self.layout();
+        let (width, height, scale) = (layout.width, layout.height, layout.scale);
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            width, height
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<rect width="{}" height="{}" fill="{}"/>"#,
+            width,
+            height,
+            Color::WHITE
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<g transform="translate({0} {0}) scale({1})" shape-rendering="crispEdges">"#,
+            MARGIN, scale
+        )
+        .unwrap();
+        for shape in &self.shapes {
+            match shape {
+                Shape::Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                    fill,
+                } => writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                    x, y, width, height, fill
+                ),
+                Shape::Polygon { points, fill } => writeln!(
+                    svg,
+                    r#"<polygon points="{}" fill="{}"/>"#,
+                    svg_points(points),
+                    fill
+                ),
+                Shape::Outline {
+                    points,
+                    stroke,
+                    width,
+                } => writeln!(
+                    svg,
+                    r#"<polygon points="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+                    svg_points(points),
+                    stroke,
+                    width.max(1.0 / scale)
+                ),
+            }
+            .unwrap();
+        }
+        svg.push_str("</g>\n");
+        svg_text(&mut svg, MARGIN, layout.caption, &self.caption);
+        for ((color, label), (x, y)) in self.legend.iter().zip(&layout.entries) {
+            writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{2}" height="{2}" fill="{3}" stroke="{4}"/>"#,
+                x,
+                y + 3,
+                SWATCH,
+                color,
+                Color::BLACK
+            )
+            .unwrap();
+            svg_text(&mut svg, x + SWATCH + 6, *y, label);
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    pub fn write_png(&self, writer: impl Write) -> io::Result<()> {
+        let layout = self.layout();
+        let scale = layout.scale;
+        let mut canvas = Canvas::new(layout.width, layout.height);
+        let margin = MARGIN as f64;
+        for shape in &self.shapes {
+            match shape {
+                Shape::Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                    fill,
+                } => {
+                    let x0 = (x * scale).round();
+                    let y0 = (y * scale).round();
+                    let x1 = ((x + width) * scale).round().max(x0 + 1.0);
+                    let y1 = ((y + height) * scale).round().max(y0 + 1.0);
+                    canvas.fill(x0 + margin, y0 + margin, x1 + margin, y1 + margin, *fill);
+                }
+                Shape::Polygon { points, fill } => {
+                    for row in 0..layout.drawing_height {
+                        let y = (row as f64 + 0.5) / scale;
+                        let mut crossings = edges(points)
+                            .filter(|(a, b)| (a.1 <= y) != (b.1 <= y))
+                            .map(|(a, b)| (a.0 + (y - a.1) * (b.0 - a.0) / (b.1 - a.1)) * scale)
+                            .collect::<Vec<_>>();
+                        crossings.sort_by(f64::total_cmp);
+                        for pair in crossings.chunks_exact(2) {
+                            let x0 = (pair[0] - 0.5).ceil() + margin;
+                            let x1 = (pair[1] - 0.5).ceil() + margin;
+                            let y0 = row as f64 + margin;
+                            canvas.fill(x0, y0, x1, y0 + 1.0, *fill);
+                        }
+                    }
+                }
+                Shape::Outline {
+                    points,
+                    stroke,
+                    width,
+                } => {
+                    let thickness = (width * scale).max(1.0).round();
+                    for (a, b) in edges(points) {
+                        let length = ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt() * scale;
+                        let steps = (length * 2.0).ceil().max(1.0) as u32;
+                        for step in 0..=steps {
+                            let t = step as f64 / steps as f64;
+                            let x = (a.0 + (b.0 - a.0) * t) * scale + margin;
+                            let y = (a.1 + (b.1 - a.1) * t) * scale + margin;
+                            let x0 = (x - thickness / 2.0).round();
+                            let y0 = (y - thickness / 2.0).round();
+                            canvas.fill(x0, y0, x0 + thickness, y0 + thickness, *stroke);
+                        }
+                    }
+                }
+            }
+        }
+        canvas.text(MARGIN, layout.caption, &self.caption);
+        for ((color, label), (x, y)) in self.legend.iter().zip(&layout.entries) {
+            let (x0, y0) = (*x as f64, (y + 3) as f64);
+            let size = SWATCH as f64;
+            canvas.fill(x0, y0, x0 + size, y0 + size, Color::BLACK);
+            canvas.fill(x0 + 1.0, y0 + 1.0, x0 + size - 1.0, y0 + size - 1.0, *color);
+            canvas.text(x + SWATCH + 6, *y, label);
+        }
+
+        let mut encoder = png::Encoder::new(writer, layout.width, layout.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer
+            .write_image_data(&canvas.pixels)
+            .map_err(io::Error::other)?;
+        writer.finish().map_err(io::Error::other)
+    }
+}
+
+/// The edges of the polygon through `points`, including the one closing it.
+fn edges(points: &[(f64, f64)]) -> impl Iterator<Item = ((f64, f64), (f64, f64))> + '_ {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+}
+
+fn svg_points(points: &[(f64, f64)]) -> String {
+    points
+        .iter()
+        .map(|(x, y)| format!("{},{}", x, y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Writes a line of text with its top at `y`.
+fn svg_text(svg: &mut String, x: u32, y: u32, text: &str) {
+    let text = text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    writeln!(
+        svg,
+        r#"<text x="{}" y="{}" font-family="monospace" font-size="16">{}</text>"#,
+        x,
+        y + 15,
+        text
+    )
+    .unwrap();
+}
+
+/// RGB pixels of a PNG being drawn.
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Self {
+        let Color(r, g, b) = Color::WHITE;
+        Self {
+            width,
+            height,
+            pixels: [r, g, b].repeat((width * height) as usize),
+        }
+    }
+
+    /// Fills the pixels from `x0` and `y0` up to `x1` and `y1`, leaving out the ones outside.
+    fn fill(&mut self, x0: f64, y0: f64, x1: f64, y1: f64, color: Color) {
+        let clamp = |value: f64, max: u32| value.clamp(0.0, max as f64) as u32;
+        let (x0, x1) = (clamp(x0, self.width), clamp(x1, self.width));
+        let (y0, y1) = (clamp(y0, self.height), clamp(y1, self.height));
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let i = ((y * self.width + x) * 3) as usize;
+                self.pixels[i..i + 3].copy_from_slice(&[color.0, color.1, color.2]);
+            }
+        }
+    }
+
+    /// Writes a line of text with its top at `y`.
+    fn text(&mut self, x: u32, y: u32, text: &str) {
+        let size = FONT_SCALE as f64;
+        for (i, c) in text.chars().enumerate() {
+            let left = x + i as u32 * CHAR_WIDTH;
+            for (row, bits) in font::glyph(c).iter().enumerate() {
+                for column in 0..5 {
+                    if bits & (0x10 >> column) != 0 {
+                        let x0 = (left + column * FONT_SCALE) as f64;
+                        let y0 = (y + 3 + row as u32 * FONT_SCALE) as f64;
+                        self.fill(x0, y0, x0 + size, y0 + size, Color::BLACK);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    const RED: Color = Color(255, 0, 0);
+    const BLUE: Color = Color(0, 0, 255);
+
+    fn decode(png: &[u8]) -> (u32, u32, Vec<u8>) {
+        let mut reader = png::Decoder::new(Cursor::new(png)).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        (info.width, info.height, pixels)
+    }
+
+    #[test]
+    fn runs_of_cells_are_merged() {
+        let grid = Grid::from_rows(vec![vec![1, 1, 0], vec![0, 2, 2]]).unwrap();
+        let mut picture = Picture::new(3.0, 2.0, "");
+        picture.cells(&grid, |_, tile| match tile {
+            1 => Some(RED),
+            2 => Some(BLUE),
+            _ => None,
+        });
+        assert_eq!(
+            picture.shapes,
+            [
+                Shape::Rect {
+                    x: 0.0,
+                    y: 0.0,
+                    width: 2.0,
+                    height: 1.0,
+                    fill: RED
+                },
+                Shape::Rect {
+                    x: 1.0,
+                    y: 1.0,
+                    width: 2.0,
+                    height: 1.0,
+                    fill: BLUE
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn svg_has_the_shapes_caption_and_legend() {
+        let mut picture = Picture::new(4.0, 2.0, "a < b & c");
+        picture.rect(0.0, 0.0, 2.0, 1.0, RED);
+        picture.outline(vec![(0.5, 0.5), (3.5, 0.5), (3.5, 1.5)], BLUE, 1.0);
+        picture.legend(RED, "red");
+        let svg = picture.to_svg();
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="336" height="80""#)
+        );
+        assert!(svg.contains(r##"<rect x="0" y="0" width="2" height="1" fill="#ff0000"/>"##));
+        assert!(svg.contains(
+            r##"<polygon points="0.5,0.5 3.5,0.5 3.5,1.5" fill="none" stroke="#0000ff" stroke-width="1"/>"##
+        ));
+        assert!(svg.contains(">a &lt; b &amp; c</text>"));
+        assert!(svg.contains(r##"<rect x="8" y="55" width="14" height="14" fill="#ff0000""##));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn png_is_rasterized() {
+        let mut picture = Picture::new(10.0, 10.0, "square");
+        picture.rect(0.0, 0.0, 5.0, 10.0, RED);
+        picture.polygon(vec![(5.0, 0.0), (10.0, 0.0), (10.0, 5.0), (5.0, 5.0)], BLUE);
+        picture.legend(BLUE, "blue");
+        let mut png = vec![];
+        picture.write_png(&mut png).unwrap();
+        let (width, height, pixels) = decode(&png);
+        assert_eq!((width, height), (336, 144));
+        let pixel = |x: u32, y: u32| {
+            let i = ((y * width + x) * 3) as usize;
+            Color(pixels[i], pixels[i + 1], pixels[i + 2])
+        };
+        assert_eq!(pixel(8, 8), RED);
+        assert_eq!(pixel(47, 87), RED);
+        assert_eq!(pixel(48, 8), BLUE);
+        assert_eq!(pixel(87, 47), BLUE);
+        assert_eq!(pixel(48, 48), Color::WHITE);
+        // the swatch of the legend and the first pixel of the "s" of the caption
+        assert_eq!(pixel(12, 127), BLUE);
+        assert_eq!(pixel(10, 103), Color::BLACK);
+    }
+
+    #[test]
+    fn legend_wraps() {
+        let mut picture = Picture::new(1.0, 1.0, "");
+        for label in ["first entry", "second entry", "third"] {
+            picture.legend(RED, label);
+        }
+        let layout = picture.layout();
+        assert_eq!(layout.scale, 8.0);
+        assert_eq!(layout.entries, [(8, 44), (8, 64), (184, 64)]);
+        assert_eq!(layout.height, 92);
+    }
+
+    #[test]
+    fn large_drawings_are_scaled_down() {
+        let layout = Picture::new(20_000_000.0, 1000.0, "").layout();
+        assert_eq!(layout.scale, 0.0001);
+        assert_eq!(layout.width, 2016);
+    }
+
+    #[test]
+    fn colors_are_mixed() {
+        assert_eq!(Color::BLACK.mix(Color::WHITE, 0.5), Color(128, 128, 128));
+        assert_eq!(RED.mix(BLUE, 2.0), BLUE);
+        assert_eq!(Color(1, 171, 255).to_string(), "#01abff");
+    }
+}
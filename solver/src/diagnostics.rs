@@ -0,0 +1,62 @@
+//! Sink for the maps and other visualizations some solutions can print while solving. Nothing
+//! is written until the runner sets a sink, so the solutions stay quiet in tests and benchmarks.
+
+use std::{
+    fmt::Display,
+    io::Write,
+    sync::{Mutex, PoisonError},
+};
+
+static SINK: Mutex<Option<Box<dyn Write + Send>>> = Mutex::new(None);
+
+/// Sends diagnostics to `sink`, or disables them with `None`.
+pub fn set_sink(sink: Option<Box<dyn Write + Send>>) {
+    *SINK.lock().unwrap_or_else(PoisonError::into_inner) = sink;
+}
+
+/// Whether a sink is set, to skip building diagnostics that would be thrown away.
+pub fn enabled() -> bool {
+    SINK.lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .is_some()
+}
+
+/// Writes `diagnostic` followed by a line break to the sink, if there is one.
+pub fn print(diagnostic: impl Display) {
+    if let Some(sink) = SINK.lock().unwrap_or_else(PoisonError::into_inner).as_mut() {
+        // diagnostics are best effort, a broken sink must not fail the solution
+        let _ = writeln!(sink, "{}", diagnostic);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn print_writes_to_sink() {
+        let output = Shared::default();
+        print("dropped");
+        set_sink(Some(Box::new(output.clone())));
+        assert!(enabled());
+        print("#.#");
+        set_sink(None);
+        assert!(!enabled());
+        print("dropped");
+        assert_eq!(*output.0.lock().unwrap(), b"#.#\n");
+    }
+}
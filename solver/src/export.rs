@@ -0,0 +1,43 @@
+use std::{
+    fmt::{self, Display},
+    io,
+};
+
+use crate::ParseError;
+
+/// Error of the functions writing a puzzle input out in another format, like Graphviz.
+#[derive(Debug)]
+pub enum ExportError {
+    Parse(ParseError),
+    Io(io::Error),
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Parse(error) => write!(f, "{}", error),
+            ExportError::Io(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExportError::Parse(error) => Some(error),
+            ExportError::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<ParseError> for ExportError {
+    fn from(error: ParseError) -> Self {
+        ExportError::Parse(error)
+    }
+}
+
+impl From<io::Error> for ExportError {
+    fn from(error: io::Error) -> Self {
+        ExportError::Io(error)
+    }
+}
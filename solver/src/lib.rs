@@ -0,0 +1,196 @@
+use std::{
+    cell::Cell,
+    fmt::{self, Display},
+    time::{Duration, Instant},
+};
+
+pub mod diagnostics;
+pub mod export;
+pub mod params;
+pub mod parse;
+pub mod recorder;
+pub mod stream;
+
+pub use export::ExportError;
+pub use parse::ParseError;
+pub use stream::{StreamError, StreamSolver};
+
+/// Common interface implemented by every day crate, so that the `aoc` runner can dispatch to
+/// any day and part without knowing about the individual `process_part_*` functions.
+pub trait Solver: Sync {
+    fn day(&self) -> u8;
+
+    /// Parses `input` and solves `part` of the puzzle, see [`Run::timed`].
+    fn run(&self, part: Part, input: &str) -> Result<Run, ParseError>;
+
+    /// Checks `(name, value)` overrides of the day's parameters, see [`params`]. Days without
+    /// parameters take none.
+    fn check_params(&self, overrides: &[(String, String)]) -> Result<(), String> {
+        match overrides {
+            [] => Ok(()),
+            _ => Err(format!("Day {} has no parameters", self.day())),
+        }
+    }
+
+    /// Checks the parameters in effect, see [`params::current`], against the `input` of `part`,
+    /// for parameters that only fit some inputs. Runners call it before [`Solver::run`].
+    fn check_input_params(&self, _part: Part, _input: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn solve(&self, part: Part, input: &str) -> Result<String, ParseError> {
+        self.run(part, input).map(|run| run.answer)
+    }
+
+    /// Only parses the input of `part`, returning the time it took. Used to benchmark parsers
+    /// without making every day's input types public.
+    fn parse(&self, part: Part, input: &str) -> Result<Duration, ParseError> {
+        let _guard = ParseOnly::enable();
+        self.run(part, input).map(|run| run.parse_time)
+    }
+}
+
+thread_local! {
+    static PARSE_ONLY: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Makes [`Run::timed`] skip solving until dropped.
+struct ParseOnly;
+
+impl ParseOnly {
+    fn enable() -> Self {
+        PARSE_ONLY.with(|parse_only| parse_only.set(true));
+        ParseOnly
+    }
+}
+
+impl Drop for ParseOnly {
+    fn drop(&mut self) {
+        PARSE_ONLY.with(|parse_only| parse_only.set(false));
+    }
+}
+
+/// Answer of a part together with the time it took to parse the input and to solve the part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub answer: String,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+impl Run {
+    /// Runs `parse` and passes its output to `solve`, timing the two separately. Within
+    /// [`Solver::parse`] the answer is left empty and `solve` is not called.
+    pub fn timed<I>(
+        parse: impl FnOnce() -> Result<I, ParseError>,
+        solve: impl FnOnce(I) -> String,
+    ) -> Result<Run, ParseError> {
+        let start = Instant::now();
+        let input = parse()?;
+        let parse_time = start.elapsed();
+        if PARSE_ONLY.with(Cell::get) {
+            return Ok(Run {
+                answer: String::new(),
+                parse_time,
+                solve_time: Duration::ZERO,
+            });
+        }
+        let start = Instant::now();
+        let answer = solve(input);
+        let solve_time = start.elapsed();
+        Ok(Run {
+            answer,
+            parse_time,
+            solve_time,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl TryFrom<u8> for Part {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => Err(format!("Invalid part: {}", value)),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Echo;
+
+    impl Solver for Echo {
+        fn day(&self) -> u8 {
+            0
+        }
+
+        fn run(&self, part: Part, input: &str) -> Result<Run, ParseError> {
+            Run::timed(
+                || match input {
+                    "" => Err(ParseError::new(1, 1, "anything")),
+                    _ => Ok(input),
+                },
+                |input| format!("{}:{}", part, input),
+            )
+        }
+    }
+
+    #[test]
+    fn solve_returns_the_answer() {
+        assert_eq!(Echo.solve(Part::One, "x"), Ok("1:x".to_string()));
+        assert_eq!(Echo.solve(Part::Two, "x"), Ok("2:x".to_string()));
+        assert_eq!(
+            Echo.solve(Part::One, ""),
+            Err(ParseError::new(1, 1, "anything"))
+        );
+    }
+
+    #[test]
+    fn parse_skips_solving() {
+        struct Unsolvable;
+
+        impl Solver for Unsolvable {
+            fn day(&self) -> u8 {
+                0
+            }
+
+            fn run(&self, _part: Part, input: &str) -> Result<Run, ParseError> {
+                Run::timed(|| Ok(input), |_| unreachable!("parse must not solve"))
+            }
+        }
+
+        assert!(Unsolvable.parse(Part::One, "x").is_ok());
+        assert_eq!(Echo.solve(Part::One, "x"), Ok("1:x".to_string()));
+    }
+
+    #[test]
+    fn part_try_from_works() {
+        assert_eq!(Part::try_from(1), Ok(Part::One));
+        assert_eq!(Part::try_from(2), Ok(Part::Two));
+        assert!(Part::try_from(3).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+//! Parameters of the puzzles that the solutions would otherwise hard-code, like the number of
+//! spin cycles on day 14. A day with parameters has a struct of them whose `Default` holds the
+//! puzzle's values, and the runner overrides them with `--param name=value`. Like the
+//! diagnostics, the overrides are global, so tests pass their parameters explicitly instead.
+
+use std::{
+    fmt::Display,
+    str::FromStr,
+    sync::{Mutex, PoisonError},
+};
+
+/// A struct of parameters that can be set by name.
+pub trait Params: Default {
+    const NAMES: &'static [&'static str];
+
+    /// Sets the parameter `name`, one of [`Params::NAMES`].
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String>;
+}
+
+static OVERRIDES: Mutex<Vec<(String, String)>> = Mutex::new(vec![]);
+
+/// Overrides the defaults of the parameters with `(name, value)` pairs, check them with
+/// [`with_overrides`] first.
+pub fn set_overrides(overrides: Vec<(String, String)>) {
+    *OVERRIDES.lock().unwrap_or_else(PoisonError::into_inner) = overrides;
+}
+
+/// The parameters with the overrides applied. Panics if an override does not fit `P`.
+pub fn current<P: Params>() -> P {
+    let overrides = OVERRIDES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    with_overrides(&overrides).unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// The defaults of `P` with the `(name, value)` pairs applied.
+pub fn with_overrides<P: Params>(overrides: &[(String, String)]) -> Result<P, String> {
+    let mut params = P::default();
+    for (name, value) in overrides {
+        if !P::NAMES.contains(&name.as_str()) {
+            return Err(format!(
+                "Unknown parameter {}, expected one of {}",
+                name,
+                P::NAMES.join(", ")
+            ));
+        }
+        params.set(name, value)?;
+    }
+    Ok(params)
+}
+
+/// Parses the value of the parameter `name`.
+pub fn parse<T>(name: &str, value: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .parse()
+        .map_err(|error| format!("Invalid value {} of {}: {}", value, name, error))
+}
+
+/// Parses a `name=value` argument.
+pub fn parse_override(argument: &str) -> Result<(String, String), String> {
+    match argument.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => Err(format!("Expected name=value, got {}", argument)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Cycles {
+        cycles: u64,
+        spin: bool,
+    }
+
+    impl Default for Cycles {
+        fn default() -> Self {
+            Self {
+                cycles: 1_000_000_000,
+                spin: true,
+            }
+        }
+    }
+
+    impl Params for Cycles {
+        const NAMES: &'static [&'static str] = &["cycles", "spin"];
+
+        fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+            match name {
+                "cycles" => self.cycles = parse(name, value)?,
+                _ => self.spin = parse(name, value)?,
+            }
+            Ok(())
+        }
+    }
+
+    fn overrides(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn overrides_are_applied() {
+        assert_eq!(with_overrides(&[]), Ok(Cycles::default()));
+        assert_eq!(
+            with_overrides(&overrides(&[("cycles", "5000"), ("spin", "false")])),
+            Ok(Cycles {
+                cycles: 5000,
+                spin: false
+            })
+        );
+        assert_eq!(
+            with_overrides::<Cycles>(&overrides(&[("tilt", "1")])),
+            Err("Unknown parameter tilt, expected one of cycles, spin".to_string())
+        );
+        assert_eq!(
+            with_overrides::<Cycles>(&overrides(&[("cycles", "-1")])),
+            Err("Invalid value -1 of cycles: invalid digit found in string".to_string())
+        );
+    }
+
+    #[test]
+    fn overrides_are_parsed() {
+        assert_eq!(
+            parse_override("cycles=5000"),
+            Ok(("cycles".to_string(), "5000".to_string()))
+        );
+        assert!(parse_override("cycles").is_err());
+        assert!(parse_override("=5").is_err());
+    }
+}
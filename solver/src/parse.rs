@@ -0,0 +1,367 @@
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    fmt::{self, Display},
+};
+
+use nom::{
+    error::{ContextError, ErrorKind, FromExternalError},
+    InputLength, Parser,
+};
+
+pub type IResult<I, O> = nom::IResult<I, O, NomError<I>>;
+
+/// Error returned by the puzzle input parsers, pointing at the offending position of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+impl ParseError {
+    /// `line` and `column` are 1-based, `column` counts characters.
+    pub fn new(line: usize, column: usize, expected: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            expected: expected.into(),
+        }
+    }
+
+    /// Creates an error located at the start of `position`, which has to be a subslice of
+    /// `input`.
+    pub fn at(input: &str, position: &str, expected: impl Into<String>) -> Self {
+        let offset = (position.as_ptr() as usize)
+            .saturating_sub(input.as_ptr() as usize)
+            .min(input.len());
+        Self::at_offset(input, offset, expected)
+    }
+
+    /// Creates an error located at the byte `offset` of `input`, an offset inside of a
+    /// character points at that character.
+    pub fn at_offset(input: &str, offset: usize, expected: impl Into<String>) -> Self {
+        let offset = (0..=offset.min(input.len()))
+            .rev()
+            .find(|offset| input.is_char_boundary(*offset))
+            .unwrap_or_default();
+        let before = &input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let column = before[line_start..].trim_end_matches('\r').chars().count() + 1;
+        Self::new(line, column, expected)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected {} on line {}, column {}",
+            self.expected, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+thread_local! {
+    static FURTHEST_FAILURE: RefCell<Option<Failure>> = const { RefCell::new(None) };
+}
+
+/// Runs `parser` on the whole `input`. Trailing line breaks are ignored, anything else left over
+/// is reported as an error.
+///
+/// Combinators like `separated_list1` or `many1` backtrack when an item fails, which would make
+/// a broken line look like an early end of the list. To point at the actual problem, the
+/// failure that got the furthest into the input is reported, unless the parser gave up with
+/// `nom::Err::Failure`.
+pub fn parse_all<'a, O, P>(input: &'a str, mut parser: P) -> Result<O, ParseError>
+where
+    P: Parser<&'a str, O, NomError<&'a str>>,
+{
+    let trimmed = input.trim_end_matches(['\r', '\n']);
+    let outer = FURTHEST_FAILURE.with(|furthest| furthest.replace(None));
+    let result = parser.parse(trimmed);
+    let furthest = FURTHEST_FAILURE.with(|furthest| furthest.replace(outer));
+    let furthest_offset = |failure: &Failure| trimmed.len() - failure.remaining;
+    match result {
+        Ok(("", output)) => Ok(output),
+        Ok((rest, _)) => match furthest {
+            Some(failure) if failure.remaining <= rest.len() => {
+                Err(failure.locate(input, furthest_offset(&failure)))
+            }
+            _ => Err(ParseError::at(input, rest, "end of input")),
+        },
+        Err(nom::Err::Error(error)) => {
+            let failure = furthest.unwrap_or(error.failure);
+            Err(failure.locate(input, furthest_offset(&failure)))
+        }
+        Err(nom::Err::Failure(error)) => {
+            Err(error.failure.locate(input, furthest_offset(&error.failure)))
+        }
+        Err(nom::Err::Incomplete(_)) => {
+            Err(ParseError::at_offset(input, trimmed.len(), "more input"))
+        }
+    }
+}
+
+/// Same as nom's `tag`, but the error names the expected token.
+pub fn tag<'a>(token: &'static str) -> impl Fn(&'a str) -> IResult<&'a str, &'a str> {
+    move |input: &'a str| match input.strip_prefix(token) {
+        Some(rest) => Ok((rest, &input[..token.len()])),
+        None => Err(nom::Err::Error(NomError::new(
+            input,
+            Expected::Token(token),
+        ))),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    Token(&'static str),
+    Description(&'static str),
+    Char(char),
+    Kind(ErrorKind),
+}
+
+impl Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expected::Token(token) => write!(f, "'{}'", token.escape_debug()),
+            Expected::Description(description) => write!(f, "{}", description),
+            Expected::Char(c) => write!(f, "'{}'", c.escape_debug()),
+            Expected::Kind(kind) => match kind {
+                ErrorKind::Digit => write!(f, "a number"),
+                ErrorKind::Alpha => write!(f, "letters"),
+                ErrorKind::AlphaNumeric => write!(f, "letters or digits"),
+                ErrorKind::HexDigit => write!(f, "hexadecimal digits"),
+                ErrorKind::Space | ErrorKind::MultiSpace => write!(f, "a space"),
+                ErrorKind::CrLf => write!(f, "a line break"),
+                ErrorKind::Eof => write!(f, "end of input"),
+                ErrorKind::MapRes | ErrorKind::MapOpt | ErrorKind::Verify => {
+                    write!(f, "a valid value")
+                }
+                _ => write!(f, "{}", kind.description().to_lowercase()),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Failure {
+    remaining: usize,
+    expected: Vec<Expected>,
+    context: Option<&'static str>,
+}
+
+impl Failure {
+    fn merge(&mut self, other: Failure) {
+        other.expected.into_iter().for_each(|expected| {
+            if !self.expected.contains(&expected) {
+                self.expected.push(expected);
+            }
+        });
+        if self.context.is_none() {
+            self.context = other.context;
+        }
+    }
+
+    fn record(&self) {
+        FURTHEST_FAILURE.with(|furthest| {
+            let mut furthest = furthest.borrow_mut();
+            match furthest.as_mut() {
+                Some(failure) if failure.remaining < self.remaining => {}
+                Some(failure) if failure.remaining == self.remaining => failure.merge(self.clone()),
+                _ => *furthest = Some(self.clone()),
+            }
+        });
+    }
+
+    fn locate(&self, input: &str, offset: usize) -> ParseError {
+        let mut expected = self
+            .expected
+            .iter()
+            .map(|expected| expected.to_string())
+            .collect::<Vec<_>>();
+        let last = expected.pop().unwrap_or_default();
+        let mut description = if expected.is_empty() {
+            last
+        } else {
+            format!("{} or {}", expected.join(", "), last)
+        };
+        if let Some(context) = self.context {
+            description = format!("{} {}", description, context);
+        }
+        ParseError::at_offset(input, offset, description)
+    }
+}
+
+/// nom error type used by all parsers, see [`parse_all`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NomError<I> {
+    input: I,
+    failure: Failure,
+}
+
+impl<I: InputLength> NomError<I> {
+    pub fn new(input: I, expected: Expected) -> Self {
+        let failure = Failure {
+            remaining: input.input_len(),
+            expected: vec![expected],
+            context: None,
+        };
+        failure.record();
+        Self { input, failure }
+    }
+}
+
+impl<I: InputLength> nom::error::ParseError<I> for NomError<I> {
+    fn from_error_kind(input: I, kind: ErrorKind) -> Self {
+        Self::new(input, Expected::Kind(kind))
+    }
+
+    fn append(_input: I, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: I, c: char) -> Self {
+        Self::new(input, Expected::Char(c))
+    }
+
+    fn or(mut self, other: Self) -> Self {
+        match self.failure.remaining.cmp(&other.failure.remaining) {
+            Ordering::Less => self,
+            Ordering::Greater => other,
+            Ordering::Equal => {
+                self.failure.merge(other.failure);
+                self
+            }
+        }
+    }
+}
+
+impl<I> ContextError<I> for NomError<I> {
+    fn add_context(_input: I, context: &'static str, mut other: Self) -> Self {
+        if other.failure.context.is_none() {
+            other.failure.context = Some(context);
+            FURTHEST_FAILURE.with(|furthest| {
+                if let Some(failure) = furthest.borrow_mut().as_mut() {
+                    if failure.remaining == other.failure.remaining && failure.context.is_none() {
+                        failure.context = Some(context);
+                    }
+                }
+            });
+        }
+        other
+    }
+}
+
+impl<I: InputLength, E> FromExternalError<I, E> for NomError<I> {
+    fn from_external_error(input: I, kind: ErrorKind, _error: E) -> Self {
+        Self::new(input, Expected::Kind(kind))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::{
+        branch::alt,
+        character::complete::{self, alpha1, line_ending},
+        error::context,
+        multi::separated_list1,
+        sequence::{preceded, separated_pair},
+    };
+
+    fn module(input: &str) -> IResult<&str, (&str, Vec<&str>)> {
+        separated_pair(
+            alt((tag("broadcaster"), preceded(tag("%"), alpha1))),
+            context("after module name", tag(" -> ")),
+            separated_list1(tag(", "), alpha1),
+        )(input)
+    }
+
+    fn modules(input: &str) -> IResult<&str, Vec<(&str, Vec<&str>)>> {
+        separated_list1(line_ending, module)(input)
+    }
+
+    #[test]
+    fn parse_all_works() {
+        let result = parse_all("broadcaster -> a, b\n%a -> b\n", modules);
+        assert_eq!(
+            result,
+            Ok(vec![("broadcaster", vec!["a", "b"]), ("a", vec!["b"])])
+        );
+    }
+
+    #[test]
+    fn malformed_line_is_reported_on_its_line() {
+        let result = parse_all("broadcaster -> a\n%a -> b\n%b -< c", modules);
+        assert_eq!(
+            result,
+            Err(ParseError::new(3, 3, "' -> ' after module name"))
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "expected ' -> ' after module name on line 3, column 3"
+        );
+    }
+
+    #[test]
+    fn alternatives_are_merged() {
+        let result = parse_all("2 red, 2 purple", |input| {
+            separated_list1(
+                tag(", "),
+                separated_pair(
+                    complete::u32,
+                    tag(" "),
+                    alt((tag("red"), tag("green"), tag("blue"))),
+                ),
+            )(input)
+        });
+        assert_eq!(
+            result,
+            Err(ParseError::new(1, 10, "'red', 'green' or 'blue'"))
+        );
+    }
+
+    #[test]
+    fn trailing_input_is_an_error() {
+        let result = parse_all("%a -> b, c d", modules);
+        assert_eq!(result, Err(ParseError::new(1, 11, "', ' or a line break")));
+        let result = parse_all("12 x", complete::u32);
+        assert_eq!(result, Err(ParseError::new(1, 3, "end of input")));
+    }
+
+    #[test]
+    fn failures_do_not_leak_between_parses() {
+        assert!(parse_all("%a -> b, c d", modules).is_err());
+        let result = parse_all("12 x", complete::u32);
+        assert_eq!(result, Err(ParseError::new(1, 3, "end of input")));
+    }
+
+    #[test]
+    fn failure_is_reported_where_it_happened() {
+        let result = parse_all("%a -> b\n%x -> c", |input| {
+            let (rest, modules) = modules(input)?;
+            match modules.iter().find(|(name, _)| *name == "x") {
+                Some((name, _)) => Err(nom::Err::Failure(NomError::new(
+                    &input[name.as_ptr() as usize - input.as_ptr() as usize..],
+                    Expected::Description("a known module"),
+                ))),
+                None => Ok((rest, modules)),
+            }
+        });
+        assert_eq!(result, Err(ParseError::new(2, 2, "a known module")));
+    }
+
+    #[test]
+    fn at_counts_characters() {
+        let input = "ab\r\nűx";
+        let error = ParseError::at(input, &input[6..], "y");
+        assert_eq!(error, ParseError::new(2, 2, "y"));
+        let error = ParseError::at_offset(input, 5, "y");
+        assert_eq!(error, ParseError::new(2, 1, "y"));
+    }
+}
@@ -0,0 +1,119 @@
+//! Step recorder for the solutions that simulate something over time, like rocks rolling or a
+//! beam spreading. They record a frame per step, which `aoc replay` plays back. Like the
+//! diagnostics, nothing is recorded until the runner starts a recording.
+
+use std::{
+    fmt::Display,
+    sync::{Mutex, PoisonError},
+};
+
+/// State of the simulation after a step, drawn as text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// Number of the step, counting every recorded step including the dropped ones.
+    pub step: usize,
+    pub label: String,
+    pub text: String,
+}
+
+struct Recording {
+    frames: Vec<Frame>,
+    /// The latest frame if it was dropped, so that the final state is never lost.
+    last: Option<Frame>,
+    steps: usize,
+    /// Only every `stride`th step is kept.
+    stride: usize,
+    limit: usize,
+}
+
+static RECORDING: Mutex<Option<Recording>> = Mutex::new(None);
+
+/// Starts recording, keeping at most `limit` frames. Once the limit is reached every other
+/// frame is dropped and only every other step is kept from then on, so that long simulations
+/// are sampled evenly.
+pub fn start(limit: usize) {
+    *RECORDING.lock().unwrap_or_else(PoisonError::into_inner) = Some(Recording {
+        frames: vec![],
+        last: None,
+        steps: 0,
+        stride: 1,
+        limit: limit.max(2),
+    });
+}
+
+/// Whether a recording is running, to skip drawing frames that would be thrown away.
+pub fn recording() -> bool {
+    RECORDING
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .is_some()
+}
+
+/// Records a step, if a recording is running.
+pub fn record(label: impl Display, frame: impl Display) {
+    if !recording() {
+        return;
+    }
+    let label = label.to_string();
+    let text = frame.to_string();
+    let mut recording = RECORDING.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(recording) = recording.as_mut() else {
+        return;
+    };
+    let frame = Frame {
+        step: recording.steps,
+        label,
+        text,
+    };
+    recording.steps += 1;
+    if !frame.step.is_multiple_of(recording.stride) {
+        recording.last = Some(frame);
+        return;
+    }
+    recording.last = None;
+    recording.frames.push(frame);
+    if recording.frames.len() > recording.limit {
+        recording.stride *= 2;
+        let stride = recording.stride;
+        recording
+            .frames
+            .retain(|frame| frame.step.is_multiple_of(stride));
+    }
+}
+
+/// Stops recording and returns the recorded frames, ending with the latest step.
+pub fn finish() -> Vec<Frame> {
+    match RECORDING
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take()
+    {
+        Some(mut recording) => {
+            recording.frames.extend(recording.last);
+            recording.frames
+        }
+        None => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_are_sampled_evenly() {
+        record("dropped", "");
+        start(4);
+        assert!(recording());
+        for step in 0..10 {
+            record(format!("step {}", step), step);
+        }
+        let frames = finish();
+        assert!(!recording());
+        let steps = frames.iter().map(|frame| frame.step).collect::<Vec<_>>();
+        assert_eq!(steps, [0, 4, 8, 9]);
+        assert_eq!(frames[1].label, "step 4");
+        assert_eq!(frames[1].text, "4");
+        assert!(finish().is_empty());
+    }
+}
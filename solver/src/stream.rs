@@ -0,0 +1,138 @@
+use std::{
+    fmt::{self, Display},
+    io::{self, BufRead},
+};
+
+use crate::{ParseError, Part, Solver};
+
+/// Implemented by the days whose input is a sequence of independent records, like lines, so
+/// that they can be solved from any reader while only holding one record in memory.
+pub trait StreamSolver: Solver {
+    fn solve_stream(&self, part: Part, reader: &mut dyn BufRead) -> Result<String, StreamError>;
+}
+
+/// Error of solving a part from a reader.
+#[derive(Debug)]
+pub enum StreamError {
+    Parse(ParseError),
+    Io(io::Error),
+}
+
+impl Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Parse(error) => write!(f, "{}", error),
+            StreamError::Io(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StreamError::Parse(error) => Some(error),
+            StreamError::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<ParseError> for StreamError {
+    fn from(error: ParseError) -> Self {
+        StreamError::Parse(error)
+    }
+}
+
+impl From<io::Error> for StreamError {
+    fn from(error: io::Error) -> Self {
+        StreamError::Io(error)
+    }
+}
+
+/// Splits `reader` at `delimiter` and calls `f` with every record, without the delimiter and
+/// without trailing line breaks. Only one record is read into memory at a time.
+///
+/// Like [`crate::parse::parse_all`], empty records at the end of the input are ignored.
+/// Errors returned by `f` are located relative to the record and get moved to the record's
+/// position in the whole input.
+pub fn for_each_record(
+    reader: &mut dyn BufRead,
+    delimiter: u8,
+    mut f: impl FnMut(&str) -> Result<(), ParseError>,
+) -> Result<(), StreamError> {
+    let mut buffer = vec![];
+    let mut position = (1, 1);
+    // empty records are only passed on once a non-empty one follows them
+    let mut empty_records = vec![];
+    loop {
+        buffer.clear();
+        if reader.read_until(delimiter, &mut buffer)? == 0 {
+            return Ok(());
+        }
+        let record = std::str::from_utf8(&buffer)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        let start = position;
+        for c in record.chars() {
+            position = match c {
+                '\n' => (position.0 + 1, 1),
+                _ => (position.0, position.1 + 1),
+            };
+        }
+        let record = record
+            .strip_suffix(delimiter as char)
+            .unwrap_or(record)
+            .trim_end_matches(['\r', '\n']);
+        if record.is_empty() {
+            empty_records.push(start);
+            continue;
+        }
+        for empty_start in empty_records.drain(..) {
+            f("").map_err(|error| relocate(error, empty_start))?;
+        }
+        f(record).map_err(|error| relocate(error, start))?;
+    }
+}
+
+fn relocate(error: ParseError, (line, column): (usize, usize)) -> ParseError {
+    if error.line == 1 {
+        ParseError::new(line, column + error.column - 1, error.expected)
+    } else {
+        ParseError::new(line + error.line - 1, error.column, error.expected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records(input: &str, delimiter: u8) -> Result<Vec<String>, StreamError> {
+        let mut records = vec![];
+        for_each_record(&mut input.as_bytes(), delimiter, |record| {
+            records.push(record.to_string());
+            Ok(())
+        })?;
+        Ok(records)
+    }
+
+    #[test]
+    fn records_are_split() {
+        assert_eq!(
+            records("a\r\nb\n\nc\n\n", b'\n').unwrap(),
+            ["a", "b", "", "c"]
+        );
+        assert_eq!(records("a,b,,c\n", b',').unwrap(), ["a", "b", "", "c"]);
+        assert!(records("", b'\n').unwrap().is_empty());
+    }
+
+    #[test]
+    fn errors_are_relocated() {
+        let no_g = |record: &str| match record.find('g') {
+            Some(column) => Err(ParseError::new(1, column + 1, "no g")),
+            None => Ok(()),
+        };
+        let result = for_each_record(&mut "ab,cd\nef,gh".as_bytes(), b',', no_g);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "expected no g on line 2, column 4"
+        );
+    }
+}